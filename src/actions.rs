@@ -1,13 +1,16 @@
 use crate::{
     app::App,
+    backups,
     config::{EditorStyle, ThemePreset, config_path},
+    date_input::parse_relative_date_input,
     integrations::gemini,
     integrations::google,
-    models::{self, Priority},
+    models::{self, Priority, TaskStatus},
     storage,
 };
 use chrono::{Duration, Local};
 use std::fs;
+use std::path::PathBuf;
 
 pub fn open_tag_popup(app: &mut App) {
     if let Ok(tags) = storage::get_all_tags(&app.config.data.log_path) {
@@ -19,6 +22,54 @@ pub fn open_tag_popup(app: &mut App) {
     }
 }
 
+/// Lists backups of the selected entry's daily file (today's file when nothing is selected).
+pub fn open_backup_popup(app: &mut App) {
+    let target = app
+        .logs_state
+        .selected()
+        .and_then(|i| app.logs.get(i))
+        .map(|entry| PathBuf::from(&entry.file_path))
+        .unwrap_or_else(|| storage::get_file_path_for_date(&app.config.data.log_path, &app.active_date));
+
+    match backups::list_backups(&target) {
+        Ok(list) if list.is_empty() => {
+            let name = target.file_name().unwrap_or_default().to_string_lossy();
+            app.toast(format!("No backups of {name} yet."));
+        }
+        Ok(list) => {
+            app.backups = list;
+            app.backup_list_state.select(Some(0));
+            app.show_backup_popup = true;
+        }
+        Err(_) => app.toast("Failed to read backups."),
+    }
+}
+
+pub fn restore_selected_backup(app: &mut App) {
+    let Some(backup) = app
+        .backup_list_state
+        .selected()
+        .and_then(|i| app.backups.get(i))
+        .cloned()
+    else {
+        return;
+    };
+    app.show_backup_popup = false;
+    app.backups.clear();
+
+    match backups::restore_backup(&backup) {
+        Ok(()) => {
+            app.update_logs();
+            let name = backup.target.file_name().unwrap_or_default().to_string_lossy();
+            app.toast(format!(
+                "Restored {name} from {}.",
+                backup.taken_at.format("%Y-%m-%d %H:%M:%S")
+            ));
+        }
+        Err(_) => app.toast("Failed to restore backup."),
+    }
+}
+
 pub fn toggle_todo_in_timeline(app: &mut App) {
     if let Some(i) = app.logs_state.selected()
         && i < app.logs.len()
@@ -33,7 +84,7 @@ pub fn toggle_todo_in_timeline(app: &mut App) {
                 app.update_logs();
                 app.logs_state.select(Some(i));
             }
-            Err(_) => app.toast("Failed to complete tasks."),
+            Err(err) => app.report_write_error(&err, "Failed to complete tasks."),
         }
     }
 }
@@ -43,20 +94,24 @@ pub fn complete_task_chain(app: &mut App) {
         && i < app.tasks.len()
     {
         let task = app.tasks[i].clone();
-        if task.is_done {
-            app.toast("Task already done.");
+        if task.status.is_closed() {
+            app.toast(format!("Task already {}.", task.status.label()));
             return;
         }
-        if let Ok(completed) = storage::complete_task_chain(&app.config.data.log_path, &task)
-            && task.carryover_from.is_some()
-            && completed > 0
-        {
-            let message = if completed == 1 {
-                "Completed 1 carry-over task".to_string()
-            } else {
-                format!("Completed {} carry-over tasks", completed)
-            };
-            app.toast(message);
+        match storage::complete_task_chain(&app.config.data.log_path, &task) {
+            Ok(completed) if task.carryover_from.is_some() && completed > 0 => {
+                let message = if completed == 1 {
+                    "Completed 1 carry-over task".to_string()
+                } else {
+                    format!("Completed {} carry-over tasks", completed)
+                };
+                app.toast(message);
+            }
+            Ok(_) => {}
+            Err(err) => {
+                app.report_write_error(&err, "Failed to complete task.");
+                return;
+            }
         }
         app.update_logs();
     }
@@ -81,13 +136,251 @@ pub fn cycle_task_priority(app: &mut App) {
         None => "Priority cleared",
     };
 
-    match storage::cycle_task_priority(&task.file_path, task.line_number) {
+    match storage::cycle_task_priority(&task.file_path, task.line_number, &task.task_identity) {
         Ok(true) => {
             app.update_logs();
             app.toast(message);
         }
         Ok(false) => app.toast("Task not found."),
-        Err(_) => app.toast("Failed to update priority."),
+        Err(err) => app.report_write_error(&err, "Failed to update priority."),
+    }
+}
+
+pub fn cycle_task_status(app: &mut App) {
+    let Some(task) = app
+        .tasks_state
+        .selected()
+        .and_then(|i| app.tasks.get(i))
+        .cloned()
+    else {
+        app.toast("No task selected.");
+        return;
+    };
+    set_task_status(
+        app,
+        &task.file_path,
+        task.line_number,
+        &task.task_identity,
+        task.status.cycle(),
+    );
+}
+
+pub fn cycle_agenda_task_status(app: &mut App) {
+    let Some(item) = app
+        .agenda_state
+        .selected()
+        .and_then(|i| app.agenda_items.get(i))
+        .cloned()
+    else {
+        app.toast("No agenda item selected.");
+        return;
+    };
+    if item.kind != models::AgendaItemKind::Task {
+        app.toast("Not a task.");
+        return;
+    }
+    if item.projected {
+        app.toast("Upcoming repeat; change the current occurrence instead.");
+        return;
+    }
+    let identity = storage::text_identity(&item.text);
+    set_task_status(
+        app,
+        &item.file_path,
+        item.line_number,
+        &identity,
+        item.status.cycle(),
+    );
+}
+
+fn set_task_status(
+    app: &mut App,
+    file_path: &str,
+    line_number: usize,
+    identity: &str,
+    status: TaskStatus,
+) {
+    match storage::set_task_status(file_path, line_number, identity, status) {
+        Ok(_) => {
+            app.update_logs();
+            app.toast(format!("Task {}", status.label()));
+        }
+        Err(err) => app.report_write_error(&err, "Failed to update task status."),
+    }
+}
+
+/// Defers the selected task by `offset`, a relative date such as `+1d` or `next mon`, counted
+/// from the task's current day or today, whichever is later.
+pub fn defer_selected_task(app: &mut App, offset: &str) {
+    let Some(target) = selected_defer_target(app) else {
+        return;
+    };
+    let base = target.date.max(Local::now().date_naive());
+    match parse_relative_date_input(offset, base) {
+        Some(date) => defer_task_to(app, &target, date),
+        None => app.toast("Invalid defer offset."),
+    }
+}
+
+pub fn open_defer_popup(app: &mut App) {
+    let Some(target) = selected_defer_target(app) else {
+        return;
+    };
+    app.defer_target = Some(target);
+    app.defer_input.clear();
+    app.show_defer_popup = true;
+}
+
+pub fn defer_task_to(app: &mut App, target: &models::DeferTarget, date: chrono::NaiveDate) {
+    match storage::reschedule_task(
+        &target.file_path,
+        target.line_number,
+        &target.task_identity,
+        date,
+    ) {
+        Ok(true) => {
+            app.update_logs();
+            app.toast(format!("Deferred to {}", date.format("%a %Y-%m-%d")));
+        }
+        Ok(false) => app.toast("Task is already on that day."),
+        Err(err) => app.report_write_error(&err, "Failed to defer task."),
+    }
+}
+
+fn selected_defer_target(app: &mut App) -> Option<models::DeferTarget> {
+    if app.navigate_focus == models::NavigateFocus::Agenda {
+        let Some(item) = app
+            .agenda_state
+            .selected()
+            .and_then(|i| app.agenda_items.get(i))
+            .cloned()
+        else {
+            app.toast("No agenda item selected.");
+            return None;
+        };
+        if item.kind != models::AgendaItemKind::Task {
+            app.toast("Not a task.");
+            return None;
+        }
+        if item.projected {
+            app.toast("Upcoming repeat; defer the current occurrence instead.");
+            return None;
+        }
+        return Some(models::DeferTarget {
+            date: item.schedule.scheduled.or(item.schedule.due).unwrap_or(item.date),
+            task_identity: storage::text_identity(&item.text),
+            text: item.text,
+            file_path: item.file_path,
+            line_number: item.line_number,
+        });
+    }
+
+    let Some(task) = app
+        .tasks_state
+        .selected()
+        .and_then(|i| app.tasks.get(i))
+        .cloned()
+    else {
+        app.toast("No task selected.");
+        return None;
+    };
+    let today = Local::now().date_naive();
+    Some(models::DeferTarget {
+        date: task
+            .schedule
+            .scheduled
+            .or(task.schedule.due)
+            .or(task.date)
+            .unwrap_or(today),
+        text: task.text,
+        file_path: task.file_path,
+        line_number: task.line_number,
+        task_identity: task.task_identity,
+    })
+}
+
+/// Marks or unmarks the selected task for a bulk action and moves to the next row.
+pub fn toggle_task_mark(app: &mut App) {
+    let Some(target) = selected_defer_target(app) else {
+        return;
+    };
+    app.toggle_task_mark(models::TaskRef {
+        file_path: target.file_path,
+        line_number: target.line_number,
+        task_identity: target.task_identity,
+    });
+    if app.navigate_focus == models::NavigateFocus::Agenda {
+        app.agenda_move_selection(1);
+    } else {
+        app.tasks_down();
+    }
+}
+
+/// Opens the bulk popup for the marked tasks, or for the selected task when none are marked.
+pub fn open_bulk_popup(app: &mut App) {
+    let targets = if app.marked_tasks.is_empty() {
+        let Some(target) = selected_defer_target(app) else {
+            return;
+        };
+        vec![models::TaskRef {
+            file_path: target.file_path,
+            line_number: target.line_number,
+            task_identity: target.task_identity,
+        }]
+    } else {
+        app.marked_tasks.clone()
+    };
+    app.bulk_targets = targets;
+    app.bulk_prompt = None;
+    app.bulk_input.clear();
+    app.bulk_list_state.select(Some(0));
+    app.show_bulk_popup = true;
+}
+
+/// Runs `action` on the bulk popup's tasks. `input` is the date or tag typed for
+/// reschedule and add-tag.
+pub fn run_bulk_action(app: &mut App, action: models::BulkAction, input: &str) {
+    let edit = match action {
+        models::BulkAction::Complete => storage::TaskBatchEdit::Complete,
+        models::BulkAction::PriorityHigh => {
+            storage::TaskBatchEdit::SetPriority(Some(Priority::High))
+        }
+        models::BulkAction::PriorityMedium => {
+            storage::TaskBatchEdit::SetPriority(Some(Priority::Medium))
+        }
+        models::BulkAction::PriorityLow => {
+            storage::TaskBatchEdit::SetPriority(Some(Priority::Low))
+        }
+        models::BulkAction::ClearPriority => storage::TaskBatchEdit::SetPriority(None),
+        models::BulkAction::Reschedule => {
+            let Some(date) = parse_relative_date_input(input, Local::now().date_naive()) else {
+                app.toast("Invalid date. Try tomorrow, fri, +3d or 2025-02-01.");
+                return;
+            };
+            storage::TaskBatchEdit::Reschedule(date)
+        }
+        models::BulkAction::AddTag => {
+            let tag = input.trim().trim_start_matches('#');
+            if tag.is_empty() || tag.contains(char::is_whitespace) {
+                app.toast("Tags are a single word.");
+                return;
+            }
+            storage::TaskBatchEdit::AddTag(tag.to_string())
+        }
+        models::BulkAction::Delete => storage::TaskBatchEdit::Delete,
+    };
+
+    app.show_bulk_popup = false;
+    app.bulk_prompt = None;
+    let targets = std::mem::take(&mut app.bulk_targets);
+    match storage::edit_tasks(&targets, &edit) {
+        Ok(changed) => {
+            app.marked_tasks.clear();
+            app.update_logs();
+            let noun = if changed == 1 { "task" } else { "tasks" };
+            app.toast(format!("{}: {changed} {noun}", action.label().trim_end_matches('.')));
+        }
+        Err(err) => app.report_write_error(&err, "Failed to update tasks."),
     }
 }
 
@@ -194,11 +487,15 @@ pub fn toggle_agenda_task(app: &mut App) {
         app.toast("Not a task.");
         return;
     }
+    if item.projected {
+        app.toast("Upcoming repeat; complete the current occurrence first.");
+        return;
+    }
 
-    if storage::toggle_task_status(&item.file_path, item.line_number).is_ok() {
-        app.update_logs();
-    } else {
-        app.toast("Failed to toggle task.");
+    let identity = storage::text_identity(&item.text);
+    match storage::toggle_task_status(&item.file_path, item.line_number, &identity) {
+        Ok(()) => app.update_logs(),
+        Err(err) => app.report_write_error(&err, "Failed to toggle task."),
     }
 }
 
@@ -256,8 +553,8 @@ pub fn open_or_toggle_pomodoro_for_selected_task(app: &mut App) {
     }
 
     let task = app.tasks[i].clone();
-    if task.is_done {
-        app.toast("Cannot start pomodoro on done task.");
+    if task.status.is_closed() {
+        app.toast(format!("Cannot start pomodoro on {} task.", task.status.label()));
         return;
     }
 
@@ -270,10 +567,12 @@ pub fn open_or_toggle_pomodoro_for_selected_task(app: &mut App) {
         && *file_path == task.file_path
         && *line_number == task.line_number
     {
+        app.log_pomodoro_session(Local::now(), true);
         app.pomodoro_end = None;
         app.pomodoro_start = None;
         app.pomodoro_target = None;
         app.toast("Pomodoro stopped.");
+        app.save_pomodoro_state();
         return;
     }
 
@@ -282,6 +581,56 @@ pub fn open_or_toggle_pomodoro_for_selected_task(app: &mut App) {
     app.show_pomodoro_popup = true;
 }
 
+pub fn open_focus_mode(app: &mut App) {
+    if app.pomodoro_end.is_none() {
+        app.toast("No pomodoro running.");
+        return;
+    }
+    app.focus_entry = match app.pomodoro_target.as_ref() {
+        Some(models::PomodoroTarget::Task {
+            file_path,
+            line_number,
+            ..
+        }) => storage::read_entry_containing_line(file_path, *line_number)
+            .ok()
+            .flatten(),
+        None => None,
+    };
+    app.focus_input.clear();
+    app.show_focus_mode = true;
+}
+
+/// Logs the focus-mode capture line as a new `#interrupt` note.
+pub fn log_interruption(app: &mut App) {
+    let text = app.focus_input.trim();
+    if text.is_empty() {
+        return;
+    }
+    let note = format!("{text} #interrupt");
+    match storage::append_entry(&app.config.data.log_path, &note) {
+        Ok(()) => {
+            app.focus_input.clear();
+            app.update_logs();
+            app.toast("Interruption logged.");
+        }
+        Err(err) => app.report_write_error(&err, "Failed to log interruption."),
+    }
+}
+
+/// Starts the break timer for `phase`, which has no task attached.
+pub fn start_pomodoro_break(app: &mut App, phase: models::PomodoroPhase) {
+    let minutes = app.pomodoro_minutes(phase).clamp(1, 600) as i64;
+
+    let now = Local::now();
+    app.pomodoro_start = Some(now);
+    app.pomodoro_end = Some(now + Duration::minutes(minutes));
+    app.pomodoro_target = None;
+    app.pomodoro_phase = phase;
+    app.pomodoro_pending_break = None;
+    app.toast(format!("{} started: {}m", phase.label(), minutes));
+    app.save_pomodoro_state();
+}
+
 pub fn submit_search(app: &mut App) {
     let query = app
         .textarea
@@ -304,9 +653,7 @@ pub fn submit_search(app: &mut App) {
     app.search_highlight_query = Some(trimmed.to_string());
     app.search_highlight_ready_at = Some(Local::now() + Duration::milliseconds(150));
     if let Ok(results) = storage::search_entries(&app.config.data.log_path, trimmed) {
-        app.logs = results;
-        app.is_search_result = true;
-        app.logs_state.select(Some(0));
+        app.set_search_results(results);
     }
 }
 
@@ -1,18 +1,24 @@
+use crate::backups::Backup;
+use crate::block_ids::split_block_id;
 use crate::config::{Config, Theme};
 use crate::integrations::gemini::{AiSearchOutcome, AiSearchResult};
 use crate::integrations::google::{AuthDisplay, AuthPollResult};
+use crate::log_watcher::LogWatcher;
 use crate::models::{
-    DatePickerField, EditorMode, EntryIdentity, FoldOverride, FoldState, InputMode, LogEntry,
-    NavigateFocus, PomodoroTarget, Priority, TaskFilter, TaskItem, TaskSchedule, TimelineFilter,
-    count_trailing_tomatoes, is_heading_timestamp_line, is_timestamped_line, split_timestamp_line,
+    BulkAction, DatePickerField, DeferTarget, EditorMode, EntryIdentity, FoldOverride, FoldState,
+    InputMode, LogEntry, NavigateFocus, PomodoroPhase, PomodoroTarget, Priority, TaskFilter,
+    TaskItem, TaskRef, TaskSchedule, TimelineFilter, count_trailing_tomatoes,
+    is_heading_timestamp_line, is_timestamped_line, split_task_checkbox, split_timestamp_line,
     strip_timestamp_prefix,
 };
+use crate::quick_add::{QuickAdd, parse_quick_add};
+use crate::recurrence::{IntervalUnit, Recurrence};
+use crate::search_rank::{SearchHit, Snippet};
 use crate::storage;
 use arboard::Clipboard;
 use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, Timelike};
 use ratatui::widgets::ListState;
 use std::collections::HashMap;
-use std::path::Path;
 use std::sync::mpsc::Receiver;
 use tui_textarea::CursorMove;
 use tui_textarea::TextArea;
@@ -30,11 +36,20 @@ const HISTORY_LOAD_FILE_COUNT: usize = 2;
 pub struct EditingEntry {
     pub file_path: String,
     pub start_line: usize,
-    pub end_line: usize,
     pub timestamp_prefix: String, // e.g. "## [12:34:56]"
     pub from_search: bool,
     pub search_query: Option<String>,
     pub is_raw: bool,
+    /// File lines the composer was opened from, used to detect edits made elsewhere.
+    pub original_lines: Vec<String>,
+    /// Set when the watcher saw the file change while the composer was open.
+    pub changed_on_disk: bool,
+    /// Set after the first save attempt was refused because of a conflict.
+    pub conflict_warned: bool,
+    /// Block ID of the entry heading, re-attached when the entry is saved.
+    pub block_id: Option<String>,
+    /// Day the entry belongs to; a conflicting save is appended to this day.
+    pub date: Option<NaiveDate>,
 }
 
 #[derive(Clone)]
@@ -65,6 +80,8 @@ pub struct App<'a> {
     pub textarea_viewport_col: u16,
     pub textarea_viewport_height: usize,
     pub composer_dirty: bool,
+    /// Set with the composer's quick-add toggle to save phrases like `tomorrow 3pm` as typed.
+    pub quick_add_literal: bool,
     pub editor_mode: EditorMode,
     pub visual_anchor: Option<(usize, usize)>,
     pub pending_command: Option<PendingEditCommand>,
@@ -90,6 +107,8 @@ pub struct App<'a> {
     pub all_tasks: Vec<TaskItem>,
     pub tasks: Vec<TaskItem>,
     pub tasks_state: ListState,
+    /// Tasks marked for a bulk action, from the Tasks or Agenda panel.
+    pub marked_tasks: Vec<TaskRef>,
     pub task_filter: TaskFilter,
     pub timeline_filter: TimelineFilter,
     pub today_done_tasks: usize,
@@ -110,6 +129,10 @@ pub struct App<'a> {
     pub agenda_selected_day: NaiveDate,
     pub agenda_filter: TaskFilter,
     pub agenda_show_unscheduled: bool,
+    /// Recorded pomodoro sessions, drawn as blocks on the agenda timeline.
+    pub pomodoro_sessions: Vec<storage::PomodoroSession>,
+    /// Time worked per task from `pomodoro_sessions`, for `@est` progress badges.
+    pub time_spent: crate::models::TimeSpent,
     pub show_date_picker_popup: bool,
     pub date_picker_field: DatePickerField,
     pub date_picker_schedule: TaskSchedule,
@@ -119,6 +142,8 @@ pub struct App<'a> {
     pub date_picker_input: String,
     pub date_picker_input_mode: bool,
     pub is_search_result: bool,
+    /// Match snippets for the current search results, parallel to `logs`.
+    pub search_snippets: Vec<Snippet>,
     pub should_quit: bool,
     pub show_exit_popup: bool,
     pub show_delete_entry_popup: bool,
@@ -128,9 +153,24 @@ pub struct App<'a> {
     pub pomodoro_start: Option<DateTime<Local>>,
     pub pomodoro_end: Option<DateTime<Local>>,
     pub pomodoro_target: Option<PomodoroTarget>,
+    /// Phase of the running timer; breaks have no target.
+    pub pomodoro_phase: PomodoroPhase,
+    /// Work sessions finished today, for picking the long break.
+    pub pomodoro_sessions_today: u32,
+    /// Break offered by the completion alert, started with Enter.
+    pub pomodoro_pending_break: Option<PomodoroPhase>,
+    // Full-screen focus view for the running timer
+    pub show_focus_mode: bool,
+    pub focus_input: String,
+    /// Entry containing the pomodoro's task, read when focus mode opens.
+    pub focus_entry: Option<LogEntry>,
     pub show_activity_popup: bool,
-    pub activity_data: HashMap<String, (usize, usize)>, // "YYYY-MM-DD" -> (line_count, tomato_count)
+    // "YYYY-MM-DD" -> (line_count, tomato_count, done_count)
+    pub activity_data: HashMap<String, (usize, usize, usize)>,
     pub show_path_popup: bool,
+    pub show_backup_popup: bool,
+    pub backups: Vec<Backup>,
+    pub backup_list_state: ListState,
     pub show_theme_popup: bool,
     pub theme_list_state: ListState,
     pub theme_preview_backup: Option<Theme>,
@@ -142,6 +182,17 @@ pub struct App<'a> {
     pub pomodoro_minutes_input: String,
     pub pomodoro_pending_task: Option<TaskItem>,
 
+    pub show_defer_popup: bool,
+    pub defer_input: String,
+    pub defer_target: Option<DeferTarget>,
+
+    pub show_bulk_popup: bool,
+    pub bulk_list_state: ListState,
+    pub bulk_targets: Vec<TaskRef>,
+    /// Action waiting for input or confirmation in the bulk popup.
+    pub bulk_prompt: Option<BulkAction>,
+    pub bulk_input: String,
+
     pub show_memo_preview_popup: bool,
     pub memo_preview_entry: Option<LogEntry>,
     pub memo_preview_scroll: usize,
@@ -170,6 +221,9 @@ pub struct App<'a> {
     pub earliest_available_date: Option<NaiveDate>,
     pub is_loading_more: bool,
 
+    /// Detects edits made to the log directory outside MemoLog.
+    pub log_watcher: LogWatcher,
+
     // Entry-level scroll offset for tall entries (row-based scroll within a single entry)
     pub entry_scroll_offset: usize,
     // Flag to indicate we should scroll to the bottom of the selected entry on next render
@@ -185,6 +239,8 @@ pub struct App<'a> {
 impl<'a> App<'a> {
     pub fn new() -> App<'a> {
         let config = Config::load();
+        storage::configure(&config.data);
+        crate::task_metadata::configure_syntax(&config.ui.metadata_syntax);
 
         let now = Local::now();
         let today = now.date_naive();
@@ -257,6 +313,7 @@ impl<'a> App<'a> {
             textarea_viewport_col: 0,
             textarea_viewport_height: 0,
             composer_dirty: false,
+            quick_add_literal: false,
             editor_mode: EditorMode::Normal,
             visual_anchor: None,
             pending_command: None,
@@ -281,6 +338,7 @@ impl<'a> App<'a> {
             all_tasks,
             tasks: Vec::new(),
             tasks_state,
+            marked_tasks: Vec::new(),
             task_filter,
             timeline_filter,
             today_done_tasks,
@@ -296,6 +354,8 @@ impl<'a> App<'a> {
             tags: Vec::new(),
             tag_list_state: ListState::default(),
             agenda_all_items: Vec::new(),
+            pomodoro_sessions: Vec::new(),
+            time_spent: crate::models::TimeSpent::default(),
             agenda_items: Vec::new(),
             agenda_state: ListState::default(),
             agenda_selected_day: today,
@@ -310,6 +370,7 @@ impl<'a> App<'a> {
             date_picker_input: String::new(),
             date_picker_input_mode: false,
             is_search_result: false,
+            search_snippets: Vec::new(),
             should_quit: false,
             show_exit_popup: false,
             show_delete_entry_popup: false,
@@ -317,9 +378,18 @@ impl<'a> App<'a> {
             pomodoro_start: None,
             pomodoro_end: None,
             pomodoro_target: None,
+            pomodoro_phase: PomodoroPhase::Work,
+            pomodoro_sessions_today: 0,
+            pomodoro_pending_break: None,
+            show_focus_mode: false,
+            focus_input: String::new(),
+            focus_entry: None,
             show_activity_popup: false,
             activity_data: HashMap::new(),
             show_path_popup: false,
+            show_backup_popup: false,
+            backups: Vec::new(),
+            backup_list_state: ListState::default(),
             show_theme_popup: false,
             theme_list_state: ListState::default(),
             theme_preview_backup: None,
@@ -328,6 +398,14 @@ impl<'a> App<'a> {
             show_pomodoro_popup: false,
             pomodoro_minutes_input: String::new(),
             pomodoro_pending_task: None,
+            show_defer_popup: false,
+            defer_input: String::new(),
+            defer_target: None,
+            show_bulk_popup: false,
+            bulk_list_state: ListState::default(),
+            bulk_targets: Vec::new(),
+            bulk_prompt: None,
+            bulk_input: String::new(),
             show_memo_preview_popup: false,
             memo_preview_entry: None,
             memo_preview_scroll: 0,
@@ -350,6 +428,7 @@ impl<'a> App<'a> {
             loaded_start_date: Some(effective_start),
             earliest_available_date,
             is_loading_more: false,
+            log_watcher: LogWatcher::new(&config.data.log_path),
             entry_scroll_offset: 0,
             entry_scroll_to_bottom: false,
             selected_entry_line_count: 0,
@@ -364,15 +443,22 @@ impl<'a> App<'a> {
     }
 
     pub fn start_edit_entry(&mut self, entry: &LogEntry) {
-        let mut lines: Vec<String> =
-            storage::read_lines_range(&entry.file_path, entry.line_number, entry.end_line)
-                .unwrap_or_else(|_| entry.content.lines().map(|s| s.to_string()).collect());
-        lines = strip_fold_markers_from_lines(&lines);
+        let original_lines: Vec<String> = storage::read_entry_lines(entry)
+            .unwrap_or_else(|_| entry.content.lines().map(|s| s.to_string()).collect());
+        let mut lines = strip_fold_markers_from_lines(&original_lines);
         if lines.is_empty() {
             return;
         }
 
-        let first_line = lines.remove(0);
+        let mut first_line = lines.remove(0);
+        let block_id = match split_block_id(&first_line) {
+            (heading, Some(id)) if is_timestamped_line(&first_line) => {
+                let id = id.to_string();
+                first_line = heading.to_string();
+                Some(id)
+            }
+            _ => None,
+        };
         let (timestamp_prefix, first_content) = split_timestamp_prefix(&first_line);
         if !first_content.is_empty() {
             lines.insert(0, first_content);
@@ -384,30 +470,38 @@ impl<'a> App<'a> {
         self.editing_entry = Some(EditingEntry {
             file_path: entry.file_path.clone(),
             start_line: entry.line_number,
-            end_line: entry.end_line,
             timestamp_prefix,
             from_search: self.is_search_result,
             search_query: self.last_search_query.clone(),
             is_raw: false,
+            original_lines,
+            changed_on_disk: false,
+            conflict_warned: false,
+            block_id,
+            date: entry.date,
         });
         self.composer_dirty = false;
         self.transition_to(InputMode::Editing);
     }
 
     pub fn start_edit_raw_file(&mut self, file_path: String, mut lines: Vec<String>) {
+        let original_lines = lines.clone();
         if lines.is_empty() {
             lines.push(String::new());
         }
-        let end_line = lines.len().saturating_sub(1);
         self.textarea = TextArea::from(lines);
         self.editing_entry = Some(EditingEntry {
             file_path,
             start_line: 0,
-            end_line,
             timestamp_prefix: String::new(),
             from_search: false,
             search_query: None,
             is_raw: true,
+            original_lines,
+            changed_on_disk: false,
+            conflict_warned: false,
+            block_id: None,
+            date: None,
         });
         self.composer_dirty = false;
         self.transition_to(InputMode::Editing);
@@ -417,6 +511,9 @@ impl<'a> App<'a> {
     pub fn update_logs(&mut self) {
         let today = Local::now().date_naive();
         let preserve_selection = self.logs_state.selected();
+        // Take the watcher baseline before reading, so a file changed mid-reload is still
+        // reported on the next poll instead of being marked as seen.
+        self.log_watcher.sync();
 
         // Reset entry scroll offset when logs are updated
         self.entry_scroll_offset = 0;
@@ -726,10 +823,11 @@ impl<'a> App<'a> {
         if let Err(err) = storage::update_fold_marker(
             &entry.file_path,
             entry.line_number,
+            &entry.content,
             override_state,
         ) {
             eprintln!("Failed to save fold state: {}", err);
-            self.toast("Failed to save fold state.");
+            self.report_write_error(&err, "Failed to save fold state.");
         }
         self.entry_scroll_offset = 0;
     }
@@ -783,6 +881,110 @@ impl<'a> App<'a> {
         count.max(1)
     }
 
+    fn quick_add_active(&self) -> bool {
+        self.config.ui.quick_add
+            && !self.quick_add_literal
+            && !self.editing_entry.as_ref().is_some_and(|editing| editing.is_raw)
+    }
+
+    /// Rewrites quick-add phrases in `line`, unless quick add is off or the line was already in
+    /// the entry when the composer opened (re-saving must not re-date old text).
+    pub fn quick_add_line(&self, line: &str) -> Option<QuickAdd> {
+        if !self.quick_add_active()
+            || self
+                .editing_entry
+                .as_ref()
+                .is_some_and(|editing| editing.original_lines.iter().any(|l| l == line))
+        {
+            return None;
+        }
+        parse_quick_add(line, Local::now().date_naive())
+    }
+
+    /// The quick-add rewrite of the line under the cursor, previewed in the status bar.
+    pub fn quick_add_preview(&self) -> Option<QuickAdd> {
+        let (row, _) = self.textarea.cursor();
+        self.quick_add_line(self.textarea.lines().get(row)?)
+    }
+
+    /// True when the file under the composer no longer holds the lines it was opened from.
+    pub fn editing_conflicts_with_disk(&self) -> bool {
+        let Some(editing) = self.editing_entry.as_ref() else {
+            return false;
+        };
+        if editing.is_raw {
+            let current: Vec<String> = std::fs::read_to_string(&editing.file_path)
+                .unwrap_or_default()
+                .lines()
+                .map(|line| line.to_string())
+                .collect();
+            return current != editing.original_lines;
+        }
+        storage::locate_entry_range(
+            &editing.file_path,
+            editing.start_line,
+            &editing.original_lines.join("\n"),
+        )
+        .is_err()
+    }
+
+    /// Reports a failed file mutation, reloading first when the target changed on disk.
+    pub fn report_write_error(&mut self, err: &std::io::Error, message: &str) {
+        if storage::is_stale_target(err) {
+            self.update_logs();
+            self.toast("Changed on disk since it was loaded. Reloaded; please try again.");
+        } else {
+            self.toast(message);
+        }
+    }
+
+    /// Re-reads logs after files changed outside MemoLog, re-running the active search if any.
+    pub fn reload_from_disk(&mut self) {
+        let search = self
+            .is_search_result
+            .then(|| self.last_search_query.clone())
+            .flatten();
+        let selected = self.logs_state.selected();
+        self.update_logs();
+
+        if let Some(query) = search
+            && let Ok(results) = storage::search_entries(&self.config.data.log_path, &query)
+        {
+            self.set_search_results(results);
+            self.search_highlight_query = Some(query);
+            self.search_highlight_ready_at = Some(Local::now() + Duration::milliseconds(150));
+            self.apply_fold_markers();
+            if let Some(i) = selected
+                && !self.logs.is_empty()
+            {
+                self.logs_state.select(Some(i.min(self.logs.len() - 1)));
+            }
+        }
+
+        if self.show_tag_popup
+            && let Ok(tags) = storage::get_all_tags(&self.config.data.log_path)
+        {
+            self.tags = tags;
+            if self
+                .tag_list_state
+                .selected()
+                .is_none_or(|i| i >= self.tags.len())
+            {
+                self.tag_list_state
+                    .select((!self.tags.is_empty()).then_some(0));
+            }
+        }
+    }
+
+    /// Shows ranked search hits in the timeline and keeps their snippets for the title bar.
+    pub fn set_search_results(&mut self, hits: Vec<SearchHit>) {
+        let (logs, snippets) = hits.into_iter().map(|hit| (hit.entry, hit.snippet)).unzip();
+        self.logs = logs;
+        self.search_snippets = snippets;
+        self.is_search_result = true;
+        self.logs_state.select(Some(0));
+    }
+
     pub(crate) fn apply_fold_markers(&mut self) {
         let overrides = extract_fold_markers_from_logs(&mut self.all_logs);
         for (key, value) in overrides {
@@ -875,22 +1077,24 @@ impl<'a> App<'a> {
             return;
         };
 
-        let mut lines =
-            storage::read_lines_range(&entry.file_path, entry.line_number, entry.end_line)
-                .unwrap_or_else(|_| entry.content.lines().map(|s| s.to_string()).collect());
+        let entry = entry.clone();
+        let mut lines = match storage::read_entry_lines(&entry) {
+            Ok(lines) => lines,
+            Err(err) => {
+                self.report_write_error(&err, "Failed to update context tag.");
+                return;
+            }
+        };
         let changed = apply_context_tag_to_lines(&mut lines, context);
         if !changed {
             return;
         }
 
-        if let Err(err) = storage::replace_entry_lines(
-            &entry.file_path,
-            entry.line_number,
-            entry.end_line,
-            &lines,
-        ) {
+        if let Err(err) =
+            storage::replace_entry_lines(&entry.file_path, entry.line_number, &entry.content, &lines)
+        {
             eprintln!("Failed to update context tag: {}", err);
-            self.toast("Failed to update context tag.");
+            self.report_write_error(&err, "Failed to update context tag.");
             return;
         }
 
@@ -931,19 +1135,31 @@ impl<'a> App<'a> {
             TaskFilter::Open => self
                 .all_tasks
                 .iter()
-                .filter(|task| !task.is_done)
+                .filter(|task| !task.status.is_closed())
                 .cloned()
                 .collect(),
             TaskFilter::Done => self
                 .all_tasks
                 .iter()
-                .filter(|task| task.is_done)
+                .filter(|task| task.status.is_closed())
                 .cloned()
                 .collect(),
             TaskFilter::All => self.all_tasks.clone(),
         };
 
-        self.tasks.sort_by_key(|task| (task_priority_rank(task.priority), task.line_number));
+        if self.task_filter == TaskFilter::Done {
+            // Most recently completed first; tasks without a `@done` stamp go last.
+            self.tasks.sort_by_key(|task| {
+                (
+                    std::cmp::Reverse((task.schedule.done, task.schedule.done_time)),
+                    task_priority_rank(task.priority),
+                    task.line_number,
+                )
+            });
+        } else {
+            self.tasks
+                .sort_by_key(|task| (task_priority_rank(task.priority), task.line_number));
+        }
 
         if self.tasks.is_empty() {
             self.tasks_state.select(None);
@@ -982,8 +1198,8 @@ impl<'a> App<'a> {
             .filter(|item| match item.kind {
                 crate::models::AgendaItemKind::Note => true,
                 crate::models::AgendaItemKind::Task => match filter {
-                    TaskFilter::Open => !item.is_done,
-                    TaskFilter::Done => item.is_done,
+                    TaskFilter::Open => !item.status.is_closed(),
+                    TaskFilter::Done => item.status.is_closed(),
                     TaskFilter::All => true,
                 },
             })
@@ -1055,6 +1271,10 @@ impl<'a> App<'a> {
             storage::read_agenda_entries(&self.config.data.log_path, start, end)
                 .unwrap_or_default();
         self.agenda_all_items = items;
+        self.pomodoro_sessions =
+            storage::read_pomodoro_sessions(&self.config.data.log_path).unwrap_or_default();
+        self.time_spent =
+            storage::time_spent(&self.pomodoro_sessions, self.config.pomodoro.work_minutes);
         self.apply_agenda_filter(true);
         self.set_agenda_selected_day(self.agenda_selected_day);
     }
@@ -1111,7 +1331,9 @@ impl<'a> App<'a> {
                 .date_picker_schedule
                 .start
                 .unwrap_or(self.date_picker_default_date),
-            DatePickerField::Time | DatePickerField::Duration => self.date_picker_default_date,
+            DatePickerField::Time | DatePickerField::Duration | DatePickerField::Recurrence => {
+                self.date_picker_default_date
+            }
         }
     }
 
@@ -1132,7 +1354,7 @@ impl<'a> App<'a> {
             DatePickerField::Scheduled => self.date_picker_schedule.scheduled = Some(date),
             DatePickerField::Due => self.date_picker_schedule.due = Some(date),
             DatePickerField::Start => self.date_picker_schedule.start = Some(date),
-            DatePickerField::Time | DatePickerField::Duration => {}
+            DatePickerField::Time | DatePickerField::Duration | DatePickerField::Recurrence => {}
         }
     }
 
@@ -1144,11 +1366,118 @@ impl<'a> App<'a> {
         self.date_picker_schedule.duration_minutes = Some(minutes);
     }
 
+    /// The line's `@every` rule, or weekly when it has none yet.
+    pub fn date_picker_effective_recurrence(&self) -> Recurrence {
+        self.date_picker_schedule
+            .recurrence
+            .clone()
+            .unwrap_or(Recurrence::Interval {
+                count: 1,
+                unit: IntervalUnit::Week,
+            })
+    }
+
+    pub fn set_date_picker_recurrence(&mut self, recurrence: Recurrence) {
+        self.date_picker_schedule.recurrence = Some(recurrence);
+    }
+
+    /// Saves the running timer and today's session count to `state.toml`, so a restart can
+    /// resume the timer.
+    pub fn save_pomodoro_state(&mut self) {
+        let timer = match (self.pomodoro_start, self.pomodoro_end) {
+            (Some(start), Some(end)) => Some(storage::PomodoroTimer {
+                start,
+                end,
+                phase: self.pomodoro_phase,
+                target: self.pomodoro_target.clone(),
+            }),
+            _ => None,
+        };
+        let state = storage::PomodoroState {
+            sessions_date: Some(self.active_date.clone()),
+            sessions: self.pomodoro_sessions_today,
+            timer,
+        };
+        if let Err(err) = storage::save_pomodoro_state(&self.config.data.log_path, &state) {
+            self.report_write_error(&err, "Failed to save pomodoro state.");
+        }
+    }
+
+    /// Records the running work session as ending at `ended_at`. Breaks are not recorded.
+    pub fn log_pomodoro_session(&mut self, ended_at: DateTime<Local>, interrupted: bool) {
+        let (Some(start), Some(end), Some(PomodoroTarget::Task {
+            text,
+            file_path,
+            task_identity,
+            block_id,
+            ..
+        })) = (
+            self.pomodoro_start,
+            self.pomodoro_end,
+            self.pomodoro_target.as_ref(),
+        )
+        else {
+            return;
+        };
+        if self.pomodoro_phase.is_break() {
+            return;
+        }
+
+        let minutes = |to: DateTime<Local>| (to - start).num_minutes().max(0) as u32;
+        let session = storage::PomodoroSession {
+            start,
+            end: ended_at,
+            task: text.clone(),
+            task_identity: task_identity.clone(),
+            file_path: file_path.clone(),
+            block_id: block_id.clone(),
+            planned_minutes: minutes(end),
+            actual_minutes: minutes(ended_at),
+            interrupted,
+        };
+        match storage::append_pomodoro_session(&self.config.data.log_path, &session) {
+            Ok(()) => {
+                self.pomodoro_sessions.push(session);
+                self.time_spent =
+                    storage::time_spent(&self.pomodoro_sessions, self.config.pomodoro.work_minutes);
+            }
+            Err(err) => self.report_write_error(&err, "Failed to record pomodoro session."),
+        }
+    }
+
+    /// Configured length of a `phase` timer in minutes.
+    pub fn pomodoro_minutes(&self, phase: PomodoroPhase) -> u64 {
+        let pomodoro = &self.config.pomodoro;
+        match phase {
+            PomodoroPhase::Work => pomodoro.work_minutes,
+            PomodoroPhase::ShortBreak => pomodoro.short_break_minutes,
+            PomodoroPhase::LongBreak => pomodoro.long_break_minutes,
+        }
+    }
+
+    pub fn is_task_marked(&self, file_path: &str, line_number: usize) -> bool {
+        self.marked_tasks
+            .iter()
+            .any(|t| t.file_path == file_path && t.line_number == line_number)
+    }
+
+    /// Marks `task`, or unmarks it if it was already marked. Returns whether it is now marked.
+    pub fn toggle_task_mark(&mut self, task: TaskRef) -> bool {
+        let before = self.marked_tasks.len();
+        self.marked_tasks
+            .retain(|t| !(t.file_path == task.file_path && t.line_number == task.line_number));
+        if self.marked_tasks.len() < before {
+            return false;
+        }
+        self.marked_tasks.push(task);
+        true
+    }
+
     pub fn task_counts(&self) -> (usize, usize) {
         let mut open = 0usize;
         let mut done = 0usize;
         for task in &self.all_tasks {
-            if task.is_done {
+            if task.status.is_closed() {
                 done += 1;
             } else {
                 open += 1;
@@ -1193,6 +1522,7 @@ impl<'a> App<'a> {
             InputMode::Editing => {
                 self.textarea.set_placeholder_text(PLACEHOLDER_COMPOSE);
                 self.set_navigate_focus(NavigateFocus::Timeline);
+                self.quick_add_literal = false;
                 self.textarea_viewport_row = 0;
                 self.textarea_viewport_col = 0;
                 self.textarea_viewport_height = 0;
@@ -1349,7 +1679,9 @@ fn copy_to_clipboard(text: &str) {
 fn split_timestamp_prefix(line: &str) -> (String, String) {
     if let Some((prefix, rest)) = split_timestamp_line(line) {
         if is_heading_timestamp_line(line) {
-            (line.trim_end().to_string(), rest.to_string())
+            // Keep the heading as written (without trailing text) so saving preserves its style.
+            let heading = &line[..line.len() - rest.len()];
+            (heading.trim_end().to_string(), rest.to_string())
         } else {
             (prefix.trim_end().to_string(), rest.to_string())
         }
@@ -1374,20 +1706,11 @@ fn compute_today_task_stats(logs: &[LogEntry]) -> (usize, usize) {
                 continue;
             }
 
-            if let Some(text) = s.strip_prefix("- [ ] ") {
-                // Carryover tasks have ⟦date⟧ marker - exclude their pre-existing tomatoes
-                if !is_carryover_task(text) {
-                    tomatoes += count_trailing_tomatoes(text);
+            if let Some((status, text)) = split_task_checkbox(s) {
+                if status.is_done() {
+                    done += 1;
                 }
-                continue;
-            }
-
-            if let Some(text) = s
-                .strip_prefix("- [x] ")
-                .or_else(|| s.strip_prefix("- [X] "))
-            {
-                done += 1;
-                // Only count tomatoes if not a carryover task
+                // Carryover tasks have ⟦date⟧ marker - exclude their pre-existing tomatoes
                 if !is_carryover_task(text) {
                     tomatoes += count_trailing_tomatoes(text);
                 }
@@ -1526,7 +1849,7 @@ pub(crate) fn strip_context_tags_from_line(line: &str) -> (String, bool) {
     (out, changed)
 }
 
-fn apply_context_tag_to_lines(lines: &mut Vec<String>, context: TimelineFilter) -> bool {
+pub(crate) fn apply_context_tag_to_lines(lines: &mut Vec<String>, context: TimelineFilter) -> bool {
     let mut changed = false;
     for line in lines.iter_mut() {
         let (updated, did_change) = strip_context_tags_from_line(line);
@@ -1571,10 +1894,14 @@ fn apply_context_tag_to_lines(lines: &mut Vec<String>, context: TimelineFilter)
     if target.trim().is_empty() {
         *target = tag.to_string();
     } else {
-        if !target.ends_with(' ') {
-            target.push(' ');
+        // Obsidian block IDs have to stay at the very end of the line.
+        let (text, block_id) = split_block_id(target);
+        let mut updated = text.to_string();
+        if !updated.ends_with(' ') {
+            updated.push(' ');
         }
-        target.push_str(tag);
+        updated.push_str(tag);
+        *target = crate::block_ids::with_block_id(&updated, block_id);
     }
 
     true
@@ -1663,11 +1990,10 @@ fn strip_fold_markers_from_lines(lines: &[String]) -> Vec<String> {
 }
 
 fn count_distinct_entry_dates(entries: &[LogEntry]) -> usize {
-    let mut last: Option<String> = None;
+    let mut last: Option<NaiveDate> = None;
     let mut count = 0usize;
     for entry in entries {
-        let date = file_date(&entry.file_path);
-        let Some(date) = date else {
+        let Some(date) = entry.date else {
             continue;
         };
         if last.as_ref() != Some(&date) {
@@ -1678,13 +2004,6 @@ fn count_distinct_entry_dates(entries: &[LogEntry]) -> usize {
     count
 }
 
-fn file_date(file_path: &str) -> Option<String> {
-    Path::new(file_path)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_string())
-}
-
 fn round_time_to_quarter(time: NaiveTime) -> NaiveTime {
     let total_minutes = time.hour() as i32 * 60 + time.minute() as i32;
     let rounded = ((total_minutes + 14) / 15) * 15;
@@ -1694,7 +2013,7 @@ fn round_time_to_quarter(time: NaiveTime) -> NaiveTime {
         .unwrap_or(NaiveTime::from_hms_opt(0, 0, 0).expect("Valid time constant"))
 }
 
-fn task_priority_rank(priority: Option<Priority>) -> u8 {
+pub(crate) fn task_priority_rank(priority: Option<Priority>) -> u8 {
     match priority {
         Some(Priority::High) => 0,
         Some(Priority::Medium) => 1,
@@ -1703,14 +2022,14 @@ fn task_priority_rank(priority: Option<Priority>) -> u8 {
     }
 }
 
-fn agenda_sort_key(
+pub(crate) fn agenda_sort_key(
     item: &crate::models::AgendaItem,
     today: NaiveDate,
 ) -> (NaiveDate, u8, u8, NaiveTime, usize) {
     let is_overdue = item.kind == crate::models::AgendaItemKind::Task
         && item.schedule.due.is_some()
         && item.schedule.due.unwrap_or(today) < today
-        && !item.is_done;
+        && !item.status.is_closed();
     let overdue_rank = if is_overdue { 0 } else { 1 };
     let kind_rank = match item.kind {
         crate::models::AgendaItemKind::Task => 0,
@@ -1727,7 +2046,9 @@ fn agenda_sort_key(
 
 fn agenda_timeline_indices(app: &App) -> Vec<usize> {
     let day = app.agenda_selected_day;
+    let warning_days = app.config.ui.deadline_warning_days;
     let mut overdue = Vec::new();
+    let mut upcoming = Vec::new();
     let mut all_day = Vec::new();
     let mut timed = Vec::new();
     let mut unscheduled = Vec::new();
@@ -1735,13 +2056,14 @@ fn agenda_timeline_indices(app: &App) -> Vec<usize> {
     for (idx, item) in app.agenda_items.iter().enumerate() {
         match item.kind {
             crate::models::AgendaItemKind::Task => {
-                let is_overdue = item.schedule.due.is_some()
-                    && item.schedule.due.unwrap_or(day) < day
-                    && !item.is_done;
-                if is_overdue {
+                if item.is_overdue_on(day) {
                     overdue.push(idx);
                     continue;
                 }
+                if item.date != day && item.is_deadline_upcoming(day, warning_days) {
+                    upcoming.push(idx);
+                    continue;
+                }
                 if item.schedule.is_empty() {
                     if app.agenda_show_unscheduled {
                         unscheduled.push(idx);
@@ -1784,6 +2106,14 @@ fn agenda_timeline_indices(app: &App) -> Vec<usize> {
             item.line_number,
         )
     });
+    upcoming.sort_by_key(|idx| {
+        let item = &app.agenda_items[*idx];
+        (
+            item.schedule.due.unwrap_or(day),
+            task_priority_rank(item.priority),
+            item.line_number,
+        )
+    });
     all_day.sort_by_key(|idx| {
         let item = &app.agenda_items[*idx];
         (task_priority_rank(item.priority), item.line_number)
@@ -1809,6 +2139,7 @@ fn agenda_timeline_indices(app: &App) -> Vec<usize> {
 
     let mut visible = Vec::new();
     visible.extend(overdue);
+    visible.extend(upcoming);
     visible.extend(all_day);
     visible.extend(timed);
     visible.extend(unscheduled);
@@ -1828,12 +2159,14 @@ mod tests {
                 file_path: "/test/2025-12-22.md".to_string(),
                 line_number: 1,
                 end_line: 1,
+                date: NaiveDate::from_ymd_opt(2025, 12, 22),
             },
             LogEntry {
                 content: "Another short entry".to_string(),
                 file_path: "/test/2025-12-22.md".to_string(),
                 line_number: 2,
                 end_line: 2,
+                date: NaiveDate::from_ymd_opt(2025, 12, 22),
             },
         ];
         app.all_logs = app.logs.clone();
@@ -1943,4 +2276,101 @@ mod tests {
         assert_eq!(app.logs_state.selected(), Some(1));
         assert_eq!(app.entry_scroll_offset, 0);
     }
+
+    #[test]
+    fn log_pomodoro_session_records_interrupted_work() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "memolog-pomodoro-test-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let mut app = App::new();
+        app.config.data.log_path = dir.clone();
+        app.pomodoro_sessions.clear();
+        let start = Local::now() - Duration::minutes(12);
+        app.pomodoro_start = Some(start);
+        app.pomodoro_end = Some(start + Duration::minutes(25));
+        app.pomodoro_phase = PomodoroPhase::Work;
+        app.pomodoro_target = Some(PomodoroTarget::Task {
+            text: "Write report".to_string(),
+            file_path: "/logs/2025-03-12.md".to_string(),
+            line_number: 3,
+            task_identity: "write report".to_string(),
+            block_id: None,
+        });
+
+        app.log_pomodoro_session(start + Duration::minutes(12), true);
+        let recorded = storage::read_pomodoro_sessions(&dir).expect("read sessions");
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].task, "Write report");
+        assert_eq!(recorded[0].planned_minutes, 25);
+        assert_eq!(recorded[0].actual_minutes, 12);
+        assert!(recorded[0].interrupted);
+        assert_eq!(app.pomodoro_sessions.len(), 1);
+
+        // Breaks are not recorded.
+        app.pomodoro_phase = PomodoroPhase::ShortBreak;
+        app.log_pomodoro_session(Local::now(), false);
+        assert_eq!(storage::read_pomodoro_sessions(&dir).expect("read").len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn editing_conflict_detects_external_edits() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("memolog-conflict-test-{}.md", std::process::id()));
+        std::fs::write(&path, "## [09:00:00]\nOriginal\n\n## [10:00:00]\nOther\n")
+            .expect("write log");
+        let entry = LogEntry {
+            content: "## [09:00:00]\nOriginal".to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            line_number: 0,
+            end_line: 1,
+            date: None,
+        };
+
+        let mut app = App::new();
+        app.start_edit_entry(&entry);
+        assert!(!app.editing_conflicts_with_disk());
+
+        std::fs::write(&path, "## [09:00:00]\nOriginal\n\n## [10:00:00]\nOther edited\n")
+            .expect("append elsewhere");
+        assert!(!app.editing_conflicts_with_disk());
+
+        std::fs::write(&path, "## [08:00:00]\nInserted above\n## [09:00:00]\nOriginal\n")
+            .expect("shift lines");
+        assert!(!app.editing_conflicts_with_disk());
+
+        std::fs::write(&path, "## [09:00:00]\nOriginal, edited elsewhere\n")
+            .expect("edit entry");
+        assert!(app.editing_conflicts_with_disk());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn block_id_keeps_entry_identity_and_stays_out_of_composer() {
+        let entry_at = |content: &str, line_number: usize| LogEntry {
+            content: content.to_string(),
+            file_path: "/test/missing/2025-12-22.md".to_string(),
+            line_number,
+            end_line: line_number + 1,
+            date: NaiveDate::from_ymd_opt(2025, 12, 22),
+        };
+        let with_id = "## [09:00:00] ^abc123\nBody";
+        assert!(
+            EntryIdentity::from(&entry_at(with_id, 0)) == EntryIdentity::from(&entry_at(with_id, 7))
+        );
+        let plain = "## [09:00:00]\nBody";
+        assert!(
+            EntryIdentity::from(&entry_at(plain, 0)) != EntryIdentity::from(&entry_at(plain, 7))
+        );
+
+        let mut app = App::new();
+        app.start_edit_entry(&entry_at(with_id, 0));
+        assert_eq!(app.textarea.lines(), ["Body"]);
+        let editing = app.editing_entry.as_ref().expect("editing");
+        assert_eq!(editing.block_id.as_deref(), Some("abc123"));
+    }
 }
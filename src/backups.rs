@@ -0,0 +1,238 @@
+//! Crash-safe writes for log files and the rolling backups kept for each of them.
+//!
+//! Every mutation of a log file goes through `write_log_file`: the current version is copied
+//! to `<log_path>/.memolog/backups/<file path without .md>/`, then the new content is written to
+//! a temp file, fsynced and renamed over the original. A crash or a full disk leaves either the
+//! old or the new file in place, never a truncated one.
+
+use chrono::{Local, NaiveDateTime};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use crate::storage;
+
+pub const DEFAULT_BACKUP_COUNT: usize = 10;
+const BACKUPS_DIR_NAME: &str = "backups";
+const BACKUP_STAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%9f";
+/// Length of a formatted stamp; names may carry a `_NNN` suffix after it.
+const BACKUP_STAMP_LEN: usize = 25;
+
+static BACKUP_LIMIT: AtomicUsize = AtomicUsize::new(DEFAULT_BACKUP_COUNT);
+static LOG_ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+/// Distinguishes temp files of concurrent writes to the same path within this process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Debug)]
+pub struct Backup {
+    /// Backup file under `.memolog/backups/`.
+    pub path: PathBuf,
+    /// Log file the backup was taken from.
+    pub target: PathBuf,
+    pub taken_at: NaiveDateTime,
+    pub line_count: usize,
+}
+
+/// Sets where backups are stored and how many versions of each file are kept (0 disables them).
+pub fn configure(log_path: &Path, limit: usize) {
+    BACKUP_LIMIT.store(limit, Ordering::Relaxed);
+    let mut root = LOG_ROOT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *root = Some(log_path.to_path_buf());
+}
+
+/// Backs up the current version of `path`, then replaces it atomically with `content`.
+/// Writing identical content is a no-op.
+pub fn write_log_file(path: &Path, content: &str) -> io::Result<()> {
+    if let Ok(existing) = fs::read_to_string(path) {
+        if existing == content {
+            return Ok(());
+        }
+        let limit = BACKUP_LIMIT.load(Ordering::Relaxed);
+        if limit > 0 {
+            backup_file(path, &existing, limit)?;
+        }
+    }
+    write_atomic(path, content)
+}
+
+/// Writes `content` to a temp file next to `path`, fsyncs it and renames it into place.
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = parent.join(format!(
+        ".{file_name}.{}.{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // Persist the rename itself. Not every platform can open directories; the data is
+    // already durable in that case, so a failure here is not an error.
+    if let Ok(dir) = fs::File::open(parent) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// Lists the backups of `target`, newest first.
+pub fn list_backups(target: &Path) -> io::Result<Vec<Backup>> {
+    let dir = backup_dir_for(target);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let path = entry.path();
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(stamp) = stem.get(..BACKUP_STAMP_LEN) else {
+            continue;
+        };
+        let Ok(taken_at) = NaiveDateTime::parse_from_str(stamp, BACKUP_STAMP_FORMAT) else {
+            continue;
+        };
+        let line_count = fs::read_to_string(&path)
+            .map(|content| content.lines().count())
+            .unwrap_or(0);
+        backups.push(Backup {
+            path,
+            target: target.to_path_buf(),
+            taken_at,
+            line_count,
+        });
+    }
+    // Names sort in time order, including same-instant suffixes.
+    backups.sort_by(|a, b| b.path.cmp(&a.path));
+    Ok(backups)
+}
+
+/// Restores `backup` over its log file. The version being replaced is backed up first,
+/// so a restore can itself be undone.
+pub fn restore_backup(backup: &Backup) -> io::Result<()> {
+    let content = fs::read_to_string(&backup.path)?;
+    write_log_file(&backup.target, &content)
+}
+
+fn backup_file(path: &Path, content: &str, limit: usize) -> io::Result<()> {
+    let dir = backup_dir_for(path);
+    fs::create_dir_all(&dir)?;
+
+    let stamp = Local::now().format(BACKUP_STAMP_FORMAT).to_string();
+    let mut backup_path = dir.join(format!("{stamp}.md"));
+    let mut suffix = 1;
+    while backup_path.exists() {
+        backup_path = dir.join(format!("{stamp}_{suffix:03}.md"));
+        suffix += 1;
+    }
+    write_atomic(&backup_path, content)?;
+    prune_backups(&dir, limit)
+}
+
+fn prune_backups(dir: &Path, limit: usize) -> io::Result<()> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("md"))
+        .collect();
+    if paths.len() <= limit {
+        return Ok(());
+    }
+    // Stamps sort lexicographically in time order.
+    paths.sort();
+    for path in &paths[..paths.len() - limit] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn backup_dir_for(path: &Path) -> PathBuf {
+    let root = LOG_ROOT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .filter(|root| path.starts_with(root))
+        .or_else(|| path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    // Nested layouts keep their folders, e.g. `backups/2025/01/2025-01-06/`.
+    let relative = path
+        .strip_prefix(&root)
+        .unwrap_or(path)
+        .with_extension("");
+    storage::state_dir_path(&root)
+        .join(BACKUPS_DIR_NAME)
+        .join(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_keep_rolling_backups_and_restore() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "memolog-backup-test-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let day = dir.join("2025-01-01.md");
+
+        write_log_file(&day, "v0\n").expect("create");
+        assert!(list_backups(&day).expect("list").is_empty());
+        for version in 1..=DEFAULT_BACKUP_COUNT + 2 {
+            write_log_file(&day, &format!("v{version}\n")).expect("write");
+        }
+        write_log_file(&day, "unchanged\n").expect("write");
+        write_log_file(&day, "unchanged\n").expect("write same");
+
+        let backups = list_backups(&day).expect("list");
+        assert_eq!(backups.len(), DEFAULT_BACKUP_COUNT);
+        assert_eq!(
+            fs::read_to_string(&backups[0].path).expect("read newest"),
+            format!("v{}\n", DEFAULT_BACKUP_COUNT + 2)
+        );
+        assert!(
+            fs::read_dir(&dir)
+                .expect("read dir")
+                .flatten()
+                .all(|entry| !entry.file_name().to_string_lossy().ends_with(".tmp"))
+        );
+
+        restore_backup(&backups[1]).expect("restore");
+        assert_eq!(
+            fs::read_to_string(&day).expect("read day"),
+            format!("v{}\n", DEFAULT_BACKUP_COUNT + 1)
+        );
+        let newest = list_backups(&day).expect("list").remove(0);
+        assert_eq!(
+            fs::read_to_string(newest.path).expect("read backup"),
+            "unchanged\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
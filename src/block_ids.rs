@@ -0,0 +1,137 @@
+//! Obsidian-compatible block IDs (`^a1b2c3`) on entry headings and task lines.
+//!
+//! Line numbers shift whenever a file is edited, so state that has to find an entry or task
+//! again later (fold overrides, Google sync) is keyed by its block ID when it has one.
+//! IDs are optional: new ones are only written when `data.block_ids` is enabled, and
+//! `memolog migrate block-ids` backfills them into existing logs. IDs already in a file are
+//! always preserved.
+
+use rand::Rng;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::models::is_timestamped_line;
+use crate::storage;
+
+const ID_LEN: usize = 6;
+const ID_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether new entries and tasks get a block ID when they are written.
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Splits a trailing ` ^id` off `line`, returning the rest of the line and the ID.
+pub fn split_block_id(line: &str) -> (&str, Option<&str>) {
+    let trimmed = line.trim_end();
+    let Some((space_idx, space)) = trimmed
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+    else {
+        return (line, None);
+    };
+    let Some(id) = trimmed[space_idx + space.len_utf8()..].strip_prefix('^') else {
+        return (line, None);
+    };
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return (line, None);
+    }
+    (trimmed[..space_idx].trim_end(), Some(id))
+}
+
+/// Removes a trailing block ID from `line`, if any.
+pub fn strip_block_id(line: &str) -> &str {
+    split_block_id(line).0
+}
+
+/// Re-attaches `id` to the end of `line`.
+pub fn with_block_id(line: &str, id: Option<&str>) -> String {
+    match id {
+        Some(id) => format!("{} ^{id}", line.trim_end()),
+        None => line.to_string(),
+    }
+}
+
+/// Entry headings and task lines are the lines that carry block IDs.
+pub fn takes_block_id(line: &str) -> bool {
+    if line.contains("System: Carryover Checked") {
+        return false;
+    }
+    is_timestamped_line(line) || storage::is_task_line(line)
+}
+
+/// Appends a fresh ID to every heading and task line in `lines` that lacks one, avoiding
+/// IDs already used in `lines` or `existing` (the rest of the file). Returns how many were added.
+pub fn assign_missing(lines: &mut [String], existing: &str) -> usize {
+    let mut taken: HashSet<String> = existing
+        .lines()
+        .chain(lines.iter().map(String::as_str))
+        .filter_map(|line| split_block_id(line).1.map(str::to_string))
+        .collect();
+    let mut added = 0;
+    for line in lines.iter_mut() {
+        if !takes_block_id(line) || split_block_id(line).1.is_some() {
+            continue;
+        }
+        let id = loop {
+            let candidate = generate();
+            if taken.insert(candidate.clone()) {
+                break candidate;
+            }
+        };
+        *line = with_block_id(line, Some(&id));
+        added += 1;
+    }
+    added
+}
+
+fn generate() -> String {
+    let mut rng = rand::thread_rng();
+    (0..ID_LEN)
+        .map(|_| ID_ALPHABET[rng.gen_range(0..ID_ALPHABET.len())] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_and_assigns_block_ids() {
+        assert_eq!(
+            split_block_id("- [ ] Ship it 🍅 ^ab-12"),
+            ("- [ ] Ship it 🍅", Some("ab-12"))
+        );
+        assert_eq!(
+            split_block_id("## [09:00:00] ^x1y2z3"),
+            ("## [09:00:00]", Some("x1y2z3"))
+        );
+        assert_eq!(
+            split_block_id("x^2 is ^ not an id"),
+            ("x^2 is ^ not an id", None)
+        );
+        assert_eq!(split_block_id("^solo"), ("^solo", None));
+
+        let mut lines = vec![
+            "## [09:00:00]".to_string(),
+            "Plain note".to_string(),
+            "- [ ] Task".to_string(),
+            "- [x] Done ^keepme".to_string(),
+        ];
+        assert_eq!(assign_missing(&mut lines, ""), 2);
+        let heading_id = split_block_id(&lines[0]).1.expect("heading id");
+        let task_id = split_block_id(&lines[2]).1.expect("task id");
+        assert_eq!(heading_id.len(), ID_LEN);
+        assert_ne!(heading_id, task_id);
+        assert_eq!(lines[1], "Plain note");
+        assert_eq!(lines[3], "- [x] Done ^keepme");
+        assert_eq!(assign_missing(&mut lines, ""), 0);
+    }
+}
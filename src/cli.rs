@@ -0,0 +1,944 @@
+//! Headless subcommands that run without taking over the terminal.
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::io::{self, Read};
+
+use crate::app::{agenda_sort_key, apply_context_tag_to_lines, task_priority_rank};
+use crate::config::{Config, google_token_path};
+use crate::date_input::parse_relative_date_input;
+use crate::file_layout::{self, FileLayout};
+use crate::integrations::google::{self, AuthPollResult, SyncError};
+use crate::models::{AgendaItem, AgendaItemKind, TaskFilter, TimeSpent, TimelineFilter};
+use crate::storage;
+
+const USAGE: &str = "\
+Usage:
+  memolog                       Launch the TUI
+  memolog add <text>...         Append an entry to today's log
+  memolog add -                 Read the entry body from stdin
+  memolog tasks                 List tasks (open by default)
+  memolog agenda                List today's agenda
+  memolog estimates             List tasks that went over their @est estimate
+  memolog sync google           Run a two-way Google Calendar/Tasks sync
+  memolog migrate block-ids     Add block IDs (^abc123) to existing entries and tasks
+  memolog migrate layout        Move existing logs into the configured data.file_layout
+
+Options for `add`:
+  --date <date>                 Target date (YYYY-MM-DD, today, yesterday, -1d, mon, ...)
+  --context <work|personal>     Tag the entry with #work or #personal
+  --task                        Record each line as an open task (- [ ])
+
+Options for `tasks` and `agenda`:
+  --date <date>                 Day to list (default: today)
+  --week                        List the whole week (Mon-Sun) containing the day
+  --filter <open|done|all>      Task filter (default: open)
+  --open, --done, --all         Shorthand for --filter open|done|all
+  --unscheduled                 Include unscheduled tasks (`agenda` only)
+  --json                        Print JSON instead of a table
+
+Options for `estimates`:
+  --all                         List every task with an estimate, not only those over it
+  --json                        Print JSON instead of a table
+
+Options for `sync google`:
+  --wait                        When auth is required, wait for the browser login and then sync
+
+Options for `migrate layout`:
+  --from <pattern>              Layout the logs are stored in now (default: {yyyy}-{mm}-{dd}.md)";
+
+pub enum Command {
+    Add(AddArgs),
+    Tasks(ListArgs),
+    Agenda(ListArgs),
+    Estimates(EstimateArgs),
+    SyncGoogle(SyncArgs),
+    Migrate(MigrateTarget),
+    Help,
+}
+
+pub struct AddArgs {
+    /// Entry body; `None` means read it from stdin.
+    pub text: Option<String>,
+    pub date: Option<NaiveDate>,
+    pub context: Option<TimelineFilter>,
+    pub as_task: bool,
+}
+
+pub struct ListArgs {
+    pub date: NaiveDate,
+    pub week: bool,
+    pub filter: TaskFilter,
+    pub unscheduled: bool,
+    pub json: bool,
+}
+
+pub struct EstimateArgs {
+    /// Include tasks that are still within their estimate.
+    pub all: bool,
+    pub json: bool,
+}
+
+pub struct SyncArgs {
+    /// Keep the local OAuth listener alive until the browser redirect arrives.
+    pub wait_for_auth: bool,
+}
+
+pub enum MigrateTarget {
+    BlockIds,
+    /// Move logs stored under `from` into the configured layout.
+    Layout { from: FileLayout },
+}
+
+impl ListArgs {
+    /// Inclusive date range covered by the listing, mirroring the agenda day/week views.
+    fn range(&self) -> (NaiveDate, NaiveDate) {
+        if self.week {
+            let start = self.date - Duration::days(self.date.weekday().num_days_from_monday() as i64);
+            (start, start + Duration::days(6))
+        } else {
+            (self.date, self.date)
+        }
+    }
+}
+
+/// Runs a subcommand when arguments are present.
+/// Returns `None` when the TUI should be launched instead, otherwise the process exit code.
+pub fn run(args: &[String]) -> Option<i32> {
+    if args.is_empty() {
+        return None;
+    }
+
+    let today = Local::now().date_naive();
+    let command = match parse_args(args, today) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("memolog: {message}");
+            eprintln!("{USAGE}");
+            return Some(2);
+        }
+    };
+
+    let code = match command {
+        Command::Help => {
+            println!("{USAGE}");
+            0
+        }
+        Command::Add(add) => {
+            let config = load_config();
+            match run_add(&config, add, today) {
+                Ok(()) => 0,
+                Err(err) => {
+                    eprintln!("memolog: failed to add entry: {err}");
+                    1
+                }
+            }
+        }
+        Command::Tasks(list) => {
+            let config = load_config();
+            report_list(run_tasks(&config, &list), list.json, &time_spent(&config))
+        }
+        Command::Agenda(list) => {
+            let config = load_config();
+            report_list(run_agenda(&config, &list), list.json, &time_spent(&config))
+        }
+        Command::Estimates(estimates) => {
+            let config = load_config();
+            let time_spent = time_spent(&config);
+            let items = run_estimates(&config, &estimates, &time_spent, today);
+            report_list(items, estimates.json, &time_spent)
+        }
+        Command::SyncGoogle(sync) => {
+            let config = load_config();
+            run_sync_google(&config, &sync)
+        }
+        Command::Migrate(target) => {
+            let config = load_config();
+            run_migrate(&config, target)
+        }
+    };
+    Some(code)
+}
+
+pub fn parse_args(args: &[String], today: NaiveDate) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Help);
+    };
+    match command.as_str() {
+        "add" => parse_add_args(rest, today).map(Command::Add),
+        "tasks" => parse_list_args(rest, today, false).map(Command::Tasks),
+        "agenda" => parse_list_args(rest, today, true).map(Command::Agenda),
+        "estimates" => parse_estimate_args(rest).map(Command::Estimates),
+        "sync" => parse_sync_args(rest).map(Command::SyncGoogle),
+        "migrate" => parse_migrate_args(rest).map(Command::Migrate),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        other => Err(format!("unknown command `{other}`")),
+    }
+}
+
+fn parse_add_args(args: &[String], today: NaiveDate) -> Result<AddArgs, String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut from_stdin = false;
+    let mut date = None;
+    let mut context = None;
+    let mut as_task = false;
+    let mut only_positional = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if only_positional {
+            words.push(arg.clone());
+            continue;
+        }
+        let (flag, inline_value) = split_flag(arg);
+        match flag {
+            "--" => only_positional = true,
+            "-" => from_stdin = true,
+            "--task" => as_task = true,
+            "--date" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                let parsed = parse_relative_date_input(&value, today)
+                    .ok_or_else(|| format!("invalid date `{value}`"))?;
+                date = Some(parsed);
+            }
+            "--context" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                context = Some(match value.trim().to_lowercase().as_str() {
+                    "work" => TimelineFilter::Work,
+                    "personal" => TimelineFilter::Personal,
+                    _ => return Err(format!("invalid context `{value}` (use work or personal)")),
+                });
+            }
+            _ if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+            _ => words.push(arg.clone()),
+        }
+    }
+
+    if from_stdin && !words.is_empty() {
+        return Err("`-` cannot be combined with entry text".to_string());
+    }
+    let text = if from_stdin {
+        None
+    } else if words.is_empty() {
+        return Err("nothing to add".to_string());
+    } else {
+        Some(words.join(" "))
+    };
+
+    Ok(AddArgs {
+        text,
+        date,
+        context,
+        as_task,
+    })
+}
+
+/// Parses the shared `tasks`/`agenda` options; `--unscheduled` is only accepted for the agenda.
+fn parse_list_args(args: &[String], today: NaiveDate, agenda: bool) -> Result<ListArgs, String> {
+    let mut list = ListArgs {
+        date: today,
+        week: false,
+        filter: TaskFilter::Open,
+        unscheduled: false,
+        json: false,
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline_value) = split_flag(arg);
+        match flag {
+            "--week" => list.week = true,
+            "--unscheduled" if agenda => list.unscheduled = true,
+            "--unscheduled" => return Err("`--unscheduled` only applies to `agenda`".to_string()),
+            "--json" => list.json = true,
+            "--open" => list.filter = TaskFilter::Open,
+            "--done" => list.filter = TaskFilter::Done,
+            "--all" => list.filter = TaskFilter::All,
+            "--date" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                list.date = parse_relative_date_input(&value, today)
+                    .ok_or_else(|| format!("invalid date `{value}`"))?;
+            }
+            "--filter" => {
+                let value = flag_value(flag, inline_value, &mut iter)?;
+                list.filter = match value.trim().to_lowercase().as_str() {
+                    "open" => TaskFilter::Open,
+                    "done" => TaskFilter::Done,
+                    "all" => TaskFilter::All,
+                    _ => return Err(format!("invalid filter `{value}` (use open, done or all)")),
+                };
+            }
+            _ if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    Ok(list)
+}
+
+fn parse_estimate_args(args: &[String]) -> Result<EstimateArgs, String> {
+    let mut estimates = EstimateArgs {
+        all: false,
+        json: false,
+    };
+    for arg in args {
+        match arg.as_str() {
+            "--all" => estimates.all = true,
+            "--json" => estimates.json = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(estimates)
+}
+
+fn parse_sync_args(args: &[String]) -> Result<SyncArgs, String> {
+    let Some((target, rest)) = args.split_first() else {
+        return Err("missing sync target (expected `google`)".to_string());
+    };
+    if target != "google" {
+        return Err(format!("unknown sync target `{target}`"));
+    }
+
+    let mut sync = SyncArgs {
+        wait_for_auth: false,
+    };
+    for arg in rest {
+        match arg.as_str() {
+            "--wait" => sync.wait_for_auth = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(sync)
+}
+
+fn parse_migrate_args(args: &[String]) -> Result<MigrateTarget, String> {
+    match args {
+        [target] if target == "block-ids" => Ok(MigrateTarget::BlockIds),
+        [target, rest @ ..] if target == "layout" => parse_migrate_layout_args(rest),
+        [] => Err("missing migration (expected `block-ids` or `layout`)".to_string()),
+        [target] => Err(format!("unknown migration `{target}`")),
+        [_, extra, ..] => Err(format!("unexpected argument `{extra}`")),
+    }
+}
+
+fn parse_migrate_layout_args(args: &[String]) -> Result<MigrateTarget, String> {
+    let mut from = FileLayout::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = split_flag(arg);
+        match flag {
+            "--from" => {
+                let value = inline
+                    .or_else(|| iter.next().cloned())
+                    .ok_or("`--from` needs a layout pattern")?;
+                from = FileLayout::parse(&value)?;
+            }
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(MigrateTarget::Layout { from })
+}
+
+/// Splits `--flag=value` into its parts; other arguments are returned unchanged.
+fn split_flag(arg: &str) -> (&str, Option<String>) {
+    match arg.split_once('=') {
+        Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+        _ => (arg, None),
+    }
+}
+
+fn flag_value<'a>(
+    flag: &str,
+    inline_value: Option<String>,
+    iter: &mut impl Iterator<Item = &'a String>,
+) -> Result<String, String> {
+    inline_value
+        .or_else(|| iter.next().cloned())
+        .ok_or_else(|| format!("`{flag}` requires a value"))
+}
+
+fn load_config() -> Config {
+    let config = Config::load();
+    storage::configure(&config.data);
+    crate::task_metadata::configure_syntax(&config.ui.metadata_syntax);
+    config
+}
+
+fn run_add(config: &Config, add: AddArgs, today: NaiveDate) -> io::Result<()> {
+    let text = match add.text {
+        Some(text) => text,
+        None => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            buf
+        }
+    };
+
+    let content = compose_add_content(&text, add.context, add.as_task);
+    if content.trim().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "entry is empty"));
+    }
+
+    let log_path = &config.data.log_path;
+    match add.date {
+        Some(date) if date != today => storage::append_entry_to_date(log_path, date, &content),
+        _ => storage::append_entry(log_path, &content),
+    }
+}
+
+fn run_tasks(config: &Config, list: &ListArgs) -> io::Result<Vec<AgendaItem>> {
+    let (start, end) = list.range();
+    if list.filter == TaskFilter::Done {
+        return run_done_tasks(config, start, end);
+    }
+    let mut items = storage::read_tasks_for_date_range(&config.data.log_path, start, end)?;
+    // Listings show each task line once, not every projected occurrence of a recurring one.
+    items.retain(|item| !item.projected && matches_filter(item, list.filter));
+    items.sort_by_key(|item| {
+        (
+            task_priority_rank(item.priority),
+            item.date,
+            item.file_path.clone(),
+            item.line_number,
+        )
+    });
+    Ok(items)
+}
+
+/// Tasks closed within `start..=end`, newest first. Tasks count on the date of their `@done`
+/// stamp, so tasks scheduled before or after the range but finished in it are included;
+/// unstamped ones fall back to their agenda date.
+fn run_done_tasks(
+    config: &Config,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> io::Result<Vec<AgendaItem>> {
+    let mut items =
+        storage::read_tasks_for_date_range(&config.data.log_path, NaiveDate::MIN, NaiveDate::MAX)?;
+    items.retain(|item| {
+        let completed = item.schedule.done.unwrap_or(item.date);
+        item.status.is_closed() && completed >= start && completed <= end
+    });
+    items.sort_by_key(|item| {
+        std::cmp::Reverse((
+            item.schedule.done.unwrap_or(item.date),
+            item.schedule.done_time,
+            item.line_number,
+        ))
+    });
+    Ok(items)
+}
+
+fn run_agenda(config: &Config, list: &ListArgs) -> io::Result<Vec<AgendaItem>> {
+    let (start, end) = list.range();
+    let mut items = storage::read_agenda_entries(&config.data.log_path, start, end)?;
+    items.retain(|item| {
+        let unscheduled = item.kind == AgendaItemKind::Task && item.schedule.is_empty();
+        matches_filter(item, list.filter) && (list.unscheduled || !unscheduled)
+    });
+    items.sort_by_key(|item| agenda_sort_key(item, list.date));
+    Ok(items)
+}
+
+/// Tasks with an `@est` estimate (only those over it unless `--all`), oldest first. Scheduled
+/// tasks are looked up as far ahead as the TUI agenda goes.
+fn run_estimates(
+    config: &Config,
+    estimates: &EstimateArgs,
+    time_spent: &TimeSpent,
+    today: NaiveDate,
+) -> io::Result<Vec<AgendaItem>> {
+    let end = today + Duration::days(3650);
+    let mut items = storage::read_tasks_for_date_range(&config.data.log_path, NaiveDate::MIN, end)?;
+    items.retain(|item| {
+        let Some(estimate) = item.schedule.estimate.filter(|_| !item.projected) else {
+            return false;
+        };
+        let identity = storage::task_identity(&item.text);
+        let spent = time_spent.minutes(&item.task_instance(&identity), item.tomato_count);
+        estimates.all || estimate.progress(item.tomato_count, spent).1
+    });
+    items.sort_by_key(|item| (item.date, item.file_path.clone(), item.line_number));
+    Ok(items)
+}
+
+fn matches_filter(item: &AgendaItem, filter: TaskFilter) -> bool {
+    match item.kind {
+        AgendaItemKind::Note => true,
+        AgendaItemKind::Task => match filter {
+            TaskFilter::Open => !item.status.is_closed(),
+            TaskFilter::Done => item.status.is_closed(),
+            TaskFilter::All => true,
+        },
+    }
+}
+
+/// Time worked per task for estimate progress: recorded pomodoro sessions, else tomatoes.
+fn time_spent(config: &Config) -> TimeSpent {
+    let sessions = storage::read_pomodoro_sessions(&config.data.log_path).unwrap_or_default();
+    storage::time_spent(&sessions, config.pomodoro.work_minutes)
+}
+
+fn report_list(result: io::Result<Vec<AgendaItem>>, json: bool, time_spent: &TimeSpent) -> i32 {
+    let items = match result {
+        Ok(items) => items,
+        Err(err) => {
+            eprintln!("memolog: failed to read logs: {err}");
+            return 1;
+        }
+    };
+    if json {
+        match serde_json::to_string_pretty(&items) {
+            Ok(out) => println!("{out}"),
+            Err(err) => {
+                eprintln!("memolog: failed to encode JSON: {err}");
+                return 1;
+            }
+        }
+    } else {
+        for line in format_table(&items, time_spent) {
+            println!("{line}");
+        }
+    }
+    0
+}
+
+fn format_table(items: &[AgendaItem], time_spent: &TimeSpent) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| {
+            let status = match item.kind {
+                AgendaItemKind::Note => "   ".to_string(),
+                AgendaItemKind::Task => format!("[{}]", item.status.marker()),
+            };
+            let time = item
+                .time
+                .map(|t| t.format("%H:%M").to_string())
+                .unwrap_or_else(|| "     ".to_string());
+            let date = if item.kind == AgendaItemKind::Task && item.schedule.is_empty() {
+                "----------".to_string()
+            } else {
+                item.date.format("%Y-%m-%d").to_string()
+            };
+            let indent = "  ".repeat(item.indent);
+            let done = match (item.schedule.done, item.schedule.done_time) {
+                (Some(date), Some(time)) => format!(" (done {} {})", date, time.format("%H:%M")),
+                (Some(date), None) => format!(" (done {date})"),
+                (None, _) => String::new(),
+            };
+            let estimate = item
+                .schedule
+                .estimate
+                .map(|estimate| {
+                    let identity = storage::task_identity(&item.text);
+                    let task = item.task_instance(&identity);
+                    let spent = time_spent.minutes(&task, item.tomato_count);
+                    format!(" {}", estimate.badge(item.tomato_count, spent))
+                })
+                .unwrap_or_default();
+            format!("{date} {time} {status} {indent}{}{estimate}{done}", item.text)
+        })
+        .collect()
+}
+
+fn run_sync_google(config: &Config, sync: &SyncArgs) -> i32 {
+    match google::sync(config) {
+        Ok(report) => {
+            println!("Google sync complete: {}", report.summary());
+            0
+        }
+        Err(SyncError::AuthRequired(session)) => {
+            eprintln!("Google auth required. Open this URL in a browser to authorize MemoLog:");
+            println!("{}", session.display.auth_url);
+            if !sync.wait_for_auth {
+                eprintln!("Re-run with `--wait` to complete the login from the shell.");
+                return 1;
+            }
+
+            eprintln!(
+                "Waiting for the browser redirect on {} ...",
+                session.display.local_url
+            );
+            let receiver = google::spawn_auth_flow_poll(
+                config.google.clone(),
+                *session,
+                google_token_path(config),
+            );
+            match receiver.recv() {
+                Ok(AuthPollResult::Success) => {
+                    eprintln!("Google auth complete. Syncing now...");
+                    run_sync_google(
+                        config,
+                        &SyncArgs {
+                            wait_for_auth: false,
+                        },
+                    )
+                }
+                Ok(AuthPollResult::Error(message)) => {
+                    eprintln!("memolog: Google auth failed: {message}");
+                    1
+                }
+                Err(_) => {
+                    eprintln!("memolog: Google auth stopped.");
+                    1
+                }
+            }
+        }
+        Err(err) => {
+            eprintln!("memolog: Google sync failed: {}", err.message());
+            1
+        }
+    }
+}
+
+fn run_migrate(config: &Config, target: MigrateTarget) -> i32 {
+    match target {
+        MigrateTarget::BlockIds => match storage::backfill_block_ids(&config.data.log_path) {
+            Ok((files, ids)) => {
+                println!("Added {ids} block IDs across {files} log files.");
+                if !config.data.block_ids {
+                    println!("Set `block_ids = true` under [data] to give new entries IDs too.");
+                }
+                0
+            }
+            Err(err) => {
+                eprintln!("memolog: block ID migration failed: {err}");
+                1
+            }
+        },
+        MigrateTarget::Layout { from } => {
+            let to = file_layout::current();
+            if from == to {
+                println!(
+                    "Nothing to move: data.file_layout is still `{}`; set it to the new layout first.",
+                    to.pattern()
+                );
+                return 0;
+            }
+            match storage::migrate_layout(&config.data.log_path, &from, &to) {
+                Ok((days, files)) => {
+                    println!(
+                        "Moved {days} days from `{}` into {files} files laid out as `{}`.",
+                        from.pattern(),
+                        to.pattern()
+                    );
+                    0
+                }
+                Err(err) => {
+                    eprintln!("memolog: layout migration failed: {err}");
+                    1
+                }
+            }
+        }
+    }
+}
+
+fn compose_add_content(text: &str, context: Option<TimelineFilter>, as_task: bool) -> String {
+    let mut lines: Vec<String> = text
+        .trim_end()
+        .lines()
+        .map(|line| {
+            if as_task {
+                task_line(line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if let Some(context) = context {
+        apply_context_tag_to_lines(&mut lines, context);
+    }
+    lines.join("\n")
+}
+
+fn task_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with("- [") {
+        return line.to_string();
+    }
+    let indent = &line[..line.len() - trimmed.len()];
+    let body = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .unwrap_or(trimmed);
+    format!("{indent}- [ ] {body}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("valid date")
+    }
+
+    #[test]
+    fn parse_add_collects_text_and_flags() {
+        let today = date("2025-03-12");
+        let parsed = parse_args(
+            &args(&["add", "Fix", "login", "--date", "yesterday", "--context=work", "--task"]),
+            today,
+        )
+        .expect("parse add");
+        let Command::Add(add) = parsed else {
+            panic!("expected add command");
+        };
+        assert_eq!(add.text.as_deref(), Some("Fix login"));
+        assert_eq!(add.date, Some(date("2025-03-11")));
+        assert!(add.context == Some(TimelineFilter::Work));
+        assert!(add.as_task);
+    }
+
+    #[test]
+    fn parse_add_rejects_bad_input() {
+        let today = date("2025-03-12");
+        assert!(parse_args(&args(&["add"]), today).is_err());
+        assert!(parse_args(&args(&["add", "-", "text"]), today).is_err());
+        assert!(parse_args(&args(&["add", "x", "--context", "home"]), today).is_err());
+        assert!(parse_args(&args(&["add", "x", "--date"]), today).is_err());
+        assert!(parse_args(&args(&["frobnicate"]), today).is_err());
+
+        let Ok(Command::Add(add)) = parse_args(&args(&["add", "-"]), today) else {
+            panic!("expected stdin add");
+        };
+        assert!(add.text.is_none());
+    }
+
+    #[test]
+    fn parse_list_args_builds_week_range() {
+        let today = date("2025-03-12");
+        let Ok(Command::Agenda(list)) = parse_args(
+            &args(&["agenda", "--week", "--filter", "all", "--json"]),
+            today,
+        ) else {
+            panic!("expected agenda command");
+        };
+        assert_eq!(list.filter, TaskFilter::All);
+        assert!(list.json);
+        assert_eq!(list.range(), (date("2025-03-10"), date("2025-03-16")));
+
+        let Ok(Command::Tasks(list)) = parse_args(&args(&["tasks", "--date=2025-03-01"]), today)
+        else {
+            panic!("expected tasks command");
+        };
+        assert_eq!(list.filter, TaskFilter::Open);
+        assert_eq!(list.range(), (date("2025-03-01"), date("2025-03-01")));
+        assert!(parse_args(&args(&["tasks", "--filter", "later"]), today).is_err());
+        assert!(parse_args(&args(&["agenda", "extra"]), today).is_err());
+    }
+
+    #[test]
+    fn parse_list_args_limits_unscheduled_to_agenda() {
+        let today = date("2025-03-12");
+        let Ok(Command::Agenda(list)) =
+            parse_args(&args(&["agenda", "--unscheduled", "--done"]), today)
+        else {
+            panic!("expected agenda command");
+        };
+        assert!(list.unscheduled);
+        assert_eq!(list.filter, TaskFilter::Done);
+
+        let Err(message) = parse_args(&args(&["tasks", "--unscheduled"]), today) else {
+            panic!("expected `tasks --unscheduled` to be rejected");
+        };
+        assert!(message.contains("agenda"));
+    }
+
+    #[test]
+    fn parse_estimate_args_accepts_all_and_json() {
+        let today = date("2025-03-12");
+        let Ok(Command::Estimates(estimates)) =
+            parse_args(&args(&["estimates", "--all", "--json"]), today)
+        else {
+            panic!("expected estimates command");
+        };
+        assert!(estimates.all && estimates.json);
+        assert!(parse_args(&args(&["estimates", "--week"]), today).is_err());
+    }
+
+    #[test]
+    fn parse_sync_args_requires_google_target() {
+        let today = date("2025-03-12");
+        let Ok(Command::SyncGoogle(sync)) = parse_args(&args(&["sync", "google", "--wait"]), today)
+        else {
+            panic!("expected sync command");
+        };
+        assert!(sync.wait_for_auth);
+        assert!(parse_args(&args(&["sync"]), today).is_err());
+        assert!(parse_args(&args(&["sync", "dropbox"]), today).is_err());
+        assert!(parse_args(&args(&["sync", "google", "--force"]), today).is_err());
+    }
+
+    #[test]
+    fn parse_migrate_args_requires_known_target() {
+        let today = date("2025-03-12");
+        assert!(matches!(
+            parse_args(&args(&["migrate", "block-ids"]), today),
+            Ok(Command::Migrate(MigrateTarget::BlockIds))
+        ));
+        assert!(parse_args(&args(&["migrate"]), today).is_err());
+        assert!(matches!(
+            parse_args(&args(&["migrate", "layout"]), today),
+            Ok(Command::Migrate(MigrateTarget::Layout { from })) if from == FileLayout::default()
+        ));
+        assert!(matches!(
+            parse_args(&args(&["migrate", "layout", "--from", "{yyyy}/W{ww}.md"]), today),
+            Ok(Command::Migrate(MigrateTarget::Layout { from })) if from.is_shared()
+        ));
+        assert!(parse_args(&args(&["migrate", "layout", "--from={dd}.md"]), today).is_err());
+        assert!(parse_args(&args(&["migrate", "notes"]), today).is_err());
+        assert!(parse_args(&args(&["migrate", "block-ids", "now"]), today).is_err());
+    }
+
+    #[test]
+    fn compose_add_content_applies_task_and_context() {
+        let content = compose_add_content(
+            "Ship release\n  - write notes\n- [x] tag build\n",
+            Some(TimelineFilter::Personal),
+            true,
+        );
+        assert_eq!(
+            content,
+            "- [ ] Ship release #personal\n  - [ ] write notes\n- [x] tag build"
+        );
+        assert_eq!(compose_add_content("plain note", None, false), "plain note");
+    }
+
+    #[test]
+    fn run_tasks_lists_recurring_task_once() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "memolog-cli-test-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        std::fs::write(
+            storage::get_file_path_for_date(&dir, "2999-01-07"),
+            "- [ ] Water plants @every(1d) @sched(2999-01-07)\n",
+        )
+        .expect("write log");
+
+        let mut config = Config::default();
+        config.data.log_path = dir.clone();
+        let list = ListArgs {
+            date: date("2999-01-07"),
+            week: true,
+            filter: TaskFilter::Open,
+            unscheduled: false,
+            json: true,
+        };
+        let items = run_tasks(&config, &list).expect("run tasks");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].text, "Water plants");
+        assert!(!items[0].projected);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn run_estimates_counts_sessions_per_task_line() {
+        use chrono::TimeZone;
+
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "memolog-cli-estimates-test-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let day1 = storage::get_file_path_for_date(&dir, "2025-01-06");
+        let day2 = storage::get_file_path_for_date(&dir, "2025-01-07");
+        std::fs::write(
+            &day1,
+            "- [x] Water plants @every(1d) @sched(2025-01-06) @est(30m) 🍅🍅\n\
+             - [ ] Water plants @every(1d) @sched(2025-01-07) @est(30m)\n\
+             - [ ] Write report @est(1h) 🍅🍅\n",
+        )
+        .expect("write day 1");
+        std::fs::write(&day2, "- [ ] Write report @est(1h) ⟦2025-01-06⟧ 🍅\n")
+            .expect("write day 2");
+
+        let session = |path: &std::path::Path, text: &str, day: u32, minutes: u32| {
+            let start = Local
+                .with_ymd_and_hms(2025, 1, day, 9, 0, 0)
+                .single()
+                .expect("start");
+            storage::PomodoroSession {
+                start,
+                end: start + Duration::minutes(minutes as i64),
+                task: text.to_string(),
+                task_identity: storage::task_identity(text),
+                file_path: path.to_string_lossy().to_string(),
+                block_id: None,
+                planned_minutes: 25,
+                actual_minutes: minutes,
+                interrupted: false,
+            }
+        };
+        for session in [
+            session(&day1, "Water plants", 6, 25),
+            session(&day1, "Water plants", 6, 25),
+            session(&day1, "Write report", 6, 25),
+            session(&day2, "Write report", 7, 70),
+        ] {
+            storage::append_pomodoro_session(&dir, &session).expect("record session");
+        }
+
+        let mut config = Config::default();
+        config.data.log_path = dir.clone();
+        let time_spent = time_spent(&config);
+        let estimates = EstimateArgs {
+            all: false,
+            json: false,
+        };
+        let over = run_estimates(&config, &estimates, &time_spent, date("2025-01-07"))
+            .expect("run estimates");
+        let over: Vec<(String, usize)> = over
+            .iter()
+            .map(|item| (item.file_path.clone(), item.line_number))
+            .collect();
+        // The next occurrence has no sessions of its own yet, and the line the report was
+        // carried over from keeps its 25m session plus 25m for its unrecorded tomato.
+        assert_eq!(
+            over,
+            vec![
+                (day1.to_string_lossy().to_string(), 0),
+                (day2.to_string_lossy().to_string(), 0),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn run_done_tasks_uses_done_date_not_schedule() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "memolog-cli-done-test-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        std::fs::write(
+            storage::get_file_path_for_date(&dir, "2025-03-10"),
+            "- [x] Ship beta @due(2025-04-01) @done(2025-03-12)\n\
+             - [x] Old chore @done(2025-03-01)\n",
+        )
+        .expect("write log");
+
+        let mut config = Config::default();
+        config.data.log_path = dir.clone();
+        let items = run_done_tasks(&config, date("2025-03-10"), date("2025-03-16"))
+            .expect("run done tasks");
+        let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(texts, vec!["Ship beta"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -232,12 +232,30 @@ pub struct Config {
 #[serde(default)]
 pub struct DataConfig {
     pub log_path: PathBuf,
+    /// Versions of each daily file kept under `.memolog/backups/` (0 disables backups).
+    pub backup_count: usize,
+    /// Append Obsidian block IDs (`^abc123`) to new entries and tasks.
+    pub block_ids: bool,
+    /// Stamp tasks with `@done(YYYY-MM-DD HH:MM)` when they are completed.
+    pub done_timestamps: bool,
+    /// Path pattern of log files relative to `log_path`, e.g. `{yyyy}/{mm}/{yyyy}-{mm}-{dd}.md`.
+    pub file_layout: String,
+    /// Heading written for new entries; `{time}` is replaced using `time_format`.
+    pub entry_heading: String,
+    /// strftime format of the time in entry headings, e.g. `%H:%M`.
+    pub time_format: String,
 }
 
 impl Default for DataConfig {
     fn default() -> Self {
         Self {
             log_path: default_log_dir(),
+            backup_count: crate::backups::DEFAULT_BACKUP_COUNT,
+            block_ids: false,
+            done_timestamps: false,
+            file_layout: crate::file_layout::DEFAULT_FILE_LAYOUT.to_string(),
+            entry_heading: crate::models::DEFAULT_ENTRY_HEADING.to_string(),
+            time_format: crate::models::DEFAULT_TIME_FORMAT.to_string(),
         }
     }
 }
@@ -254,6 +272,8 @@ pub struct GoogleConfig {
     pub sync_past_days: i64,
     pub sync_future_days: i64,
     pub conflict_policy: String,
+    /// `complete` marks cancelled tasks completed in Google; `delete` removes them.
+    pub cancelled_tasks: String,
     pub token_path: Option<PathBuf>,
     pub sync_state_path: Option<PathBuf>,
 }
@@ -270,6 +290,7 @@ impl Default for GoogleConfig {
             sync_past_days: 30,
             sync_future_days: 365,
             conflict_policy: "prefer_local".to_string(),
+            cancelled_tasks: "complete".to_string(),
             token_path: None,
             sync_state_path: None,
         }
@@ -332,6 +353,15 @@ pub struct UiConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub editor_style: Option<String>,
     pub line_numbers: bool,
+    /// Syntax used when MemoLog writes task metadata: `inline` (`@due(...)`) or `emoji`
+    /// (Obsidian Tasks signifiers such as `📅 2025-01-10`). Both are always read.
+    pub metadata_syntax: String,
+    /// Days before a `@due` date that a task is listed under upcoming deadlines in the agenda,
+    /// unless the task sets its own (`@due(2025-02-01, -3d)`).
+    pub deadline_warning_days: u32,
+    /// Turn phrases like `tomorrow 3pm for 30m !A` in composer task lines into metadata tokens
+    /// when the entry is saved.
+    pub quick_add: bool,
 }
 
 impl Default for UiConfig {
@@ -340,6 +370,9 @@ impl Default for UiConfig {
             theme_preset: None,
             editor_style: None,
             line_numbers: true,
+            metadata_syntax: "inline".to_string(),
+            deadline_warning_days: 7,
+            quick_add: true,
         }
     }
 }
@@ -373,9 +406,11 @@ pub struct GlobalBindings {
     pub log_dir: Vec<String>,
     pub edit_config: Vec<String>,
     pub pomodoro: Vec<String>,
+    pub focus_mode: Vec<String>,
     pub sync_google: Vec<String>,
     pub theme_switcher: Vec<String>,
     pub editor_style_switcher: Vec<String>,
+    pub backups: Vec<String>,
 }
 
 impl Default for GlobalBindings {
@@ -395,9 +430,11 @@ impl Default for GlobalBindings {
             log_dir: vec!["o".to_string()],
             edit_config: vec![",".to_string()],
             pomodoro: vec!["p".to_string()],
+            focus_mode: vec!["shift+f".to_string()],
             sync_google: vec!["ctrl+g".to_string()],
             theme_switcher: vec!["shift+t".to_string()],
             editor_style_switcher: vec!["shift+v".to_string()],
+            backups: vec!["shift+b".to_string()],
         }
     }
 }
@@ -466,6 +503,13 @@ pub struct AgendaBindings {
     pub next_week: Vec<String>,
     pub today: Vec<String>,
     pub toggle_unscheduled: Vec<String>,
+    pub status_cycle: Vec<String>,
+    pub defer_day: Vec<String>,
+    pub defer_week: Vec<String>,
+    pub defer_monday: Vec<String>,
+    pub defer_to: Vec<String>,
+    pub mark: Vec<String>,
+    pub bulk: Vec<String>,
 }
 
 impl Default for AgendaBindings {
@@ -482,6 +526,13 @@ impl Default for AgendaBindings {
             next_week: vec!["pagedown".to_string()],
             today: vec!["g".to_string()],
             toggle_unscheduled: vec!["u".to_string()],
+            status_cycle: vec!["s".to_string()],
+            defer_day: vec!["d".to_string()],
+            defer_week: vec!["w".to_string()],
+            defer_monday: vec!["m".to_string()],
+            defer_to: vec!["shift+d".to_string()],
+            mark: vec!["v".to_string()],
+            bulk: vec!["b".to_string()],
         }
     }
 }
@@ -496,6 +547,13 @@ pub struct TasksBindings {
     pub open: Vec<String>,
     pub edit: Vec<String>,
     pub priority_cycle: Vec<String>,
+    pub status_cycle: Vec<String>,
+    pub defer_day: Vec<String>,
+    pub defer_week: Vec<String>,
+    pub defer_monday: Vec<String>,
+    pub defer_to: Vec<String>,
+    pub mark: Vec<String>,
+    pub bulk: Vec<String>,
     pub filter_toggle: Vec<String>,
     pub filter_open: Vec<String>,
     pub filter_done: Vec<String>,
@@ -512,6 +570,13 @@ impl Default for TasksBindings {
             open: vec!["enter".to_string()],
             edit: vec!["e".to_string()],
             priority_cycle: vec!["shift+p".to_string()],
+            status_cycle: vec!["s".to_string()],
+            defer_day: vec!["d".to_string()],
+            defer_week: vec!["w".to_string()],
+            defer_monday: vec!["m".to_string()],
+            defer_to: vec!["shift+d".to_string()],
+            mark: vec!["v".to_string()],
+            bulk: vec!["b".to_string()],
             filter_toggle: vec!["f".to_string()],
             filter_open: vec!["1".to_string()],
             filter_done: vec!["2".to_string()],
@@ -535,6 +600,7 @@ pub struct ComposerBindings {
     pub context_work: Vec<String>,
     pub context_personal: Vec<String>,
     pub context_clear: Vec<String>,
+    pub quick_add_toggle: Vec<String>,
 }
 
 impl Default for ComposerBindings {
@@ -552,6 +618,7 @@ impl Default for ComposerBindings {
             context_work: vec!["ctrl+w".to_string()],
             context_personal: vec!["ctrl+e".to_string()],
             context_clear: vec!["ctrl+r".to_string()],
+            quick_add_toggle: vec!["ctrl+o".to_string()],
         }
     }
 }
@@ -646,6 +713,20 @@ pub struct ThemeUiOverrides {
     pub cursorline_bg: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub toast: Option<ThemeToastOverrides>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<ThemeDeadlineOverrides>,
+}
+
+/// Agenda colours for deadlines, escalating from upcoming to due today to overdue.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ThemeDeadlineOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upcoming: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub today: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overdue: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -774,6 +855,7 @@ impl Theme {
                         success: Some("80,250,123".to_string()),
                         error: Some("255,85,85".to_string()),
                     }),
+                    deadline: None,
                 }),
             },
             ThemePreset::SolarizedDark => Theme {
@@ -799,6 +881,7 @@ impl Theme {
                         success: Some("133,153,0".to_string()),
                         error: Some("220,50,47".to_string()),
                     }),
+                    deadline: None,
                 }),
             },
             ThemePreset::SolarizedLight => Theme {
@@ -824,6 +907,7 @@ impl Theme {
                         success: Some("133,153,0".to_string()),
                         error: Some("220,50,47".to_string()),
                     }),
+                    deadline: None,
                 }),
             },
             ThemePreset::NordCalm => Theme {
@@ -849,6 +933,7 @@ impl Theme {
                         success: Some("163,190,140".to_string()),
                         error: Some("191,97,106".to_string()),
                     }),
+                    deadline: None,
                 }),
             },
             ThemePreset::MonoContrast => Theme {
@@ -874,6 +959,7 @@ impl Theme {
                         success: Some("200,200,200".to_string()),
                         error: Some("220,80,80".to_string()),
                     }),
+                    deadline: None,
                 }),
             },
         }
@@ -887,6 +973,8 @@ pub struct PomodoroConfig {
     pub short_break_minutes: u64,
     pub long_break_minutes: u64,
     pub long_break_every: u64,
+    /// Start the break timer as soon as a work session ends instead of asking first.
+    pub auto_start_breaks: bool,
     pub alert_seconds: u64,
 }
 
@@ -897,6 +985,7 @@ impl Default for PomodoroConfig {
             short_break_minutes: 5,
             long_break_minutes: 15,
             long_break_every: 4,
+            auto_start_breaks: false,
             alert_seconds: 5,
         }
     }
@@ -1,5 +1,8 @@
-use crate::models::Priority;
-use crate::task_metadata::{TaskMetadataKey, remove_task_metadata_token, upsert_task_metadata_token};
+use crate::models::{Priority, split_task_checkbox};
+use crate::task_metadata::{
+    TaskMetadataKey, emoji_syntax, parse_priority_emoji, remove_task_metadata_token,
+    upsert_task_metadata_token,
+};
 use tui_textarea::{CursorMove, TextArea};
 
 pub(crate) fn insert_newline_with_auto_indent(textarea: &mut TextArea) {
@@ -91,19 +94,32 @@ pub(crate) fn cycle_task_priority(textarea: &mut TextArea) -> bool {
     };
 
     let (current_priority, remaining) = split_priority_marker(content);
-    let next_priority = next_priority(current_priority);
+    let emoji_priority = parse_priority_emoji(&remaining);
+    let next_priority = next_priority(current_priority.or(emoji_priority));
 
+    let remaining = match emoji_priority {
+        Some(_) => remove_task_metadata_token(&remaining, TaskMetadataKey::Priority),
+        None => remaining,
+    };
     let remaining = remaining.trim_start();
     let mut new_content = String::new();
-    if let Some(priority) = next_priority {
-        new_content.push_str("[#");
-        new_content.push(priority.as_char());
-        new_content.push(']');
-        if !remaining.is_empty() {
-            new_content.push(' ');
+    match next_priority {
+        Some(priority) if emoji_syntax() => {
+            let letter = priority.as_char().to_string();
+            new_content =
+                upsert_task_metadata_token(remaining, TaskMetadataKey::Priority, &letter);
+        }
+        Some(priority) => {
+            new_content.push_str("[#");
+            new_content.push(priority.as_char());
+            new_content.push(']');
+            if !remaining.is_empty() {
+                new_content.push(' ');
+            }
+            new_content.push_str(remaining);
         }
+        None => new_content.push_str(remaining),
     }
-    new_content.push_str(remaining);
 
     let new_line = format!("{indent}{prefix}{new_content}");
     if new_line == current_line {
@@ -276,16 +292,7 @@ fn parse_indent_level(line: &str) -> (usize, &str) {
 }
 
 fn checkbox_marker(rest: &str) -> Option<(&'static str, &str)> {
-    if let Some(content) = rest.strip_prefix("- [ ] ") {
-        return Some(("- [ ] ", content));
-    }
-    if let Some(content) = rest.strip_prefix("- [x] ") {
-        return Some(("- [x] ", content));
-    }
-    if let Some(content) = rest.strip_prefix("- [X] ") {
-        return Some(("- [X] ", content));
-    }
-    None
+    split_task_checkbox(rest).map(|(status, content)| (status.checkbox(), content))
 }
 
 fn bullet_marker(rest: &str) -> Option<(&'static str, &str)> {
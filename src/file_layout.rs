@@ -0,0 +1,310 @@
+//! Where each day's log lives on disk, as configured by `data.file_layout`.
+//!
+//! A layout is a path pattern relative to `data.log_path`. Patterns with `{dd}` give every day
+//! its own file; patterns with `{ww}` (ISO week) or only `{mm}` put several days in one file,
+//! where each day is a section under a `# YYYY-MM-DD` heading. Every path MemoLog reads or
+//! writes for a date goes through the layout configured here.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const DEFAULT_FILE_LAYOUT: &str = "{yyyy}-{mm}-{dd}.md";
+
+static CURRENT: RwLock<Option<FileLayout>> = RwLock::new(None);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    /// ISO week, Monday to Sunday. `{yyyy}` is the ISO week-numbering year.
+    Week,
+    Month,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Year,
+    Month,
+    Day,
+    Week,
+}
+
+#[derive(Clone, Debug)]
+pub struct FileLayout {
+    pattern: String,
+    period: Period,
+    tokens: Vec<Token>,
+    matcher: Regex,
+}
+
+impl Default for FileLayout {
+    fn default() -> Self {
+        Self::parse(DEFAULT_FILE_LAYOUT).expect("default layout is valid")
+    }
+}
+
+impl PartialEq for FileLayout {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl FileLayout {
+    /// Parses a pattern such as `{yyyy}/{mm}/{yyyy}-{mm}-{dd}.md` or `{yyyy}/W{ww}.md`.
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.trim().trim_start_matches('/');
+        if !pattern.ends_with(".md") {
+            return Err(format!("file layout `{pattern}` must end with `.md`"));
+        }
+
+        let mut tokens = Vec::new();
+        let mut regex = String::from("(?:^|/)");
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            regex.push_str(&regex::escape(&rest[..start]));
+            let Some(len) = rest[start..].find('}') else {
+                return Err(format!("unclosed `{{` in file layout `{pattern}`"));
+            };
+            let (token, width) = match &rest[start + 1..start + len] {
+                "yyyy" => (Token::Year, 4),
+                "mm" => (Token::Month, 2),
+                "dd" => (Token::Day, 2),
+                "ww" => (Token::Week, 2),
+                other => return Err(format!("unknown placeholder `{{{other}}}` in file layout")),
+            };
+            tokens.push(token);
+            regex.push_str(&format!("(\\d{{{width}}})"));
+            rest = &rest[start + len + 1..];
+        }
+        regex.push_str(&regex::escape(rest));
+        regex.push('$');
+
+        let has = |token| tokens.contains(&token);
+        let period = if !has(Token::Year) {
+            return Err(format!("file layout `{pattern}` needs a `{{yyyy}}` placeholder"));
+        } else if has(Token::Week) {
+            if has(Token::Month) || has(Token::Day) {
+                return Err("`{ww}` cannot be combined with `{mm}` or `{dd}`".to_string());
+            }
+            Period::Week
+        } else if has(Token::Day) {
+            if !has(Token::Month) {
+                return Err(format!("file layout `{pattern}` needs `{{mm}}` with `{{dd}}`"));
+            }
+            Period::Day
+        } else if has(Token::Month) {
+            Period::Month
+        } else {
+            return Err(format!(
+                "file layout `{pattern}` needs `{{dd}}`, `{{ww}}` or `{{mm}}`"
+            ));
+        };
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            period,
+            tokens,
+            matcher: Regex::new(&regex).map_err(|err| err.to_string())?,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// True when one file holds several days, each under a day heading.
+    pub fn is_shared(&self) -> bool {
+        self.period != Period::Day
+    }
+
+    /// First day stored in the file that holds `date`.
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self.period {
+            Period::Day => date,
+            Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    pub fn path_for_date(&self, log_path: &Path, date: NaiveDate) -> PathBuf {
+        let (year, week) = match self.period {
+            Period::Week => {
+                let iso = date.iso_week();
+                (iso.year(), iso.week())
+            }
+            _ => (date.year(), 0),
+        };
+        let relative = self
+            .pattern
+            .replace("{yyyy}", &format!("{year:04}"))
+            .replace("{mm}", &format!("{:02}", date.month()))
+            .replace("{dd}", &format!("{:02}", date.day()))
+            .replace("{ww}", &format!("{week:02}"));
+        log_path.join(relative)
+    }
+
+    /// First day stored in the file at `path`, or `None` if the path does not fit the layout.
+    pub fn date_for_path(&self, path: &Path) -> Option<NaiveDate> {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let captures = self.matcher.captures(&path)?;
+        let (mut year, mut month, mut day, mut week) = (None, None, None, None);
+        for (idx, token) in self.tokens.iter().enumerate() {
+            let value: u32 = captures.get(idx + 1)?.as_str().parse().ok()?;
+            let slot = match token {
+                Token::Year => &mut year,
+                Token::Month => &mut month,
+                Token::Day => &mut day,
+                Token::Week => &mut week,
+            };
+            // Repeated placeholders (e.g. `{yyyy}/{yyyy}-{mm}.md`) have to agree.
+            if slot.is_some_and(|existing| existing != value) {
+                return None;
+            }
+            *slot = Some(value);
+        }
+        let year = year? as i32;
+        match self.period {
+            Period::Day => NaiveDate::from_ymd_opt(year, month?, day?),
+            Period::Week => NaiveDate::from_isoywd_opt(year, week?, chrono::Weekday::Mon),
+            Period::Month => NaiveDate::from_ymd_opt(year, month?, 1),
+        }
+    }
+
+    /// Log files under `log_path` that fit the layout, with their first day, oldest first.
+    pub fn list_files(&self, log_path: &Path) -> io::Result<Vec<(NaiveDate, PathBuf)>> {
+        let mut files: Vec<(NaiveDate, PathBuf)> = markdown_files(log_path)?
+            .into_iter()
+            .filter_map(|path| self.date_for_path(&path).map(|date| (date, path)))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Sets the layout used by every reader and writer.
+pub fn configure(layout: FileLayout) {
+    let mut current = CURRENT
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *current = Some(layout);
+}
+
+pub fn current() -> FileLayout {
+    #[cfg(test)]
+    if let Some(layout) = TEST_LAYOUT.with(|layout| layout.borrow().clone()) {
+        return layout;
+    }
+    CURRENT
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_LAYOUT: std::cell::RefCell<Option<FileLayout>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Overrides the layout for the calling test thread only, so tests can run in parallel.
+#[cfg(test)]
+pub fn set_test_layout(layout: Option<FileLayout>) {
+    TEST_LAYOUT.with(|current| *current.borrow_mut() = layout);
+}
+
+/// Heading that starts a day's section in weekly and monthly files.
+pub fn day_heading(date: NaiveDate) -> String {
+    format!("# {}", date.format("%Y-%m-%d"))
+}
+
+pub fn parse_day_heading(line: &str) -> Option<NaiveDate> {
+    let date = line.trim_end().strip_prefix("# ")?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// All Markdown files below `log_path`, skipping hidden directories such as `.memolog`.
+pub fn markdown_files(log_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    let mut pending = vec![log_path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if dir == log_path => return Err(err),
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if path.is_dir() {
+                if !hidden {
+                    pending.push(path);
+                }
+            } else if !hidden && path.extension().and_then(|s| s.to_str()) == Some("md") {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("valid date")
+    }
+
+    #[test]
+    fn layouts_map_dates_to_paths_and_back() {
+        let root = Path::new("/vault/logs");
+        let nested = FileLayout::parse("{yyyy}/{mm}/{yyyy}-{mm}-{dd}.md").expect("nested");
+        let path = nested.path_for_date(root, date("2025-03-09"));
+        assert_eq!(path, Path::new("/vault/logs/2025/03/2025-03-09.md"));
+        assert_eq!(nested.date_for_path(&path), Some(date("2025-03-09")));
+        assert_eq!(
+            nested.date_for_path(Path::new("/vault/logs/2025/04/2025-03-09.md")),
+            None
+        );
+        assert!(!nested.is_shared());
+
+        // 2024-12-30 belongs to ISO week 1 of 2025.
+        let weekly = FileLayout::parse("{yyyy}/W{ww}.md").expect("weekly");
+        let path = weekly.path_for_date(root, date("2024-12-31"));
+        assert_eq!(path, Path::new("/vault/logs/2025/W01.md"));
+        assert_eq!(weekly.date_for_path(&path), Some(date("2024-12-30")));
+        assert_eq!(weekly.period_start(date("2025-01-05")), date("2024-12-30"));
+
+        let monthly = FileLayout::parse("{yyyy}-{mm}.md").expect("monthly");
+        assert_eq!(
+            monthly.path_for_date(root, date("2025-02-14")),
+            Path::new("/vault/logs/2025-02.md")
+        );
+        assert_eq!(monthly.period, Period::Month);
+
+        let flat = FileLayout::default();
+        assert_eq!(
+            flat.date_for_path(Path::new("/logs/2025-01-01.md")),
+            Some(date("2025-01-01"))
+        );
+        assert_eq!(flat.date_for_path(Path::new("/logs/notes.md")), None);
+
+        assert!(FileLayout::parse("{yyyy}-{mm}-{dd}.txt").is_err());
+        assert!(FileLayout::parse("{mm}-{dd}.md").is_err());
+        assert!(FileLayout::parse("{yyyy}-{hh}.md").is_err());
+        assert!(FileLayout::parse("{yyyy}-{mm}-W{ww}.md").is_err());
+    }
+
+    #[test]
+    fn parses_day_headings() {
+        assert_eq!(parse_day_heading("# 2025-01-06"), Some(date("2025-01-06")));
+        assert_eq!(parse_day_heading("## 2025-01-06"), None);
+        assert_eq!(parse_day_heading("# Meeting notes"), None);
+        assert_eq!(day_heading(date("2025-01-06")), "# 2025-01-06");
+    }
+}
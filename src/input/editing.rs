@@ -2,7 +2,10 @@ use crate::{
     app::App,
     config::key_match,
     editor::markdown,
-    models::{EditorMode, EntryIdentity, InputMode, TimelineFilter},
+    models::{
+        EditorMode, EntryIdentity, InputMode, TimelineFilter, heading_format,
+        is_heading_timestamp_line, parse_heading_time,
+    },
     storage,
 };
 use chrono::{Duration, Local};
@@ -63,6 +66,22 @@ pub fn handle_editing_mode(app: &mut App, key: KeyEvent) {
         return;
     }
 
+    if allow_composer_shortcuts
+        && key_match(&key, &app.config.keybindings.composer.quick_add_toggle)
+    {
+        if !app.config.ui.quick_add {
+            app.toast("Quick add is off (ui.quick_add).");
+            return;
+        }
+        app.quick_add_literal = !app.quick_add_literal;
+        app.toast(if app.quick_add_literal {
+            "Quick add off: phrases are saved as typed."
+        } else {
+            "Quick add on."
+        });
+        return;
+    }
+
     if allow_composer_shortcuts && key_match(&key, &app.config.keybindings.composer.date_picker) {
         app.open_date_picker();
         return;
@@ -226,9 +245,30 @@ pub(crate) fn discard_composer(app: &mut App) {
 }
 
 pub(crate) fn submit_composer(app: &mut App) {
-    let lines = app.textarea.lines().to_vec();
+    let lines: Vec<String> = app
+        .textarea
+        .lines()
+        .iter()
+        .map(|line| app.quick_add_line(line).map_or_else(|| line.clone(), |q| q.line))
+        .collect();
     let is_empty = lines.iter().all(|l| l.trim().is_empty());
 
+    // Never overwrite lines that changed underneath the composer; ask once, then save safely.
+    let conflict = app.editing_conflicts_with_disk();
+    if conflict
+        && let Some(editing) = app.editing_entry.as_mut()
+        && !editing.conflict_warned
+    {
+        editing.conflict_warned = true;
+        let message = if editing.is_raw {
+            "File changed on disk. Submit again to overwrite it, or discard."
+        } else {
+            "Entry changed on disk. Submit again to save your text as a new entry, or discard."
+        };
+        app.toast(message);
+        return;
+    }
+
     if let Some(editing) = app.editing_entry.take() {
         if editing.is_raw {
             let lines = app.textarea.lines().to_vec();
@@ -237,47 +277,67 @@ pub(crate) fn submit_composer(app: &mut App) {
             } else {
                 app.toast("Config saved. Restart to apply changes.");
             }
+        } else if conflict {
+            if !is_empty {
+                let date = editing.date.unwrap_or_else(|| Local::now().date_naive());
+                match storage::append_entry_to_date(
+                    &app.config.data.log_path,
+                    date,
+                    &lines.join("\n"),
+                ) {
+                    Ok(_) => app.toast("Saved as a new entry; the changed original was kept."),
+                    Err(_) => app.toast("Failed to save entry."),
+                }
+            }
+            app.update_logs();
         } else {
-        let selection_hint = (editing.file_path.clone(), editing.start_line);
         let mut new_lines: Vec<String> = Vec::new();
         if !is_empty {
-            let heading_time = if editing.timestamp_prefix.is_empty() {
-                Local::now().format("%H:%M:%S").to_string()
-            } else if let Some((prefix, _)) =
-                crate::models::split_timestamp_line(&editing.timestamp_prefix)
-            {
-                prefix
-                    .trim()
-                    .trim_start_matches('[')
-                    .trim_end_matches(']')
-                    .to_string()
+            let heading = if is_heading_timestamp_line(&editing.timestamp_prefix) {
+                editing.timestamp_prefix.clone()
             } else {
-                Local::now().format("%H:%M:%S").to_string()
+                let time = parse_heading_time(&editing.timestamp_prefix)
+                    .unwrap_or_else(|| Local::now().time());
+                heading_format().heading(time)
             };
 
-            new_lines.push(format!("## [{heading_time}]"));
+            new_lines.push(crate::block_ids::with_block_id(
+                &heading,
+                editing.block_id.as_deref(),
+            ));
             new_lines.extend(lines);
         }
 
-        if let Err(e) = storage::replace_entry_lines(
+        let start_line = match storage::replace_entry_lines(
             &editing.file_path,
             editing.start_line,
-            editing.end_line,
+            &editing.original_lines.join("\n"),
             &new_lines,
         ) {
-            eprintln!("Error updating entry: {}", e);
-        }
+            Ok(start_line) => start_line,
+            Err(e) => {
+                // Keep the composer open with the text intact so the edit isn't lost.
+                eprintln!("Error updating entry: {}", e);
+                app.report_write_error(&e, "Failed to update entry.");
+                app.editing_entry = Some(editing);
+                return;
+            }
+        };
+        let selection_hint = (editing.file_path.clone(), start_line);
         if let Some(state) = app
             .fold_overrides
-            .get(&EntryIdentity {
-                file_path: editing.file_path.clone(),
-                line_number: editing.start_line,
-            })
+            .get(&EntryIdentity::new(
+                &editing.file_path,
+                editing.start_line,
+                editing.original_lines.first().map_or("", String::as_str),
+            ))
             .copied()
+            && !new_lines.is_empty()
         {
             let _ = storage::update_fold_marker(
                 &editing.file_path,
-                editing.start_line,
+                start_line,
+                &new_lines.join("\n"),
                 state,
             );
         }
@@ -326,11 +386,66 @@ fn composer_has_unsaved_input(app: &App) -> bool {
 
 fn refresh_search_results(app: &mut App, query: &str) {
     if let Ok(results) = storage::search_entries(&app.config.data.log_path, query) {
-        app.logs = results;
-        app.is_search_result = true;
-        app.logs_state.select(Some(0));
+        app.set_search_results(results);
         app.search_highlight_query = Some(query.to_string());
         app.search_highlight_ready_at = Some(Local::now() + Duration::milliseconds(150));
         app.apply_fold_markers();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LogEntry;
+
+    fn composer_text(app: &App) -> Vec<String> {
+        app.textarea.lines().to_vec()
+    }
+
+    #[test]
+    fn failed_entry_update_keeps_composer_open() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "memolog-submit-test-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("2025-01-01.md");
+        std::fs::write(&path, "## [09:00:00]\nOriginal\n").expect("write log");
+        let entry = LogEntry {
+            content: "## [09:00:00]\nOriginal".to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            line_number: 0,
+            end_line: 1,
+            date: None,
+        };
+
+        let mut app = App::new();
+        app.start_edit_entry(&entry);
+        app.textarea = tui_textarea::TextArea::from(vec!["Edited".to_string()]);
+
+        // A stale entry is never overwritten; the first submit only warns.
+        std::fs::write(&path, "## [09:00:00]\nChanged elsewhere\n").expect("edit entry");
+        submit_composer(&mut app);
+        assert!(app.editing_entry.is_some());
+        assert_eq!(composer_text(&app), vec!["Edited"]);
+        assert!(app.input_mode == InputMode::Editing);
+
+        // A write that fails after the check keeps the edit and leaves the file alone.
+        // A plain file where `.memolog/` should be makes taking the backup fail.
+        std::fs::write(&path, "## [09:00:00]\nOriginal\n").expect("restore entry");
+        app.editing_entry.as_mut().expect("editing").conflict_warned = false;
+        std::fs::write(dir.join(".memolog"), "").expect("block backup dir");
+        submit_composer(&mut app);
+        assert!(app.editing_entry.is_some());
+        assert_eq!(composer_text(&app), vec!["Edited"]);
+        assert!(app.input_mode == InputMode::Editing);
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read log"),
+            "## [09:00:00]\nOriginal\n"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
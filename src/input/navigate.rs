@@ -49,6 +49,8 @@ pub fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         app.show_help_popup = true;
     } else if key_match(&key, &app.config.keybindings.global.tags) {
         actions::open_tag_popup(app);
+    } else if key_match(&key, &app.config.keybindings.global.backups) {
+        actions::open_backup_popup(app);
     } else if key_match(&key, &app.config.keybindings.global.edit_config) {
         actions::open_config_in_composer(app);
     } else if key_match(&key, &app.config.keybindings.global.sync_google) {
@@ -200,7 +202,10 @@ pub fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             }
         }
     } else if key.code == KeyCode::Esc {
-        if app.is_search_result {
+        if !app.marked_tasks.is_empty() {
+            app.marked_tasks.clear();
+            app.toast("Marks cleared.");
+        } else if app.is_search_result {
             app.last_search_query = None;
             app.update_logs();
         }
@@ -224,6 +229,34 @@ pub fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         && key_match(&key, &app.config.keybindings.agenda.toggle)
     {
         actions::toggle_agenda_task(app);
+    } else if app.navigate_focus == models::NavigateFocus::Agenda
+        && key_match(&key, &app.config.keybindings.agenda.status_cycle)
+    {
+        actions::cycle_agenda_task_status(app);
+    } else if app.navigate_focus == models::NavigateFocus::Agenda
+        && key_match(&key, &app.config.keybindings.agenda.defer_day)
+    {
+        actions::defer_selected_task(app, "+1d");
+    } else if app.navigate_focus == models::NavigateFocus::Agenda
+        && key_match(&key, &app.config.keybindings.agenda.defer_week)
+    {
+        actions::defer_selected_task(app, "+1w");
+    } else if app.navigate_focus == models::NavigateFocus::Agenda
+        && key_match(&key, &app.config.keybindings.agenda.defer_monday)
+    {
+        actions::defer_selected_task(app, "next mon");
+    } else if app.navigate_focus == models::NavigateFocus::Agenda
+        && key_match(&key, &app.config.keybindings.agenda.defer_to)
+    {
+        actions::open_defer_popup(app);
+    } else if app.navigate_focus == models::NavigateFocus::Agenda
+        && key_match(&key, &app.config.keybindings.agenda.mark)
+    {
+        actions::toggle_task_mark(app);
+    } else if app.navigate_focus == models::NavigateFocus::Agenda
+        && key_match(&key, &app.config.keybindings.agenda.bulk)
+    {
+        actions::open_bulk_popup(app);
     } else if app.navigate_focus == models::NavigateFocus::Agenda
         && key_match(&key, &app.config.keybindings.agenda.filter)
     {
@@ -261,11 +294,41 @@ pub fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         && key_match(&key, &app.config.keybindings.tasks.priority_cycle)
     {
         actions::cycle_task_priority(app);
+    } else if app.navigate_focus == models::NavigateFocus::Tasks
+        && key_match(&key, &app.config.keybindings.tasks.status_cycle)
+    {
+        actions::cycle_task_status(app);
+    } else if app.navigate_focus == models::NavigateFocus::Tasks
+        && key_match(&key, &app.config.keybindings.tasks.defer_day)
+    {
+        actions::defer_selected_task(app, "+1d");
+    } else if app.navigate_focus == models::NavigateFocus::Tasks
+        && key_match(&key, &app.config.keybindings.tasks.defer_week)
+    {
+        actions::defer_selected_task(app, "+1w");
+    } else if app.navigate_focus == models::NavigateFocus::Tasks
+        && key_match(&key, &app.config.keybindings.tasks.defer_monday)
+    {
+        actions::defer_selected_task(app, "next mon");
+    } else if app.navigate_focus == models::NavigateFocus::Tasks
+        && key_match(&key, &app.config.keybindings.tasks.defer_to)
+    {
+        actions::open_defer_popup(app);
+    } else if app.navigate_focus == models::NavigateFocus::Tasks
+        && key_match(&key, &app.config.keybindings.tasks.mark)
+    {
+        actions::toggle_task_mark(app);
+    } else if app.navigate_focus == models::NavigateFocus::Tasks
+        && key_match(&key, &app.config.keybindings.tasks.bulk)
+    {
+        actions::open_bulk_popup(app);
     } else if (app.navigate_focus == models::NavigateFocus::Tasks
         && key_match(&key, &app.config.keybindings.tasks.start_pomodoro))
         || key_match(&key, &app.config.keybindings.global.pomodoro)
     {
         actions::open_or_toggle_pomodoro_for_selected_task(app);
+    } else if key_match(&key, &app.config.keybindings.global.focus_mode) {
+        actions::open_focus_mode(app);
     } else if key_match(&key, &app.config.keybindings.global.activity) {
         actions::open_activity_popup(app);
     } else if key_match(&key, &app.config.keybindings.global.log_dir) {
@@ -6,6 +6,7 @@ use crate::{
     editor::markdown,
     input::editing,
     models::{self, DatePickerField, InputMode, Mood},
+    recurrence::{RECURRENCE_PRESETS, Recurrence},
     storage,
 };
 use chrono::{Duration, Local, NaiveTime, Timelike};
@@ -56,10 +57,22 @@ pub fn handle_popup_events(app: &mut App, key: KeyEvent) -> bool {
         return true;
     }
 
+    if app.show_focus_mode {
+        handle_focus_mode(app, key);
+        return true;
+    }
     if app.show_pomodoro_popup {
         handle_pomodoro_popup(app, key);
         return true;
     }
+    if app.show_defer_popup {
+        handle_defer_popup(app, key);
+        return true;
+    }
+    if app.show_bulk_popup {
+        handle_bulk_popup(app, key);
+        return true;
+    }
 
     if app.show_mood_popup {
         handle_mood_popup(app, key);
@@ -82,6 +95,10 @@ pub fn handle_popup_events(app: &mut App, key: KeyEvent) -> bool {
         handle_path_popup(app, key);
         return true;
     }
+    if app.show_backup_popup {
+        handle_backup_popup(app, key);
+        return true;
+    }
     false
 }
 
@@ -254,6 +271,9 @@ fn handle_date_picker_relative_input(app: &mut App, key: KeyEvent) {
             }
             DatePickerField::Time => parse_time_input(&input).map(DatePickerValue::Time),
             DatePickerField::Duration => parse_duration_input(&input).map(DatePickerValue::Duration),
+            DatePickerField::Recurrence => {
+                Recurrence::parse(&input).map(DatePickerValue::Recurrence)
+            }
         };
 
         if let Some(value) = parsed {
@@ -261,6 +281,7 @@ fn handle_date_picker_relative_input(app: &mut App, key: KeyEvent) {
                 DatePickerValue::Date(date) => app.set_date_picker_date(field, date),
                 DatePickerValue::Time(time) => app.set_date_picker_time(time),
                 DatePickerValue::Duration(minutes) => app.set_date_picker_duration(minutes),
+                DatePickerValue::Recurrence(rule) => app.set_date_picker_recurrence(rule),
             }
             app.date_picker_input.clear();
             app.date_picker_input_mode = false;
@@ -302,6 +323,9 @@ fn apply_date_picker_field(app: &mut App) {
     let duration_value = schedule.duration_minutes.or_else(|| {
         (field == DatePickerField::Duration).then(|| app.date_picker_effective_duration())
     });
+    let recurrence_value = schedule.recurrence.or_else(|| {
+        (field == DatePickerField::Recurrence).then(|| app.date_picker_effective_recurrence())
+    });
 
     let mut updated = false;
     if let Some(date) = scheduled_value {
@@ -313,7 +337,7 @@ fn apply_date_picker_field(app: &mut App) {
         );
     }
     if let Some(date) = due_value {
-        let value = date.format("%Y-%m-%d").to_string();
+        let value = crate::task_metadata::format_due(date, schedule.due_warning_days);
         updated |= markdown::upsert_task_metadata(
             &mut app.textarea,
             crate::task_metadata::TaskMetadataKey::Due,
@@ -344,6 +368,13 @@ fn apply_date_picker_field(app: &mut App) {
             &value,
         );
     }
+    if let Some(recurrence) = recurrence_value {
+        updated |= markdown::upsert_task_metadata(
+            &mut app.textarea,
+            crate::task_metadata::TaskMetadataKey::Every,
+            &recurrence.to_string(),
+        );
+    }
 
     if updated {
         app.mark_insert_modified();
@@ -358,6 +389,7 @@ fn remove_date_picker_field(app: &mut App) {
         DatePickerField::Start => crate::task_metadata::TaskMetadataKey::Start,
         DatePickerField::Time => crate::task_metadata::TaskMetadataKey::Time,
         DatePickerField::Duration => crate::task_metadata::TaskMetadataKey::Duration,
+        DatePickerField::Recurrence => crate::task_metadata::TaskMetadataKey::Every,
     };
 
     let updated = markdown::remove_task_metadata(&mut app.textarea, key);
@@ -385,6 +417,21 @@ fn adjust_date_picker_value(app: &mut App, delta_days: i64, delta_minutes: i32)
             let next = (current + delta).clamp(15, 24 * 60);
             app.set_date_picker_duration(next as u32);
         }
+        DatePickerField::Recurrence => {
+            let current = app.date_picker_effective_recurrence();
+            let index = RECURRENCE_PRESETS
+                .iter()
+                .position(|preset| Recurrence::parse(preset).as_ref() == Some(&current));
+            let len = RECURRENCE_PRESETS.len() as i64;
+            let next = match index {
+                Some(index) => (index as i64 + delta_days.signum()).rem_euclid(len),
+                None if delta_days < 0 => len - 1,
+                None => 0,
+            };
+            if let Some(rule) = Recurrence::parse(RECURRENCE_PRESETS[next as usize]) {
+                app.set_date_picker_recurrence(rule);
+            }
+        }
     }
 }
 
@@ -409,6 +456,7 @@ fn cycle_date_picker_field(field: DatePickerField, delta: i32) -> DatePickerFiel
         DatePickerField::Start,
         DatePickerField::Time,
         DatePickerField::Duration,
+        DatePickerField::Recurrence,
     ];
     let index = fields
         .iter()
@@ -441,6 +489,7 @@ enum DatePickerValue {
     Date(chrono::NaiveDate),
     Time(NaiveTime),
     Duration(u32),
+    Recurrence(Recurrence),
 }
 
 fn handle_exit_popup(app: &mut App, key: KeyEvent) {
@@ -465,13 +514,13 @@ fn handle_exit_popup(app: &mut App, key: KeyEvent) {
 fn handle_delete_entry_popup(app: &mut App, key: KeyEvent) {
     if key_match(&key, &app.config.keybindings.popup.confirm) {
         if let Some(entry) = app.delete_entry_target.take() {
-            if storage::delete_entry_lines(&entry.file_path, entry.line_number, entry.end_line)
-                .is_ok()
+            match storage::delete_entry_lines(&entry.file_path, entry.line_number, &entry.content)
             {
-                app.update_logs();
-                app.toast("Entry deleted.");
-            } else {
-                app.toast("Failed to delete entry.");
+                Ok(()) => {
+                    app.update_logs();
+                    app.toast("Entry deleted.");
+                }
+                Err(err) => app.report_write_error(&err, "Failed to delete entry."),
             }
         } else {
             app.toast("No entry selected.");
@@ -605,12 +654,10 @@ fn handle_tag_popup(app: &mut App, key: KeyEvent) {
         {
             let query = app.tags[i].0.clone();
             if let Ok(results) = storage::search_entries(&app.config.data.log_path, &query) {
-                app.logs = results;
-                app.is_search_result = true;
+                app.set_search_results(results);
                 app.last_search_query = Some(query);
                 app.search_highlight_query = app.last_search_query.clone();
                 app.search_highlight_ready_at = Some(Local::now() + Duration::milliseconds(150));
-                app.logs_state.select(Some(0));
             }
         }
         app.show_tag_popup = false;
@@ -621,6 +668,25 @@ fn handle_tag_popup(app: &mut App, key: KeyEvent) {
     }
 }
 
+fn handle_backup_popup(app: &mut App, key: KeyEvent) {
+    let len = app.backups.len();
+    if key_match(&key, &app.config.keybindings.popup.up) {
+        let i = app.backup_list_state.selected().unwrap_or(0).saturating_sub(1);
+        app.backup_list_state.select(Some(i));
+    } else if key_match(&key, &app.config.keybindings.popup.down) {
+        let i = app
+            .backup_list_state
+            .selected()
+            .map_or(0, |i| (i + 1).min(len.saturating_sub(1)));
+        app.backup_list_state.select(Some(i));
+    } else if key_match(&key, &app.config.keybindings.popup.confirm) {
+        actions::restore_selected_backup(app);
+    } else if key_match(&key, &app.config.keybindings.popup.cancel) || key.code == KeyCode::Esc {
+        app.show_backup_popup = false;
+        app.backups.clear();
+    }
+}
+
 fn handle_theme_switcher_popup(app: &mut App, key: KeyEvent) {
     let presets = ThemePreset::all();
     if presets.is_empty() {
@@ -727,17 +793,25 @@ fn handle_pomodoro_popup(app: &mut App, key: KeyEvent) {
             .clamp(1, 600);
 
         let now = Local::now();
+        if app.pomodoro_end.is_some_and(|end| now < end) {
+            app.log_pomodoro_session(now, true);
+        }
         app.pomodoro_start = Some(now);
         app.pomodoro_end = Some(now + Duration::minutes(mins));
         app.pomodoro_target = Some(models::PomodoroTarget::Task {
             text: task.text.clone(),
             file_path: task.file_path.clone(),
             line_number: task.line_number,
+            task_identity: task.task_identity.clone(),
+            block_id: task.block_id.clone(),
         });
+        app.pomodoro_phase = models::PomodoroPhase::Work;
+        app.pomodoro_pending_break = None;
         app.pomodoro_alert_expiry = None;
         app.pomodoro_alert_message = None;
         app.show_pomodoro_popup = false;
         app.toast(format!("Pomodoro started: {}m · {}", mins, task.text));
+        app.save_pomodoro_state();
         return;
     }
 
@@ -752,6 +826,118 @@ fn handle_pomodoro_popup(app: &mut App, key: KeyEvent) {
     }
 }
 
+fn handle_focus_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.show_focus_mode = false;
+            app.focus_entry = None;
+        }
+        KeyCode::Enter => actions::log_interruption(app),
+        KeyCode::Backspace => {
+            app.focus_input.pop();
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+            app.focus_input.push(c);
+        }
+        _ => {}
+    }
+}
+
+fn handle_defer_popup(app: &mut App, key: KeyEvent) {
+    if key.code == KeyCode::Esc {
+        app.show_defer_popup = false;
+        app.defer_target = None;
+        return;
+    }
+
+    if key.code == KeyCode::Enter {
+        let Some(target) = app.defer_target.clone() else {
+            app.show_defer_popup = false;
+            return;
+        };
+        let today = Local::now().date_naive();
+        let Some(date) = parse_relative_date_input(&app.defer_input, today) else {
+            app.toast("Invalid date. Try tomorrow, fri, +3d or 2025-02-01.");
+            return;
+        };
+        app.show_defer_popup = false;
+        app.defer_target = None;
+        actions::defer_task_to(app, &target, date);
+        return;
+    }
+
+    match key.code {
+        KeyCode::Backspace => {
+            app.defer_input.pop();
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+            app.defer_input.push(c);
+        }
+        _ => {}
+    }
+}
+
+fn handle_bulk_popup(app: &mut App, key: KeyEvent) {
+    if let Some(action) = app.bulk_prompt {
+        handle_bulk_prompt(app, key, action);
+        return;
+    }
+
+    if key_match(&key, &app.config.keybindings.popup.cancel) || key.code == KeyCode::Esc {
+        app.show_bulk_popup = false;
+        app.bulk_targets.clear();
+        return;
+    }
+
+    let len = models::BulkAction::ALL.len();
+    let selected = app.bulk_list_state.selected().unwrap_or(0);
+    if key_match(&key, &app.config.keybindings.popup.up) {
+        app.bulk_list_state.select(Some((selected + len - 1) % len));
+    } else if key_match(&key, &app.config.keybindings.popup.down) {
+        app.bulk_list_state.select(Some((selected + 1) % len));
+    } else if key_match(&key, &app.config.keybindings.popup.confirm) {
+        let action = models::BulkAction::ALL[selected.min(len - 1)];
+        if action.needs_prompt() {
+            app.bulk_input.clear();
+            app.bulk_prompt = Some(action);
+        } else {
+            actions::run_bulk_action(app, action, "");
+        }
+    }
+}
+
+fn handle_bulk_prompt(app: &mut App, key: KeyEvent, action: models::BulkAction) {
+    if key.code == KeyCode::Esc {
+        app.bulk_prompt = None;
+        return;
+    }
+
+    if action == models::BulkAction::Delete {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                actions::run_bulk_action(app, action, "");
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => app.bulk_prompt = None,
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Enter => {
+            let input = app.bulk_input.clone();
+            actions::run_bulk_action(app, action, &input);
+        }
+        KeyCode::Backspace => {
+            app.bulk_input.pop();
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+            app.bulk_input.push(c);
+        }
+        _ => {}
+    }
+}
+
 fn handle_path_popup(app: &mut App, key: KeyEvent) {
     if key_match(&key, &app.config.keybindings.popup.confirm) {
         // Try to open the log directory
@@ -1,5 +1,6 @@
 use crate::config::GeminiConfig;
 use crate::models::LogEntry;
+use crate::search_rank::SearchHit;
 use crate::storage;
 use reqwest::blocking::Client;
 use serde::Deserialize;
@@ -68,7 +69,7 @@ fn run_ai_search(
         Err(err) => return AiSearchOutcome::Error(err),
     };
 
-    let mut entries =
+    let mut hits =
         match storage::search_entries_by_keywords(log_path, &keywords) {
             Ok(results) => results,
             Err(err) => {
@@ -76,9 +77,10 @@ fn run_ai_search(
             }
         };
 
-    if config.max_results > 0 && entries.len() > config.max_results {
-        entries.truncate(config.max_results);
+    if config.max_results > 0 && hits.len() > config.max_results {
+        hits.truncate(config.max_results);
     }
+    let entries: Vec<LogEntry> = hits.iter().map(|hit| hit.entry.clone()).collect();
 
     if entries.is_empty() {
         return AiSearchOutcome::Success(AiSearchResult {
@@ -89,7 +91,7 @@ fn run_ai_search(
         });
     }
 
-    let answer = match generate_answer(&client, config, &api_key, question, &entries) {
+    let answer = match generate_answer(&client, config, &api_key, question, &hits) {
         Ok(text) => text,
         Err(err) => return AiSearchOutcome::Error(err),
     };
@@ -194,17 +196,22 @@ fn generate_answer(
     config: &GeminiConfig,
     api_key: &str,
     question: &str,
-    entries: &[LogEntry],
+    hits: &[SearchHit],
 ) -> Result<String, String> {
     let mut context = String::new();
-    for (idx, entry) in entries.iter().enumerate() {
+    for (idx, hit) in hits.iter().enumerate() {
+        let entry = &hit.entry;
         let index = idx + 1;
         let file = std::path::Path::new(&entry.file_path)
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or(entry.file_path.as_str())
             .to_string();
-        let snippet = truncate_chars(&entry.content, config.max_entry_chars.max(200));
+        let snippet = excerpt_around(
+            &entry.content,
+            hit.snippet.offset,
+            config.max_entry_chars.max(200),
+        );
         context.push_str(&format!(
             "[{index}] file: {file} line: {line}\n{snippet}\n\n",
             index = index,
@@ -429,6 +436,20 @@ fn extract_json_object(text: &str) -> Option<&str> {
     Some(&text[start..=end])
 }
 
+/// Truncates long entries to a window that starts shortly before the best-matching snippet.
+fn excerpt_around(text: &str, offset: usize, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars || offset == 0 || offset > text.len() {
+        return truncate_chars(text, max_chars);
+    }
+    let offset_chars = text[..offset].chars().count();
+    let skip = offset_chars.saturating_sub(max_chars / 4);
+    if skip == 0 {
+        return truncate_chars(text, max_chars);
+    }
+    let rest: String = text.chars().skip(skip).collect();
+    format!("...{}", truncate_chars(&rest, max_chars))
+}
+
 fn truncate_chars(text: &str, max_chars: usize) -> String {
     if max_chars == 0 {
         return String::new();
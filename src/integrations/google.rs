@@ -1,5 +1,6 @@
+use crate::block_ids::split_block_id;
 use crate::config::{Config, GoogleConfig, google_sync_state_path, google_token_path};
-use crate::models::{AgendaItem, AgendaItemKind, TaskSchedule};
+use crate::models::{AgendaItem, AgendaItemKind, TaskSchedule, TaskStatus, split_task_checkbox};
 use crate::storage::{self, NoteLineUpdate, TaskLineUpdate};
 use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use reqwest::blocking::Client;
@@ -23,7 +24,8 @@ const LOCAL_SYNC_FUTURE_DAYS: i64 = 3650;
 
 #[derive(Debug)]
 pub enum SyncError {
-    AuthRequired(AuthSession),
+    /// Boxed so every `Result<_, SyncError>` stays small.
+    AuthRequired(Box<AuthSession>),
     Config(String),
     Request(String),
     Io(String),
@@ -82,22 +84,26 @@ pub struct SyncReport {
     pub tasks_created: usize,
     pub tasks_updated: usize,
     pub tasks_imported: usize,
+    pub tasks_deleted: usize,
     pub events_created: usize,
     pub events_updated: usize,
     pub events_imported: usize,
+    pub events_deleted: usize,
     pub conflicts: usize,
 }
 
 impl SyncReport {
     pub fn summary(&self) -> String {
         format!(
-            "Tasks +{} ~{} <-{} | Events +{} ~{} <-{} | Conflicts {}",
+            "Tasks +{} ~{} <-{} -{} | Events +{} ~{} <-{} -{} | Conflicts {}",
             self.tasks_created,
             self.tasks_updated,
             self.tasks_imported,
+            self.tasks_deleted,
             self.events_created,
             self.events_updated,
             self.events_imported,
+            self.events_deleted,
             self.conflicts
         )
     }
@@ -148,6 +154,7 @@ struct RemoteTask {
     status: Option<String>,
     updated: Option<String>,
     due: Option<String>,
+    completed: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -186,6 +193,8 @@ struct TaskUpdateRequest {
     status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     due: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -207,7 +216,7 @@ pub fn spawn_sync(config: Config) -> Receiver<SyncOutcome> {
     thread::spawn(move || {
         let outcome = match sync(&config) {
             Ok(report) => SyncOutcome::Success(report),
-            Err(SyncError::AuthRequired(session)) => SyncOutcome::AuthRequired(session),
+            Err(SyncError::AuthRequired(session)) => SyncOutcome::AuthRequired(*session),
             Err(err) => SyncOutcome::Error(err.message()),
         };
         let _ = tx.send(outcome);
@@ -224,10 +233,13 @@ pub fn sync(config: &Config) -> Result<SyncReport, SyncError> {
     let today = Local::now().date_naive();
     let (start_date, end_date) = sync_range(config);
     let local_end_date = end_date.max(today + Duration::days(LOCAL_SYNC_FUTURE_DAYS));
-    let local_tasks =
+    let mut local_tasks =
         storage::read_tasks_for_date_range(&config.data.log_path, start_date, local_end_date)?;
     let mut local_events =
         storage::read_agenda_entries(&config.data.log_path, start_date, local_end_date)?;
+    // Projected occurrences of recurring tasks only exist in the agenda, not in the log.
+    local_tasks.retain(|item| !item.projected);
+    local_events.retain(|item| !item.projected);
     if !config.google.sync_tasks_to_calendar {
         local_events.retain(|item| item.kind == AgendaItemKind::Note);
     }
@@ -388,7 +400,7 @@ fn ensure_access_token(config: &Config) -> Result<String, SyncError> {
     let token_path = google_token_path(config);
     if !token_path.exists() {
         let session = start_local_oauth_flow(&config.google)?;
-        return Err(SyncError::AuthRequired(session));
+        return Err(SyncError::AuthRequired(Box::new(session)));
     }
 
     let stored = load_token(&token_path)?;
@@ -404,7 +416,7 @@ fn ensure_access_token(config: &Config) -> Result<String, SyncError> {
         }
         Err(_) => {
             let session = start_local_oauth_flow(&config.google)?;
-            Err(SyncError::AuthRequired(session))
+            Err(SyncError::AuthRequired(Box::new(session)))
         }
     }
 }
@@ -732,6 +744,51 @@ enum ConflictPolicy {
     PreferRemote,
 }
 
+fn cancelled_task_policy(value: &str) -> CancelledTaskPolicy {
+    match value.trim().to_lowercase().as_str() {
+        "delete" => CancelledTaskPolicy::Delete,
+        _ => CancelledTaskPolicy::Complete,
+    }
+}
+
+/// What happens in Google to tasks cancelled locally (`- [-]`). Google has no cancelled state.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CancelledTaskPolicy {
+    Complete,
+    Delete,
+}
+
+/// Whether `item` is a cancelled task that should be removed from Google rather than synced.
+fn is_deleted_cancelled(item: &AgendaItem, policy: CancelledTaskPolicy) -> bool {
+    policy == CancelledTaskPolicy::Delete
+        && item.kind == AgendaItemKind::Task
+        && item.status == TaskStatus::Cancelled
+}
+
+/// Local status after applying Google's completed flag. States Google cannot represent
+/// (in progress, deferred, cancelled) are kept while the flag still agrees with them.
+fn status_from_remote(local: TaskStatus, remote: &RemoteTask) -> TaskStatus {
+    let completed = remote.status.as_deref() == Some("completed");
+    match (completed, local.is_closed()) {
+        (true, false) => TaskStatus::Done,
+        (false, true) => TaskStatus::Open,
+        _ => local,
+    }
+}
+
+fn remote_task_status(remote: &RemoteTask) -> TaskStatus {
+    status_from_remote(TaskStatus::Open, remote)
+}
+
+/// Google Tasks status for a local task; cancelled tasks that are kept count as completed.
+fn google_task_status(status: TaskStatus) -> String {
+    if status.is_closed() {
+        "completed".to_string()
+    } else {
+        "needsAction".to_string()
+    }
+}
+
 fn sync_tasks(
     config: &Config,
     client: &Client,
@@ -742,6 +799,8 @@ fn sync_tasks(
     policy: ConflictPolicy,
     report: &mut SyncReport,
 ) -> Result<(), SyncError> {
+    let cancelled_policy = cancelled_task_policy(&config.google.cancelled_tasks);
+    let mut deleted: HashSet<String> = HashSet::new();
     let mut remote_by_id = HashMap::new();
     for item in remote_items {
         remote_by_id.insert(item.id.clone(), item.clone());
@@ -769,12 +828,33 @@ fn sync_tasks(
         let hash = task_hash(item);
         let stored = match state.tasks.get(&key).cloned() {
             Some(entry) => Some(entry),
-            None => rekey_state_by_hash(&mut state.tasks, &key, &hash),
+            None => {
+                let line_key = local_task_key_for_path(
+                    Path::new(&item.file_path),
+                    item.line_number,
+                    None,
+                );
+                rekey_state(&mut state.tasks, &key, &line_key)
+                    .or_else(|| rekey_state_by_hash(&mut state.tasks, &key, &hash))
+            }
         };
         let remote = stored
             .as_ref()
             .and_then(|entry| remote_by_id.get(&entry.google_id));
         let match_key = task_match_key(&item.text, &item.schedule);
+        if is_deleted_cancelled(item, cancelled_policy) {
+            let remote_id = match remote {
+                Some(remote) => Some(remote.id.clone()),
+                None => take_match(&mut remote_match, &match_key).map(|remote| remote.id),
+            };
+            if let Some(remote_id) = remote_id {
+                delete_remote_task(client, access_token, &config.google.tasks_list_id, &remote_id)?;
+                report.tasks_deleted += 1;
+                deleted.insert(remote_id);
+            }
+            state.tasks.remove(&key);
+            continue;
+        }
         if stored.is_none() || remote.is_none() {
             if let Some(matched_remote) = take_match(&mut remote_match, &match_key) {
                 let remote_hash = task_hash_from_remote(&matched_remote);
@@ -968,6 +1048,9 @@ fn sync_tasks(
     }
 
     for remote in remote_items {
+        if deleted.contains(&remote.id) {
+            continue;
+        }
         let exists = state
             .tasks
             .values()
@@ -975,7 +1058,7 @@ fn sync_tasks(
         if !exists {
             let update = TaskLineUpdate {
                 text: remote.title.clone().unwrap_or_else(|| "Untitled task".to_string()),
-                is_done: remote.status.as_deref() == Some("completed"),
+                status: remote_task_status(remote),
                 priority: None,
                 schedule: schedule_from_remote_task(remote),
             };
@@ -983,8 +1066,8 @@ fn sync_tasks(
             let date = schedule_anchor_date(&update.schedule)
                 .unwrap_or_else(|| Local::now().date_naive());
             let log_file = log_path_for_date(&config.data.log_path, date);
-            if let Some(line_number) = find_last_line_number(&log_file, &line) {
-                let key = local_task_key_for_path(&log_file, line_number);
+            if let Some((line_number, block_id)) = find_last_line(&log_file, &line) {
+                let key = local_task_key_for_path(&log_file, line_number, block_id.as_deref());
                 state.tasks.insert(
                     key,
                     SyncItem {
@@ -997,8 +1080,10 @@ fn sync_tasks(
             }
             storage::append_entry_to_date(&config.data.log_path, date, &line)?;
             report.tasks_imported += 1;
-            let key = find_last_line_number(&log_file, &line)
-                .map(|line_number| local_task_key_for_path(&log_file, line_number))
+            let key = find_last_line(&log_file, &line)
+                .map(|(line_number, block_id)| {
+                    local_task_key_for_path(&log_file, line_number, block_id.as_deref())
+                })
                 .unwrap_or_else(|| local_key_for_import(&config.data.log_path, date, &line));
             state.tasks.insert(
                 key,
@@ -1026,6 +1111,8 @@ fn sync_events(
     policy: ConflictPolicy,
     report: &mut SyncReport,
 ) -> Result<(), SyncError> {
+    let cancelled_policy = cancelled_task_policy(&config.google.cancelled_tasks);
+    let mut deleted: HashSet<String> = HashSet::new();
     let mut remote_by_id = HashMap::new();
     for item in remote_items {
         remote_by_id.insert(item.id.clone(), item.clone());
@@ -1065,7 +1152,15 @@ fn sync_events(
         let out_of_range = item.date < remote_start || item.date > remote_end;
         let mut stored = match state.events.get(&key).cloned() {
             Some(entry) => Some(entry),
-            None => rekey_state_by_hash(&mut state.events, &key, &hash),
+            None => {
+                let line_key = local_event_key_for_path(
+                    Path::new(&item.file_path),
+                    item.line_number,
+                    None,
+                );
+                rekey_state(&mut state.events, &key, &line_key)
+                    .or_else(|| rekey_state_by_hash(&mut state.events, &key, &hash))
+            }
         };
         let mut remote = stored
             .as_ref()
@@ -1107,6 +1202,16 @@ fn sync_events(
             }
         }
 
+        if is_deleted_cancelled(item, cancelled_policy) {
+            if let Some(remote) = remote {
+                delete_remote_event(client, access_token, &config.google.calendar_id, &remote.id)?;
+                report.events_deleted += 1;
+                deleted.insert(remote.id.clone());
+            }
+            state.events.remove(&key);
+            continue;
+        }
+
         match (stored, remote) {
             (Some(entry), Some(remote)) => {
                 let local_changed = entry.hash != hash;
@@ -1268,6 +1373,9 @@ fn sync_events(
     }
 
     for remote in remote_items {
+        if deleted.contains(&remote.id) {
+            continue;
+        }
         let exists = state
             .events
             .values()
@@ -1284,8 +1392,8 @@ fn sync_events(
             let date = schedule_anchor_date(&update.schedule)
                 .unwrap_or_else(|| Local::now().date_naive());
             let log_file = log_path_for_date(&config.data.log_path, date);
-            if let Some(line_number) = find_last_line_number(&log_file, &line) {
-                let key = local_event_key_for_path(&log_file, line_number);
+            if let Some((line_number, block_id)) = find_last_line(&log_file, &line) {
+                let key = local_event_key_for_path(&log_file, line_number, block_id.as_deref());
                 state.events.insert(
                     key,
                     SyncItem {
@@ -1298,8 +1406,10 @@ fn sync_events(
             }
             storage::append_entry_to_date(&config.data.log_path, date, &line)?;
             report.events_imported += 1;
-            let key = find_last_line_number(&log_file, &line)
-                .map(|line_number| local_event_key_for_path(&log_file, line_number))
+            let key = find_last_line(&log_file, &line)
+                .map(|(line_number, block_id)| {
+                    local_event_key_for_path(&log_file, line_number, block_id.as_deref())
+                })
                 .unwrap_or_else(|| local_key_for_import(&config.data.log_path, date, &line));
             state.events.insert(
                 key,
@@ -1394,12 +1504,9 @@ fn update_remote_task(
     let due = schedule_to_task_due(&item.schedule);
     let update = TaskUpdateRequest {
         title: item.text.clone(),
-        status: if item.is_done {
-            "completed".to_string()
-        } else {
-            "needsAction".to_string()
-        },
+        status: google_task_status(item.status),
         due,
+        completed: task_completed_timestamp(item),
     };
     let resp = client
         .patch(url)
@@ -1429,12 +1536,9 @@ fn create_remote_task(
     let due = schedule_to_task_due(&item.schedule);
     let update = TaskUpdateRequest {
         title: item.text.clone(),
-        status: if item.is_done {
-            "completed".to_string()
-        } else {
-            "needsAction".to_string()
-        },
+        status: google_task_status(item.status),
         due,
+        completed: task_completed_timestamp(item),
     };
     let resp = client
         .post(url)
@@ -1458,28 +1562,81 @@ fn apply_remote_task_update(
     local: &AgendaItem,
     remote: &RemoteTask,
 ) -> Result<TaskLineUpdate, SyncError> {
-    let schedule = schedule_from_remote_task(remote);
+    let mut schedule = schedule_from_remote_task(remote);
+    // Google has no equivalent of `@every`, so the local rule is kept.
+    schedule.recurrence = local.schedule.recurrence.clone();
+    // A local `@done` stamp is more precise than Google's, which may be missing.
+    if local.schedule.done.is_some() || !storage::done_timestamps_enabled() {
+        schedule.done = local.schedule.done;
+        schedule.done_time = local.schedule.done_time;
+    } else if schedule.done.is_none() && status_from_remote(local.status, remote).is_done() {
+        let now = Local::now().naive_local();
+        schedule.done = Some(now.date());
+        schedule.done_time = Some(now.time());
+    }
     let update = TaskLineUpdate {
         text: remote
             .title
             .clone()
             .unwrap_or_else(|| local.text.clone()),
-        is_done: remote.status.as_deref() == Some("completed"),
+        status: status_from_remote(local.status, remote),
         priority: local.priority,
         schedule: schedule.clone(),
     };
-    storage::update_task_line(&local.file_path, local.line_number, update)?;
+    let identity = storage::text_identity(&local.text);
+    storage::update_task_line(&local.file_path, local.line_number, &identity, update)?;
     Ok(TaskLineUpdate {
         text: remote
             .title
             .clone()
             .unwrap_or_else(|| local.text.clone()),
-        is_done: remote.status.as_deref() == Some("completed"),
+        status: status_from_remote(local.status, remote),
         priority: local.priority,
         schedule,
     })
 }
 
+fn delete_remote_task(
+    client: &Client,
+    access_token: &str,
+    task_list_id: &str,
+    task_id: &str,
+) -> Result<(), SyncError> {
+    let url = format!("{TASKS_API}/lists/{task_list_id}/tasks/{task_id}");
+    delete_remote(client, access_token, &url, "Task")
+}
+
+fn delete_remote_event(
+    client: &Client,
+    access_token: &str,
+    calendar_id: &str,
+    event_id: &str,
+) -> Result<(), SyncError> {
+    let url = format!("{CALENDAR_API}/calendars/{calendar_id}/events/{event_id}");
+    delete_remote(client, access_token, &url, "Event")
+}
+
+/// Sends a DELETE; an item that is already gone counts as deleted.
+fn delete_remote(
+    client: &Client,
+    access_token: &str,
+    url: &str,
+    kind: &str,
+) -> Result<(), SyncError> {
+    let resp = client
+        .delete(url)
+        .bearer_auth(access_token)
+        .send()
+        .map_err(|e| SyncError::Request(e.to_string()))?;
+    let status = resp.status();
+    if status.is_success() || status.as_u16() == 404 || status.as_u16() == 410 {
+        return Ok(());
+    }
+    Err(SyncError::Request(format!(
+        "{kind} delete failed: HTTP {status}"
+    )))
+}
+
 fn update_remote_event(
     client: &Client,
     access_token: &str,
@@ -1558,48 +1715,67 @@ fn apply_remote_event_update(
     local: &AgendaItem,
     remote: &RemoteEvent,
 ) -> Result<NoteLineUpdate, SyncError> {
-    let schedule = schedule_from_remote_event(remote);
+    let mut schedule = schedule_from_remote_event(remote);
+    schedule.recurrence = local.schedule.recurrence.clone();
+    schedule.done = local.schedule.done;
+    schedule.done_time = local.schedule.done_time;
     let raw_text = remote.summary.clone().unwrap_or_else(|| local.text.clone());
     let text = normalize_event_text(&raw_text);
+    let identity = storage::text_identity(&local.text);
     match local.kind {
         AgendaItemKind::Task => {
             let update = TaskLineUpdate {
                 text: text.clone(),
-                is_done: local.is_done,
+                status: local.status,
                 priority: local.priority,
                 schedule: schedule.clone(),
             };
-            storage::update_task_line(&local.file_path, local.line_number, update)?;
+            storage::update_task_line(&local.file_path, local.line_number, &identity, update)?;
         }
         _ => {
             let update = NoteLineUpdate {
                 text: text.clone(),
                 schedule: schedule.clone(),
             };
-            storage::update_note_line(&local.file_path, local.line_number, update)?;
+            storage::update_note_line(&local.file_path, local.line_number, &identity, update)?;
         }
     }
     Ok(NoteLineUpdate { text, schedule })
 }
 
 fn local_task_key(item: &AgendaItem) -> String {
-    local_task_key_for_path(Path::new(&item.file_path), item.line_number)
+    local_task_key_for_path(
+        Path::new(&item.file_path),
+        item.line_number,
+        item.block_id.as_deref(),
+    )
 }
 
 fn local_event_key(item: &AgendaItem) -> String {
-    local_event_key_for_path(Path::new(&item.file_path), item.line_number)
+    local_event_key_for_path(
+        Path::new(&item.file_path),
+        item.line_number,
+        item.block_id.as_deref(),
+    )
 }
 
-fn local_task_key_for_path(path: &Path, line_number: usize) -> String {
-    format!("task:{}:{}", path.to_string_lossy(), line_number)
+/// Items with a block ID are keyed by it, so their sync state survives lines moving.
+fn local_task_key_for_path(path: &Path, line_number: usize, block_id: Option<&str>) -> String {
+    match block_id {
+        Some(id) => format!("task:^{id}"),
+        None => format!("task:{}:{}", path.to_string_lossy(), line_number),
+    }
 }
 
-fn local_event_key_for_path(path: &Path, line_number: usize) -> String {
-    format!("event:{}:{}", path.to_string_lossy(), line_number)
+fn local_event_key_for_path(path: &Path, line_number: usize, block_id: Option<&str>) -> String {
+    match block_id {
+        Some(id) => format!("event:^{id}"),
+        None => format!("event:{}:{}", path.to_string_lossy(), line_number),
+    }
 }
 
 fn local_key_for_import(log_path: &Path, date: NaiveDate, line: &str) -> String {
-    let path = log_path.join(format!("{}.md", date.format("%Y-%m-%d")));
+    let path = log_path_for_date(log_path, date);
     format!("import:{}:{}", path.to_string_lossy(), stable_hash(line))
 }
 
@@ -1643,6 +1819,21 @@ fn remove_event_match(
     }
 }
 
+/// Moves state stored under `old_key` (e.g. a line-number key from before the item got a
+/// block ID) to `key`.
+fn rekey_state(
+    state: &mut HashMap<String, SyncItem>,
+    key: &str,
+    old_key: &str,
+) -> Option<SyncItem> {
+    if key == old_key {
+        return None;
+    }
+    let entry = state.remove(old_key)?;
+    state.insert(key.to_string(), entry.clone());
+    Some(entry)
+}
+
 fn rekey_state_by_hash(
     state: &mut HashMap<String, SyncItem>,
     key: &str,
@@ -1679,16 +1870,7 @@ fn normalize_event_text(text: &str) -> String {
 }
 
 fn strip_task_checkbox_prefix(text: &str) -> &str {
-    if let Some(rest) = text.strip_prefix("- [ ] ") {
-        return rest;
-    }
-    if let Some(rest) = text.strip_prefix("- [x] ") {
-        return rest;
-    }
-    if let Some(rest) = text.strip_prefix("- [X] ") {
-        return rest;
-    }
-    text
+    split_task_checkbox(text).map_or(text, |(_, rest)| rest)
 }
 
 fn strip_list_prefix(text: &str) -> &str {
@@ -1752,7 +1934,10 @@ fn task_hash(item: &AgendaItem) -> String {
     let priority = item.priority.map(|p| p.as_char()).unwrap_or('-');
     stable_hash(&format!(
         "{}|{}|{}|{}",
-        item.text, item.is_done, priority, schedule
+        item.text,
+        item.status.is_closed(),
+        priority,
+        schedule
     ))
 }
 
@@ -1767,7 +1952,10 @@ fn task_hash_from_update(update: &TaskLineUpdate) -> String {
     let priority = update.priority.map(|p| p.as_char()).unwrap_or('-');
     stable_hash(&format!(
         "{}|{}|{}|{}",
-        update.text, update.is_done, priority, schedule
+        update.text,
+        update.status.is_closed(),
+        priority,
+        schedule
     ))
 }
 
@@ -1783,7 +1971,7 @@ fn task_hash_from_remote(remote: &RemoteTask) -> String {
             .title
             .clone()
             .unwrap_or_else(|| "Untitled task".to_string()),
-        is_done: remote.status.as_deref() == Some("completed"),
+        status: remote_task_status(remote),
         priority: None,
         schedule: schedule_from_remote_task(remote),
     };
@@ -1930,9 +2118,29 @@ fn schedule_from_remote_task(remote: &RemoteTask) -> TaskSchedule {
             schedule.time = Some(dt.time());
         }
     }
+    if let Some(completed) = remote.completed.as_deref()
+        && storage::done_timestamps_enabled()
+        && let Ok(dt) = DateTime::parse_from_rfc3339(completed)
+    {
+        let local = dt.with_timezone(&Local).naive_local();
+        schedule.done = Some(local.date());
+        schedule.done_time = Some(local.time());
+    }
     schedule
 }
 
+/// Google's `completed` timestamp for a done task with a `@done` stamp. A date-only stamp
+/// counts as local midnight.
+fn task_completed_timestamp(item: &AgendaItem) -> Option<String> {
+    if !item.status.is_closed() {
+        return None;
+    }
+    let done = item.schedule.done?;
+    let time = item.schedule.done_time.unwrap_or(NaiveTime::MIN);
+    let local = Local.from_local_datetime(&done.and_time(time)).earliest()?;
+    Some(local.with_timezone(&Utc).to_rfc3339())
+}
+
 fn schedule_from_remote_event(remote: &RemoteEvent) -> TaskSchedule {
     let mut schedule = TaskSchedule::default();
     if let Some(start) = &remote.start {
@@ -1970,18 +2178,20 @@ fn schedule_anchor_date(schedule: &TaskSchedule) -> Option<NaiveDate> {
 }
 
 fn log_path_for_date(log_path: &Path, date: NaiveDate) -> PathBuf {
-    log_path.join(format!("{}.md", date.format("%Y-%m-%d")))
+    storage::get_file_path_for_date(log_path, &date.format("%Y-%m-%d").to_string())
 }
 
-fn find_last_line_number(path: &Path, line: &str) -> Option<usize> {
+/// Finds the last line equal to `line`, ignoring block IDs; returns its index and block ID.
+fn find_last_line(path: &Path, line: &str) -> Option<(usize, Option<String>)> {
     let content = fs::read_to_string(path).ok()?;
-    let mut last_index = None;
+    let mut last = None;
     for (idx, existing) in content.lines().enumerate() {
-        if existing == line {
-            last_index = Some(idx);
+        let (text, block_id) = split_block_id(existing);
+        if text == line {
+            last = Some((idx, block_id.map(str::to_string)));
         }
     }
-    last_index
+    last
 }
 
 fn to_rfc3339(date: NaiveDate, time: NaiveTime) -> String {
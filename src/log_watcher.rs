@@ -0,0 +1,127 @@
+//! Polling watcher for the log directory.
+//!
+//! MemoLog re-reads files after its own writes, but edits made in another editor (or an
+//! Obsidian sync) only show up when something triggers a reload. The watcher keeps an
+//! mtime/size snapshot of every log file, nested folders included, and reports which ones
+//! changed since the last poll.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::file_layout;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+type Snapshot = BTreeMap<String, (u64, u64)>;
+
+pub struct LogWatcher {
+    log_path: PathBuf,
+    snapshot: Snapshot,
+    last_poll: Option<Instant>,
+}
+
+impl LogWatcher {
+    pub fn new(log_path: &Path) -> Self {
+        Self {
+            log_path: log_path.to_path_buf(),
+            snapshot: scan(log_path),
+            last_poll: Some(Instant::now()),
+        }
+    }
+
+    /// Accepts the current state of the directory as known, e.g. after MemoLog wrote to it.
+    /// Call it before re-reading the files, not after, so edits that land mid-read are kept.
+    pub fn sync(&mut self) {
+        self.snapshot = scan(&self.log_path);
+    }
+
+    /// Returns paths of files created, modified or removed since the last poll or sync.
+    /// Polls at most once per `POLL_INTERVAL`; earlier calls return nothing.
+    pub fn poll(&mut self) -> Vec<String> {
+        if self
+            .last_poll
+            .is_some_and(|last| last.elapsed() < POLL_INTERVAL)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(Instant::now());
+        self.poll_now()
+    }
+
+    fn poll_now(&mut self) -> Vec<String> {
+        let current = scan(&self.log_path);
+        let mut changed: Vec<String> = current
+            .iter()
+            .filter(|(path, stamp)| self.snapshot.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            self.snapshot
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .cloned(),
+        );
+        self.snapshot = current;
+        changed
+    }
+}
+
+fn scan(log_path: &Path) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    let Ok(paths) = file_layout::markdown_files(log_path) else {
+        return snapshot;
+    };
+    for path in paths {
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        let mtime_ns = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        snapshot.insert(
+            path.to_string_lossy().to_string(),
+            (mtime_ns, metadata.len()),
+        );
+    }
+    snapshot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poll_reports_external_changes() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "memolog-watch-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let day = dir.join("2025-01-01.md");
+        fs::write(&day, "## [09:00:00]\nAlpha\n").expect("write day");
+
+        let mut watcher = LogWatcher::new(&dir);
+        assert!(watcher.poll_now().is_empty());
+
+        fs::write(&day, "## [09:00:00]\nAlpha edited elsewhere\n").expect("rewrite day");
+        fs::write(dir.join("notes.txt"), "ignored").expect("write other");
+        assert_eq!(watcher.poll_now(), vec![day.to_string_lossy().to_string()]);
+
+        fs::write(&day, "## [09:00:00]\nWritten by memolog\n").expect("rewrite day");
+        watcher.sync();
+        assert!(watcher.poll_now().is_empty());
+
+        fs::remove_file(&day).expect("remove day");
+        assert_eq!(watcher.poll_now(), vec![day.to_string_lossy().to_string()]);
+    }
+}
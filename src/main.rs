@@ -1,4 +1,4 @@
-//! Main entrypoint: terminal lifecycle, run loop, UI draw, and delegation.
+//! Main entrypoint: CLI dispatch, terminal lifecycle, run loop, UI draw, and delegation.
 
 use crossterm::{
     event::{
@@ -16,13 +16,23 @@ use std::{error::Error, io};
 
 mod actions;
 mod app;
+mod backups;
+mod block_ids;
+mod cli;
 mod config;
 mod date_input;
 mod editor;
+mod file_layout;
 mod integrations;
 mod input;
+mod log_watcher;
 mod models;
+mod quick_add;
+mod recurrence;
 mod runtime;
+mod search_index;
+mod search_query;
+mod search_rank;
 mod storage;
 mod task_metadata;
 mod ui;
@@ -30,7 +40,13 @@ mod ui;
 use app::App;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     let mut app = App::new();
+    runtime::resume_pomodoro(&mut app);
 
     // Initialize terminal
     enable_raw_mode()?;
@@ -77,11 +93,22 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<
         if app.pomodoro_alert_expiry.is_some() {
             if event::poll(std::time::Duration::from_millis(100))? {
                 let ev = event::read()?;
-                // Allow Escape key to dismiss the alert early
+                // Escape dismisses the alert early (skipping an offered break); Enter starts it
                 if let crossterm::event::Event::Key(key) = ev {
-                    if key.code == crossterm::event::KeyCode::Esc {
-                        app.pomodoro_alert_expiry = None;
-                        app.pomodoro_alert_message = None;
+                    match key.code {
+                        crossterm::event::KeyCode::Esc => {
+                            app.pomodoro_alert_expiry = None;
+                            app.pomodoro_alert_message = None;
+                            app.pomodoro_pending_break = None;
+                        }
+                        crossterm::event::KeyCode::Enter => {
+                            if let Some(phase) = app.pomodoro_pending_break {
+                                app.pomodoro_alert_expiry = None;
+                                app.pomodoro_alert_message = None;
+                                actions::start_pomodoro_break(app, phase);
+                            }
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -1,4 +1,16 @@
 use chrono::{NaiveDate, NaiveTime};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::block_ids::split_block_id;
+use crate::recurrence::Recurrence;
+
+pub const DEFAULT_ENTRY_HEADING: &str = "## [{time}]";
+pub const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+static HEADING_FORMAT: RwLock<Option<HeadingFormat>> = RwLock::new(None);
 
 #[derive(PartialEq)]
 pub enum InputMode {
@@ -14,6 +26,7 @@ pub enum DatePickerField {
     Start,
     Time,
     Duration,
+    Recurrence,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -37,7 +50,7 @@ pub enum NavigateFocus {
     Tasks,
 }
 
-#[derive(Clone, Copy, PartialEq, Default)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub enum TaskFilter {
     #[default]
     Open,
@@ -90,19 +103,50 @@ pub struct LogEntry {
     pub file_path: String,
     pub line_number: usize,
     pub end_line: usize,
+    /// Day the entry belongs to, from the file name or the enclosing day heading.
+    pub date: Option<NaiveDate>,
+}
+
+impl LogEntry {
+    /// Block ID on the entry's timestamp line, if it has one.
+    pub fn block_id(&self) -> Option<&str> {
+        let first_line = self.content.lines().next()?;
+        if !is_timestamped_line(first_line) {
+            return None;
+        }
+        split_block_id(first_line).1
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
-pub struct EntryIdentity {
-    pub file_path: String,
-    pub line_number: usize,
+pub enum EntryIdentity {
+    /// Block ID on the entry heading; stays valid when lines shift.
+    Block(String),
+    /// Position of an entry without a block ID.
+    Line { file_path: String, line_number: usize },
+}
+
+impl EntryIdentity {
+    /// Identity of the entry starting at `line_number` with heading line `first_line`.
+    pub fn new(file_path: &str, line_number: usize, first_line: &str) -> Self {
+        match split_block_id(first_line).1 {
+            Some(id) if is_timestamped_line(first_line) => Self::Block(id.to_string()),
+            _ => Self::Line {
+                file_path: file_path.to_string(),
+                line_number,
+            },
+        }
+    }
 }
 
 impl From<&LogEntry> for EntryIdentity {
     fn from(entry: &LogEntry) -> Self {
-        Self {
-            file_path: entry.file_path.clone(),
-            line_number: entry.line_number,
+        match entry.block_id() {
+            Some(id) => Self::Block(id.to_string()),
+            None => Self::Line {
+                file_path: entry.file_path.clone(),
+                line_number: entry.line_number,
+            },
         }
     }
 }
@@ -126,7 +170,7 @@ pub enum FoldOverride {
     Expanded,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct AgendaItem {
     pub kind: AgendaItemKind,
     pub date: NaiveDate,
@@ -134,43 +178,282 @@ pub struct AgendaItem {
     pub duration_minutes: Option<u32>,
     pub text: String,
     pub indent: usize,
-    pub is_done: bool,
+    pub status: TaskStatus,
     pub priority: Option<Priority>,
     pub schedule: TaskSchedule,
     pub file_path: String,
     pub line_number: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// A future occurrence of a recurring task, computed from its `@every` rule rather than
+    /// read from a line of its own.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub projected: bool,
+    /// Finished pomodoros (trailing 🍅) on the task line.
+    pub tomato_count: usize,
+    /// Day of the log section the line is in.
+    #[serde(skip)]
+    pub line_date: Option<NaiveDate>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+impl AgendaItem {
+    /// The task line this item was read from, for [`TimeSpent`].
+    pub fn task_instance<'a>(&'a self, task_identity: &'a str) -> TaskInstance<'a> {
+        TaskInstance::new(
+            &self.file_path,
+            task_identity,
+            self.block_id.as_deref(),
+            self.line_date,
+            &self.schedule,
+        )
+    }
+
+    fn open_task_due(&self) -> Option<NaiveDate> {
+        let open = self.kind == AgendaItemKind::Task && !self.status.is_closed();
+        self.schedule.due.filter(|_| open)
+    }
+
+    /// Open task whose `@due` date is before `day`.
+    pub fn is_overdue_on(&self, day: NaiveDate) -> bool {
+        self.open_task_due().is_some_and(|due| due < day)
+    }
+
+    /// Open task due after `day` whose warning period (`@due(date, -3d)`, or
+    /// `default_warning_days`) has started by `day`.
+    pub fn is_deadline_upcoming(&self, day: NaiveDate, default_warning_days: u32) -> bool {
+        let Some(due) = self.open_task_due() else {
+            return false;
+        };
+        let warning = self.schedule.due_warning_days.unwrap_or(default_warning_days);
+        due > day && due - chrono::Duration::days(warning as i64) <= day
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AgendaItemKind {
     Task,
     Note,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct TaskItem {
     pub text: String,
     pub indent: usize,
     pub tomato_count: usize,
     pub file_path: String,
     pub line_number: usize,
-    pub is_done: bool,
+    pub status: TaskStatus,
     pub priority: Option<Priority>,
     pub schedule: TaskSchedule,
     pub task_identity: String,
     pub carryover_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// Day of the log section the task was written in.
+    pub date: Option<NaiveDate>,
+}
+
+impl TaskItem {
+    /// This task line, for [`TimeSpent`].
+    pub fn task_instance(&self) -> TaskInstance<'_> {
+        TaskInstance::new(
+            &self.file_path,
+            &self.task_identity,
+            self.block_id.as_deref(),
+            self.date,
+            &self.schedule,
+        )
+    }
 }
 
-#[derive(Clone, Default, Debug, PartialEq, Eq)]
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize)]
 pub struct TaskSchedule {
     pub scheduled: Option<NaiveDate>,
     pub due: Option<NaiveDate>,
+    /// Days before `due` that the task shows up as an upcoming deadline (`@due(date, -3d)`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_warning_days: Option<u32>,
     pub start: Option<NaiveDate>,
     pub time: Option<NaiveTime>,
     pub duration_minutes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+    /// Completion date and time from `@done(...)` or `✅`. Not part of the schedule for
+    /// agenda purposes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done_time: Option<NaiveTime>,
+    /// Planned effort from `@est(...)`. Not part of the schedule for agenda purposes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<Estimate>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Planned effort: `@est(4🍅)` in pomodoros or `@est(2h)` in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Estimate {
+    Pomodoros(u32),
+    Minutes(u32),
+}
+
+impl Estimate {
+    /// `spent/estimate` for a task with `tomatoes` finished pomodoros: `2/4🍅`, or `50m/2h`
+    /// for a time estimate, measured in `spent_minutes` (see [`TimeSpent`]). The flag is set
+    /// once the task has gone over its estimate.
+    pub fn progress(self, tomatoes: usize, spent_minutes: u32) -> (String, bool) {
+        match self {
+            Estimate::Pomodoros(planned) => {
+                let tomatoes = u32::try_from(tomatoes).unwrap_or(u32::MAX);
+                (format!("{tomatoes}/{planned}🍅"), tomatoes > planned)
+            }
+            Estimate::Minutes(planned) => {
+                let label = format!("{}/{}", format_minutes(spent_minutes), format_minutes(planned));
+                (label, spent_minutes > planned)
+            }
+        }
+    }
+
+    /// [`progress`](Self::progress) in brackets: `[2/4🍅]`, or `[5/4🍅 over]`.
+    pub fn badge(self, tomatoes: usize, spent_minutes: u32) -> String {
+        match self.progress(tomatoes, spent_minutes) {
+            (progress, true) => format!("[{progress} over]"),
+            (progress, false) => format!("[{progress}]"),
+        }
+    }
+}
+
+/// One task line, as looked up in [`TimeSpent`].
+#[derive(Clone, Copy, Debug)]
+pub struct TaskInstance<'a> {
+    pub file_path: &'a str,
+    pub task_identity: &'a str,
+    pub block_id: Option<&'a str>,
+    /// Day this copy of the task starts: sessions before it were spent on an earlier copy
+    /// (the line it was carried over from, or a previous occurrence of a recurring task).
+    pub since: Option<NaiveDate>,
+}
+
+impl<'a> TaskInstance<'a> {
+    /// `line_date` is the day of the log section the task line is in. A recurring task starts
+    /// on its own occurrence, since every occurrence is written under the day it was completed.
+    pub fn new(
+        file_path: &'a str,
+        task_identity: &'a str,
+        block_id: Option<&'a str>,
+        line_date: Option<NaiveDate>,
+        schedule: &TaskSchedule,
+    ) -> Self {
+        let occurrence = schedule
+            .recurrence
+            .as_ref()
+            .and(schedule.scheduled.or(schedule.due));
+        Self {
+            file_path,
+            task_identity,
+            block_id,
+            since: line_date.max(occurrence),
+        }
+    }
+}
+
+/// Recorded minutes and completed sessions.
+#[derive(Clone, Copy, Debug, Default)]
+struct Recorded {
+    minutes: u32,
+    completed: u32,
+}
+
+impl Recorded {
+    fn add(&mut self, other: Recorded) {
+        self.minutes = self.minutes.saturating_add(other.minutes);
+        self.completed = self.completed.saturating_add(other.completed);
+    }
+}
+
+/// Time worked on each task line, for time estimates. Sessions in `pomodoro.jsonl` count with
+/// the minutes they ran; tomatoes without a recorded session (older ones, or carried over with
+/// the task) count as `work_minutes` each.
+///
+/// Sessions are keyed by the task's block ID when it has one, otherwise by file and task
+/// identity, limited to sessions started on or after the task's [`TaskInstance::since`] day.
+#[derive(Clone, Debug, Default)]
+pub struct TimeSpent {
+    work_minutes: u64,
+    by_block: HashMap<String, Recorded>,
+    by_task: HashMap<(String, String), Vec<(NaiveDate, Recorded)>>,
+}
+
+impl TimeSpent {
+    pub fn new(work_minutes: u64) -> Self {
+        Self {
+            work_minutes,
+            ..Self::default()
+        }
+    }
+
+    /// Adds a work session on `task` that started on `started` and ran for `minutes`.
+    /// `completed` sessions are the ones that added a tomato to the task line.
+    pub fn record(
+        &mut self,
+        task: &TaskInstance,
+        started: NaiveDate,
+        minutes: u32,
+        completed: bool,
+    ) {
+        let recorded = Recorded {
+            minutes,
+            completed: u32::from(completed),
+        };
+        match task.block_id {
+            Some(block_id) => self
+                .by_block
+                .entry(block_id.to_string())
+                .or_default()
+                .add(recorded),
+            None => self
+                .by_task
+                .entry((task.file_path.to_string(), task.task_identity.to_string()))
+                .or_default()
+                .push((started, recorded)),
+        }
+    }
+
+    /// Minutes spent on `task`, which has `tomatoes` finished pomodoros.
+    pub fn minutes(&self, task: &TaskInstance, tomatoes: usize) -> u32 {
+        let mut recorded = task
+            .block_id
+            .and_then(|block_id| self.by_block.get(block_id))
+            .copied()
+            .unwrap_or_default();
+        let key = (task.file_path.to_string(), task.task_identity.to_string());
+        for (started, session) in self.by_task.get(&key).into_iter().flatten() {
+            if task.since.is_none_or(|since| *started >= since) {
+                recorded.add(*session);
+            }
+        }
+
+        let untracked = u32::try_from(tomatoes)
+            .unwrap_or(u32::MAX)
+            .saturating_sub(recorded.completed);
+        let per_tomato = u32::try_from(self.work_minutes).unwrap_or(u32::MAX);
+        recorded
+            .minutes
+            .saturating_add(untracked.saturating_mul(per_tomato))
+    }
+}
+
+fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, mins) => format!("{mins}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, mins) => format!("{hours}h{mins}m"),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Priority {
     High,
     Medium,
@@ -196,6 +479,93 @@ impl Priority {
     }
 }
 
+/// State of a task checkbox. Besides `[ ]` and `[x]`, the Obsidian Tasks markers `[/]`
+/// (in progress), `[-]` (cancelled) and `[>]` (deferred) are understood.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    #[default]
+    Open,
+    InProgress,
+    Done,
+    Cancelled,
+    Deferred,
+}
+
+impl TaskStatus {
+    pub fn from_marker(c: char) -> Option<Self> {
+        match c {
+            ' ' => Some(TaskStatus::Open),
+            '/' => Some(TaskStatus::InProgress),
+            'x' | 'X' => Some(TaskStatus::Done),
+            '-' => Some(TaskStatus::Cancelled),
+            '>' => Some(TaskStatus::Deferred),
+            _ => None,
+        }
+    }
+
+    pub fn marker(self) -> char {
+        match self {
+            TaskStatus::Open => ' ',
+            TaskStatus::InProgress => '/',
+            TaskStatus::Done => 'x',
+            TaskStatus::Cancelled => '-',
+            TaskStatus::Deferred => '>',
+        }
+    }
+
+    /// The `- [?] ` prefix written for this status.
+    pub fn checkbox(self) -> &'static str {
+        match self {
+            TaskStatus::Open => "- [ ] ",
+            TaskStatus::InProgress => "- [/] ",
+            TaskStatus::Done => "- [x] ",
+            TaskStatus::Cancelled => "- [-] ",
+            TaskStatus::Deferred => "- [>] ",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskStatus::Open => "open",
+            TaskStatus::InProgress => "in progress",
+            TaskStatus::Done => "done",
+            TaskStatus::Cancelled => "cancelled",
+            TaskStatus::Deferred => "deferred",
+        }
+    }
+
+    pub fn is_done(self) -> bool {
+        self == TaskStatus::Done
+    }
+
+    /// Done and cancelled tasks need no more work: they are not carried over and count as
+    /// closed in filters.
+    pub fn is_closed(self) -> bool {
+        matches!(self, TaskStatus::Done | TaskStatus::Cancelled)
+    }
+
+    /// Next state for the status-cycle key. Completing stays on the toggle key so that
+    /// recurring tasks and carryover chains are handled in one place.
+    pub fn cycle(self) -> Self {
+        match self {
+            TaskStatus::Open => TaskStatus::InProgress,
+            TaskStatus::InProgress => TaskStatus::Deferred,
+            TaskStatus::Deferred => TaskStatus::Cancelled,
+            TaskStatus::Cancelled | TaskStatus::Done => TaskStatus::Open,
+        }
+    }
+}
+
+/// Splits a `- [?] ` checkbox off the start of `text`, returning its status and the rest.
+pub fn split_task_checkbox(text: &str) -> Option<(TaskStatus, &str)> {
+    let rest = text.strip_prefix("- [")?;
+    let mut chars = rest.chars();
+    let status = TaskStatus::from_marker(chars.next()?)?;
+    let rest = chars.as_str().strip_prefix("] ")?;
+    Some((status, rest))
+}
+
 impl TaskSchedule {
     pub fn is_empty(&self) -> bool {
         self.scheduled.is_none()
@@ -203,21 +573,274 @@ impl TaskSchedule {
             && self.start.is_none()
             && self.time.is_none()
             && self.duration_minutes.is_none()
+            && self.recurrence.is_none()
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
 pub enum PomodoroTarget {
     Task {
         text: String,
         file_path: String,
         line_number: usize,
+        task_identity: String,
+        /// Block ID (`^abc123`) of the task line, which its recorded sessions are keyed by.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        block_id: Option<String>,
     },
 }
 
+/// Which part of the pomodoro cycle the running timer belongs to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PomodoroPhase {
+    #[default]
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl PomodoroPhase {
+    /// The break that follows the `sessions`-th work session of the day: a long break every
+    /// `long_break_every` sessions (never, when that is 0), a short break otherwise.
+    pub fn break_after(sessions: u32, long_break_every: u64) -> Self {
+        if long_break_every > 0 && sessions > 0 && u64::from(sessions) % long_break_every == 0 {
+            PomodoroPhase::LongBreak
+        } else {
+            PomodoroPhase::ShortBreak
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PomodoroPhase::Work => "Work",
+            PomodoroPhase::ShortBreak => "Short break",
+            PomodoroPhase::LongBreak => "Long break",
+        }
+    }
+
+    pub fn is_break(self) -> bool {
+        self != PomodoroPhase::Work
+    }
+}
+
+/// Where a task line lives, enough for the storage layer to find it again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskRef {
+    pub file_path: String,
+    pub line_number: usize,
+    pub task_identity: String,
+}
+
+/// Actions offered by the bulk popup for the marked tasks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BulkAction {
+    Complete,
+    PriorityHigh,
+    PriorityMedium,
+    PriorityLow,
+    ClearPriority,
+    Reschedule,
+    AddTag,
+    Delete,
+}
+
+impl BulkAction {
+    pub const ALL: [BulkAction; 8] = [
+        BulkAction::Complete,
+        BulkAction::PriorityHigh,
+        BulkAction::PriorityMedium,
+        BulkAction::PriorityLow,
+        BulkAction::ClearPriority,
+        BulkAction::Reschedule,
+        BulkAction::AddTag,
+        BulkAction::Delete,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BulkAction::Complete => "Complete",
+            BulkAction::PriorityHigh => "Priority A",
+            BulkAction::PriorityMedium => "Priority B",
+            BulkAction::PriorityLow => "Priority C",
+            BulkAction::ClearPriority => "Clear priority",
+            BulkAction::Reschedule => "Reschedule...",
+            BulkAction::AddTag => "Add tag...",
+            BulkAction::Delete => "Delete",
+        }
+    }
+
+    /// Whether the action asks for input (or a confirmation) before it runs.
+    pub fn needs_prompt(self) -> bool {
+        matches!(
+            self,
+            BulkAction::Reschedule | BulkAction::AddTag | BulkAction::Delete
+        )
+    }
+}
+
+/// The task a defer action moves, taken from the Tasks or Agenda selection.
+#[derive(Clone, Debug)]
+pub struct DeferTarget {
+    pub text: String,
+    pub file_path: String,
+    pub line_number: usize,
+    pub task_identity: String,
+    /// Day the task currently sits on: its scheduled or due date, else the day it was logged.
+    pub date: NaiveDate,
+}
+
+/// How entry headings are written and recognised, from `data.entry_heading` (a template with
+/// a `{time}` placeholder, e.g. `### {time}` or `- {time}`) and `data.time_format` (strftime).
+#[derive(Clone, Debug)]
+pub struct HeadingFormat {
+    template: String,
+    time_format: String,
+    /// Matches a heading line; group 1 is the time. `None` for the built-in `## [HH:MM:SS]`.
+    matcher: Option<Regex>,
+}
+
+impl Default for HeadingFormat {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_ENTRY_HEADING.to_string(),
+            time_format: DEFAULT_TIME_FORMAT.to_string(),
+            matcher: None,
+        }
+    }
+}
+
+impl HeadingFormat {
+    pub fn new(template: &str, time_format: &str) -> Result<Self, String> {
+        if template == DEFAULT_ENTRY_HEADING && time_format == DEFAULT_TIME_FORMAT {
+            return Ok(Self::default());
+        }
+        let Some((before, after)) = template.split_once("{time}") else {
+            return Err(format!("entry heading `{template}` needs a `{{time}}` placeholder"));
+        };
+        if after.contains("{time}") || template.contains('\n') {
+            return Err(format!("entry heading `{template}` must be one line with one `{{time}}`"));
+        }
+        let time = time_format_regex(time_format)?;
+        let pattern = format!(
+            "^{}({time}){}(?:\\s+|$)",
+            regex::escape(before.trim_start()),
+            regex::escape(after)
+        );
+        Ok(Self {
+            template: template.to_string(),
+            time_format: time_format.to_string(),
+            matcher: Some(Regex::new(&pattern).map_err(|err| err.to_string())?),
+        })
+    }
+
+    /// Heading line for a new entry written at `time`.
+    pub fn heading(&self, time: NaiveTime) -> String {
+        self.template
+            .replace("{time}", &time.format(&self.time_format).to_string())
+    }
+
+    /// Identifies the template and time format, e.g. to invalidate caches parsed with another one.
+    pub fn key(&self) -> String {
+        format!("{}|{}", self.template, self.time_format)
+    }
+
+    /// Display width of a formatted time, for aligning the timeline.
+    pub fn time_width(&self) -> usize {
+        NaiveTime::from_hms_opt(12, 0, 0)
+            .map(|noon| noon.format(&self.time_format).to_string().chars().count())
+            .unwrap_or_default()
+    }
+
+    /// Splits a heading in this format into (time, rest of the line).
+    fn split<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str)> {
+        let matcher = self.matcher.as_ref()?;
+        let captures = matcher.captures(line)?;
+        let time = captures.get(1)?.as_str();
+        let end = captures.get(0)?.end();
+        Some((time, &line[end..]))
+    }
+}
+
+/// Regex for the text `format` produces. Only time-of-day specifiers are supported.
+fn time_format_regex(format: &str) -> Result<String, String> {
+    let mut regex = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            regex.push_str(&regex::escape(&c.to_string()));
+            continue;
+        }
+        let mut spec = chars.next();
+        let unpadded = spec == Some('-');
+        if unpadded {
+            spec = chars.next();
+        }
+        let part = match spec {
+            Some('H' | 'M' | 'S' | 'I') if unpadded => r"\d{1,2}",
+            Some('H' | 'M' | 'S' | 'I') => r"\d{2}",
+            Some('k' | 'l') => r"[ \d]\d",
+            Some('p') => "(?:AM|PM)",
+            Some('P') => "(?:am|pm)",
+            Some('R') => r"\d{2}:\d{2}",
+            Some('T') => r"\d{2}:\d{2}:\d{2}",
+            Some('%') => "%",
+            _ => return Err(format!("unsupported specifier in time format `{format}`")),
+        };
+        regex.push_str(part);
+    }
+    if regex.is_empty() {
+        return Err("time format is empty".to_string());
+    }
+    Ok(regex)
+}
+
+/// Sets the heading format used to write new entries and to recognise entry headings.
+pub fn configure_heading_format(format: HeadingFormat) {
+    let mut current = HEADING_FORMAT
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *current = Some(format);
+}
+
+pub fn heading_format() -> HeadingFormat {
+    with_heading_format(|format| format.cloned().unwrap_or_default())
+}
+
+fn with_heading_format<T>(f: impl FnOnce(Option<&HeadingFormat>) -> T) -> T {
+    #[cfg(test)]
+    if let Some(format) = TEST_HEADING_FORMAT.with(|format| format.borrow().clone()) {
+        return f(Some(&format));
+    }
+    f(HEADING_FORMAT
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .as_ref())
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_HEADING_FORMAT: std::cell::RefCell<Option<HeadingFormat>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Overrides the heading format for the calling test thread only.
+#[cfg(test)]
+pub fn set_test_heading_format(format: Option<HeadingFormat>) {
+    TEST_HEADING_FORMAT.with(|current| *current.borrow_mut() = format);
+}
+
 /// Returns the timestamp prefix and remaining text if present.
-/// Supports optional markdown heading markers like "## " before "[HH:MM:SS]".
+/// Recognises the configured heading format as well as the built-in "[HH:MM:SS]",
+/// with optional markdown heading markers like "## " before it, so files written in
+/// either style keep parsing.
 pub fn split_timestamp_line(line: &str) -> Option<(&str, &str)> {
+    split_builtin_timestamp(line)
+        .or_else(|| with_heading_format(|format| format.and_then(|format| format.split(line))))
+}
+
+fn split_builtin_timestamp(line: &str) -> Option<(&str, &str)> {
     let bytes = line.as_bytes();
     let mut start = 0usize;
 
@@ -271,10 +894,24 @@ pub fn strip_timestamp_prefix(line: &str) -> &str {
         .unwrap_or(line)
 }
 
-/// Returns true if the line starts with a heading timestamp like "## [HH:MM:SS]".
+/// Returns true if the line is an entry heading: "## [HH:MM:SS]" or the configured format.
+/// A bare "[HH:MM:SS] text" line is timestamped but not a heading.
 pub fn is_heading_timestamp_line(line: &str) -> bool {
     let trimmed = line.trim_start();
-    trimmed.starts_with('#') && split_timestamp_line(line).is_some()
+    if split_builtin_timestamp(line).is_some() {
+        return trimmed.starts_with('#');
+    }
+    split_timestamp_line(line).is_some()
+}
+
+/// Time of a heading or timestamped line, in whichever supported format it was written.
+pub fn parse_heading_time(line: &str) -> Option<NaiveTime> {
+    let (prefix, _) = split_timestamp_line(line)?;
+    let text = prefix.trim().trim_start_matches('[').trim_end_matches(']');
+    if let Ok(time) = NaiveTime::parse_from_str(text, DEFAULT_TIME_FORMAT) {
+        return Some(time);
+    }
+    NaiveTime::parse_from_str(text, &heading_format().time_format).ok()
 }
 
 /// Counts trailing tomato emojis (🍅) in a string.
@@ -301,7 +938,13 @@ pub fn strip_trailing_tomatoes(s: &str) -> (&str, usize) {
 
 #[cfg(test)]
 mod tests {
-    use super::{is_heading_timestamp_line, split_timestamp_line, strip_timestamp_prefix};
+    use super::{
+        AgendaItem, AgendaItemKind, Estimate, HeadingFormat, PomodoroPhase, TaskSchedule,
+        TaskInstance, TaskStatus, TimeSpent,
+        is_heading_timestamp_line, is_timestamped_line, parse_heading_time,
+        set_test_heading_format, split_timestamp_line, strip_timestamp_prefix,
+    };
+    use chrono::{NaiveDate, NaiveTime};
 
     #[test]
     fn parses_heading_timestamp_line() {
@@ -317,4 +960,123 @@ mod tests {
         let line = "## [10:12:44]";
         assert_eq!(strip_timestamp_prefix(line), "");
     }
+
+    #[test]
+    fn configured_heading_format_parses_alongside_builtin() {
+        let format = HeadingFormat::new("### {time}", "%H:%M").expect("format");
+        let time = NaiveTime::from_hms_opt(14, 5, 30).expect("time");
+        assert_eq!(format.heading(time), "### 14:05");
+        assert_eq!(format.time_width(), 5);
+
+        set_test_heading_format(Some(format));
+        assert_eq!(split_timestamp_line("### 14:05 ^abc123"), Some(("14:05", "^abc123")));
+        assert!(is_heading_timestamp_line("### 14:05"));
+        assert!(is_heading_timestamp_line("## [09:00:00]"));
+        assert!(!is_timestamped_line("### 14:05pm"));
+        assert!(!is_heading_timestamp_line("[09:00:00] inline"));
+        assert_eq!(parse_heading_time("### 14:05"), NaiveTime::from_hms_opt(14, 5, 0));
+
+        set_test_heading_format(Some(HeadingFormat::new("- {time}", "%I:%M %p").expect("list")));
+        assert!(is_heading_timestamp_line("- 02:05 PM"));
+        assert!(!is_timestamped_line("### 14:05"));
+        assert!(!is_timestamped_line("- [ ] 02:05 PM"));
+        set_test_heading_format(None);
+
+        assert!(HeadingFormat::new("### time", "%H:%M").is_err());
+        assert!(HeadingFormat::new("### {time}", "%Y-%m-%d").is_err());
+    }
+
+    #[test]
+    fn estimate_progress_counts_tomatoes_or_time() {
+        assert_eq!(
+            Estimate::Pomodoros(4).progress(2, 50),
+            ("2/4🍅".to_string(), false)
+        );
+        assert_eq!(
+            Estimate::Pomodoros(4).progress(5, 125),
+            ("5/4🍅".to_string(), true)
+        );
+        assert_eq!(
+            Estimate::Minutes(120).progress(2, 50),
+            ("50m/2h".to_string(), false)
+        );
+        assert_eq!(
+            Estimate::Minutes(90).progress(4, 100),
+            ("1h40m/1h30m".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn time_spent_counts_sessions_per_task_line() {
+        let date = |value| NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date");
+        let task = |file_path, block_id, since| TaskInstance {
+            file_path,
+            task_identity: "write report",
+            block_id,
+            since,
+        };
+        let mut spent = TimeSpent::new(25);
+        spent.record(&task("a.md", None, None), date("2025-01-06"), 50, true);
+        spent.record(&task("a.md", None, None), date("2025-01-07"), 15, false);
+        spent.record(&task("a.md", Some("abc123"), None), date("2025-01-07"), 40, true);
+
+        // Recorded minutes, plus 25m for the tomato without a session.
+        assert_eq!(spent.minutes(&task("a.md", None, None), 2), 90);
+        assert_eq!(
+            spent.minutes(&task("a.md", None, Some(date("2025-01-07"))), 0),
+            15
+        );
+        assert_eq!(spent.minutes(&task("b.md", None, None), 2), 50);
+        assert_eq!(spent.minutes(&task("b.md", Some("abc123"), None), 1), 40);
+        assert_eq!(
+            Estimate::Minutes(60).progress(2, spent.minutes(&task("a.md", None, None), 2)),
+            ("1h30m/1h".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn long_break_follows_every_nth_session() {
+        assert_eq!(PomodoroPhase::break_after(1, 4), PomodoroPhase::ShortBreak);
+        assert_eq!(PomodoroPhase::break_after(3, 4), PomodoroPhase::ShortBreak);
+        assert_eq!(PomodoroPhase::break_after(4, 4), PomodoroPhase::LongBreak);
+        assert_eq!(PomodoroPhase::break_after(8, 4), PomodoroPhase::LongBreak);
+        assert_eq!(PomodoroPhase::break_after(4, 0), PomodoroPhase::ShortBreak);
+    }
+
+    #[test]
+    fn deadline_warning_uses_task_lead_time_or_default() {
+        let date = |value| NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date");
+        let mut item = AgendaItem {
+            kind: AgendaItemKind::Task,
+            date: date("2025-02-01"),
+            time: None,
+            duration_minutes: None,
+            text: "File taxes".to_string(),
+            indent: 0,
+            status: TaskStatus::Open,
+            priority: None,
+            schedule: TaskSchedule {
+                due: Some(date("2025-02-01")),
+                ..TaskSchedule::default()
+            },
+            file_path: String::new(),
+            line_number: 0,
+            block_id: None,
+            projected: false,
+            tomato_count: 0,
+            line_date: None,
+        };
+        assert!(item.is_deadline_upcoming(date("2025-01-25"), 7));
+        assert!(!item.is_deadline_upcoming(date("2025-01-24"), 7));
+        assert!(!item.is_deadline_upcoming(date("2025-02-01"), 7));
+        assert!(item.is_overdue_on(date("2025-02-02")));
+
+        item.schedule.due_warning_days = Some(3);
+        assert!(!item.is_deadline_upcoming(date("2025-01-25"), 7));
+        assert!(item.is_deadline_upcoming(date("2025-01-29"), 7));
+
+        item.status = TaskStatus::Cancelled;
+        assert!(!item.is_deadline_upcoming(date("2025-01-29"), 7));
+        assert!(!item.is_overdue_on(date("2025-02-02")));
+    }
 }
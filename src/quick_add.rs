@@ -0,0 +1,400 @@
+//! Natural-language phrases in composer task lines, e.g. `- [ ] call Bob tomorrow 3pm for 30m
+//! !A`, rewritten into `@sched`, `@time`, `@dur` and priority tokens when the entry is saved.
+
+use chrono::{NaiveDate, NaiveTime};
+use std::ops::Range;
+
+use crate::block_ids::{split_block_id, with_block_id};
+use crate::date_input::{parse_duration_input, parse_relative_date_input};
+use crate::models::{Priority, split_task_checkbox, strip_trailing_tomatoes};
+use crate::storage::{split_trailing_context_tags, strip_carryover_marker_at_end};
+use crate::task_metadata::{
+    TaskMetadataKey, emoji_syntax, parse_priority_emoji, parse_task_metadata, parse_time,
+    upsert_task_metadata_token,
+};
+
+/// Weekday words recognised on their own. `sat`, `sun` and `wed` are left out because they are
+/// also ordinary words.
+const WEEKDAY_WORDS: &[&str] = &[
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+    "mon",
+    "tue",
+    "thu",
+    "fri",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct QuickAdd {
+    /// The line with the recognised phrases replaced by tokens.
+    pub line: String,
+    /// The tokens that were added, in the order they were found, for the status bar preview.
+    pub tokens: Vec<String>,
+}
+
+#[derive(Default)]
+struct Found {
+    scheduled: Option<NaiveDate>,
+    due: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    duration: Option<u32>,
+    priority: Option<Priority>,
+    tokens: Vec<String>,
+}
+
+/// Rewrites the phrases in a task line. Returns `None` for non-task lines and for lines with
+/// nothing to rewrite. Kinds the line already has a token for (say an `@time`) are left as text,
+/// and so is a second phrase of a kind that was already found.
+pub(crate) fn parse_quick_add(line: &str, today: NaiveDate) -> Option<QuickAdd> {
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(indent_len);
+    let (status, content) = split_task_checkbox(rest)?;
+
+    let (existing, _) = parse_task_metadata(content);
+    let has_priority =
+        content.trim_start().starts_with("[#") || parse_priority_emoji(content).is_some();
+
+    // Tomatoes, the carry-over marker and the block ID stay at the end, after any new tokens.
+    let (text, block_id) = split_block_id(content);
+    let (text, tomato_count) = strip_trailing_tomatoes(text);
+    let (head, tags) = split_trailing_context_tags(text);
+    let (base, carryover) = strip_carryover_marker_at_end(head);
+
+    let spans = word_spans(&base);
+    let words: Vec<&str> = spans.iter().map(|span| &base[span.clone()]).collect();
+    let mut found = Found::default();
+    let mut used_spans: Vec<Range<usize>> = Vec::new();
+
+    let mut i = 0;
+    while i < words.len() {
+        let used = match_priority(&words[i..], has_priority, &mut found)
+            .or_else(|| match_date(&words[i..], today, &existing, &mut found))
+            .or_else(|| match_time(&words[i..], existing.time.is_some(), &mut found))
+            .or_else(|| {
+                match_duration(&words[i..], existing.duration_minutes.is_some(), &mut found)
+            });
+        match used {
+            Some(count) => {
+                used_spans.push(spans[i].start..spans[i + count - 1].end);
+                i += count;
+            }
+            None => i += 1,
+        }
+    }
+    if found.tokens.is_empty() {
+        return None;
+    }
+
+    let mut body = remove_spans(&base, &used_spans);
+    if let Some(date) = found.scheduled {
+        body = upsert_task_metadata_token(&body, TaskMetadataKey::Scheduled, &format_date(date));
+    }
+    if let Some(date) = found.due {
+        body = upsert_task_metadata_token(&body, TaskMetadataKey::Due, &format_date(date));
+    }
+    if let Some(time) = found.time {
+        let value = time.format("%H:%M").to_string();
+        body = upsert_task_metadata_token(&body, TaskMetadataKey::Time, &value);
+    }
+    if let Some(minutes) = found.duration {
+        let value = format!("{minutes}m");
+        body = upsert_task_metadata_token(&body, TaskMetadataKey::Duration, &value);
+    }
+    match found.priority {
+        Some(priority) if emoji_syntax() => {
+            let letter = priority.as_char().to_string();
+            body = upsert_task_metadata_token(&body, TaskMetadataKey::Priority, &letter);
+        }
+        Some(priority) => body = format!("[#{}] {body}", priority.as_char()),
+        None => {}
+    }
+    if let Some(carryover) = carryover {
+        body.push_str(&format!(" ⟦{carryover}⟧"));
+    }
+    body.push_str(tags);
+    if tomato_count > 0 {
+        body.push(' ');
+        body.push_str(&"🍅".repeat(tomato_count));
+    }
+    let body = with_block_id(&body, block_id);
+
+    Some(QuickAdd {
+        line: format!("{indent}{}{body}", status.checkbox()),
+        tokens: found.tokens,
+    })
+}
+
+/// Byte ranges of the whitespace-separated words in `text`.
+fn word_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    for (idx, ch) in text.char_indices() {
+        match (ch.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push(s..idx);
+                start = None;
+            }
+            (false, None) => start = Some(idx),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push(s..text.len());
+    }
+    spans
+}
+
+/// Cuts the matched phrases out of `text` along with the whitespace before them (after them
+/// at the start of the text), leaving the spacing of everything else as the user typed it.
+fn remove_spans(text: &str, spans: &[Range<usize>]) -> String {
+    let mut output = String::new();
+    let mut cursor = 0;
+    for span in spans {
+        let start = cursor + text[cursor..span.start].trim_end().len();
+        let mut end = span.end;
+        if output.is_empty() && start == cursor {
+            end = text.len() - text[end..].trim_start().len();
+        }
+        output.push_str(&text[cursor..start]);
+        cursor = end;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
+
+/// `!A`, `!B` or `!C`.
+fn match_priority(words: &[&str], has_priority: bool, found: &mut Found) -> Option<usize> {
+    if has_priority || found.priority.is_some() {
+        return None;
+    }
+    let letter = words[0].strip_prefix('!')?;
+    let mut chars = letter.chars();
+    let priority = Priority::from_char(chars.next()?.to_ascii_uppercase())?;
+    if chars.next().is_some() {
+        return None;
+    }
+    found.priority = Some(priority);
+    found.tokens.push(format!("[#{}]", priority.as_char()));
+    Some(1)
+}
+
+/// A date, optionally after `on`; after `by` it becomes the due date instead.
+fn match_date(
+    words: &[&str],
+    today: NaiveDate,
+    existing: &crate::models::TaskSchedule,
+    found: &mut Found,
+) -> Option<usize> {
+    let first = words[0].to_lowercase();
+    let (lead, is_due) = match first.as_str() {
+        "on" => (1, false),
+        "by" => (1, true),
+        _ => (0, false),
+    };
+    if is_due && (existing.due.is_some() || found.due.is_some()) {
+        return None;
+    }
+    if !is_due && (existing.scheduled.is_some() || found.scheduled.is_some()) {
+        return None;
+    }
+
+    let (date, count) = parse_date_phrase(&words[lead..], today)?;
+    if is_due {
+        found.due = Some(date);
+        found.tokens.push(format!("@due({})", format_date(date)));
+    } else {
+        found.scheduled = Some(date);
+        found.tokens.push(format!("@sched({})", format_date(date)));
+    }
+    Some(lead + count)
+}
+
+fn parse_date_phrase(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let word = |i: usize| words.get(i).map(|w| w.to_lowercase());
+    let first = word(0)?;
+
+    // "in 3 days", "in 2 weeks"
+    if first == "in"
+        && let (Some(count), Some(unit)) = (word(1), word(2))
+        && count.chars().all(|c| c.is_ascii_digit())
+    {
+        let unit = match unit.trim_end_matches('s') {
+            "day" => 'd',
+            "week" => 'w',
+            "month" => 'm',
+            _ => return None,
+        };
+        return Some((
+            parse_relative_date_input(&format!("+{count}{unit}"), today)?,
+            3,
+        ));
+    }
+
+    if first == "next"
+        && let Some(day) = word(1)
+        && WEEKDAY_WORDS.contains(&day.as_str())
+    {
+        return Some((parse_relative_date_input(&format!("next {day}"), today)?, 2));
+    }
+
+    let single = matches!(first.as_str(), "today" | "tomorrow")
+        || WEEKDAY_WORDS.contains(&first.as_str())
+        || first.starts_with('+')
+        || first.chars().next().is_some_and(|c| c.is_ascii_digit()) && first.contains('-');
+    if single {
+        return Some((parse_relative_date_input(&first, today)?, 1));
+    }
+    None
+}
+
+/// `3pm`, `3:30pm`, `3 pm` or `15:30`, optionally after `at`.
+fn match_time(words: &[&str], has_time: bool, found: &mut Found) -> Option<usize> {
+    if has_time || found.time.is_some() {
+        return None;
+    }
+    let lead = usize::from(words[0].eq_ignore_ascii_case("at"));
+    let first = words.get(lead)?.to_lowercase();
+    let next = words.get(lead + 1).map(|w| w.to_lowercase());
+
+    let (time, count) = if let Some(suffix @ ("am" | "pm")) = next.as_deref()
+        && let Some(time) = parse_clock(&format!("{first}{suffix}"))
+    {
+        (time, 2)
+    } else if first.contains(':') || first.ends_with("am") || first.ends_with("pm") {
+        (parse_clock(&first)?, 1)
+    } else {
+        return None;
+    };
+
+    found.time = Some(time);
+    found
+        .tokens
+        .push(format!("@time({})", time.format("%H:%M")));
+    Some(lead + count)
+}
+
+fn parse_clock(word: &str) -> Option<NaiveTime> {
+    let (digits, offset) = if let Some(rest) = word.strip_suffix("am") {
+        (rest, 0)
+    } else if let Some(rest) = word.strip_suffix("pm") {
+        (rest, 12)
+    } else {
+        return parse_time(word);
+    };
+    let (hour, minute) = match digits.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None => (digits.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    NaiveTime::from_hms_opt(hour % 12 + offset, minute, 0)
+}
+
+/// `for 30m`, `for 1h30m` or `for 45 min`.
+fn match_duration(words: &[&str], has_duration: bool, found: &mut Found) -> Option<usize> {
+    if has_duration || found.duration.is_some() || !words[0].eq_ignore_ascii_case("for") {
+        return None;
+    }
+    let amount = words.get(1)?.to_lowercase();
+    let unit = words.get(2).map(|w| w.to_lowercase());
+
+    let (minutes, count) = if amount.chars().all(|c| c.is_ascii_digit())
+        && let Some(unit) = unit.as_deref()
+    {
+        let factor = match unit {
+            "m" | "min" | "mins" | "minute" | "minutes" => 1,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60,
+            _ => return None,
+        };
+        (amount.parse::<u32>().ok()?.checked_mul(factor)?, 3)
+    } else if amount.chars().any(|c| c.is_ascii_alphabetic()) {
+        (parse_duration_input(&amount)?, 2)
+    } else {
+        return None;
+    };
+    if minutes == 0 {
+        return None;
+    }
+
+    found.duration = Some(minutes);
+    found.tokens.push(format!("@dur({minutes}m)"));
+    Some(count)
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 15).unwrap() // Wed
+    }
+
+    #[test]
+    fn rewrites_date_time_duration_and_priority() {
+        let parsed = parse_quick_add("  - [ ] call Bob tomorrow 3pm for 30m !A", today())
+            .expect("quick add");
+        assert_eq!(
+            parsed.line,
+            "  - [ ] [#A] call Bob @sched(2025-01-16) @time(15:00) @dur(30m)"
+        );
+        assert_eq!(
+            parsed.tokens,
+            vec!["@sched(2025-01-16)", "@time(15:00)", "@dur(30m)", "[#A]"]
+        );
+
+        let parsed = parse_quick_add("- [ ] review on next mon at 9:30 am for 1 hour", today())
+            .expect("quick add");
+        assert_eq!(
+            parsed.line,
+            "- [ ] review @sched(2025-01-20) @time(09:30) @dur(60m)"
+        );
+
+        let parsed = parse_quick_add("- [ ] file taxes by friday", today()).expect("quick add");
+        assert_eq!(parsed.line, "- [ ] file taxes @due(2025-01-17)");
+    }
+
+    #[test]
+    fn leaves_plain_text_and_existing_tokens_alone() {
+        assert_eq!(parse_quick_add("call Bob tomorrow", today()), None);
+        assert_eq!(
+            parse_quick_add("- [ ] monitor sat data for Bob", today()),
+            None
+        );
+        let parsed = parse_quick_add("- [ ] call tomorrow @sched(2025-02-01) at 9am", today())
+            .expect("quick add");
+        assert_eq!(
+            parsed.line,
+            "- [ ] call tomorrow @sched(2025-02-01) @time(09:00)"
+        );
+    }
+
+    #[test]
+    fn keeps_trailing_markers_last_and_original_spacing() {
+        let parsed = parse_quick_add(
+            "- [ ] call  Bob tomorrow ⟦2025-01-10⟧ 🍅🍅 ^abc123",
+            today(),
+        )
+        .expect("quick add");
+        assert_eq!(
+            parsed.line,
+            "- [ ] call  Bob @sched(2025-01-16) ⟦2025-01-10⟧ 🍅🍅 ^abc123"
+        );
+
+        let parsed = parse_quick_add("- [ ] tomorrow 3pm  write   notes", today())
+            .expect("quick add");
+        assert_eq!(
+            parsed.line,
+            "- [ ] write   notes @sched(2025-01-16) @time(15:00)"
+        );
+    }
+}
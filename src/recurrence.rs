@@ -0,0 +1,406 @@
+//! `@every(...)` recurrence rules for tasks.
+//!
+//! Supported values: an interval (`3d`, `1w`, `2m`, `1y`, or `daily`/`weekly`/`monthly`/
+//! `yearly`), a list of weekdays (`mon,thu`, `weekdays`), or a day of the month
+//! (`month 15`, clamped to the last day of shorter months).
+//!
+//! Obsidian Tasks phrases (`every 2 weeks`, `every week on Monday, Thursday`,
+//! `every month on the 15th`) parse to the same rules, and [`Recurrence::obsidian_text`]
+//! writes them back in that form.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntervalUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Recurrence {
+    Interval {
+        count: u32,
+        unit: IntervalUnit,
+    },
+    /// Weekdays in Monday-first order, without duplicates.
+    Weekdays(Vec<Weekday>),
+    MonthDay(u32),
+}
+
+/// Presets offered by the date picker, in cycling order.
+pub const RECURRENCE_PRESETS: [&str; 6] = ["1d", "1w", "2w", "1m", "1y", "weekdays"];
+
+/// Upper bound on occurrences generated for one task, so a tiny interval over a long range
+/// cannot stall the agenda.
+const MAX_OCCURRENCES: usize = 400;
+
+impl Recurrence {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        if let Some(phrase) = value.strip_prefix("every ") {
+            return Self::parse_phrase(phrase);
+        }
+        match value.as_str() {
+            "daily" | "day" => return Some(Self::interval(1, IntervalUnit::Day)),
+            "weekly" | "week" => return Some(Self::interval(1, IntervalUnit::Week)),
+            "monthly" | "month" => return Some(Self::interval(1, IntervalUnit::Month)),
+            "yearly" | "year" => return Some(Self::interval(1, IntervalUnit::Year)),
+            "weekdays" | "weekday" => {
+                return Some(Self::Weekdays(vec![
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ]));
+            }
+            _ => {}
+        }
+
+        if let Some(day) = value.strip_prefix("month") {
+            let day: u32 = day.trim().parse().ok()?;
+            return (1..=31).contains(&day).then_some(Self::MonthDay(day));
+        }
+
+        if let Some(unit) = value.chars().last().and_then(interval_unit)
+            && let Ok(count) = value[..value.len() - 1].trim().parse::<u32>()
+        {
+            return (count > 0).then_some(Self::interval(count, unit));
+        }
+
+        parse_weekday_list(&value)
+    }
+
+    /// Parses the part of an Obsidian Tasks rule after `every`.
+    fn parse_phrase(phrase: &str) -> Option<Self> {
+        let phrase = phrase.trim();
+        let phrase = phrase.strip_suffix("when done").unwrap_or(phrase).trim();
+        if let Some(days) = phrase.strip_prefix("week on ") {
+            return parse_weekday_list(days);
+        }
+        if let Some(day) = phrase.strip_prefix("month on the ") {
+            let day = day.trim();
+            if day == "last" {
+                return Some(Self::MonthDay(31));
+            }
+            let digits = day.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+            let day: u32 = digits.parse().ok()?;
+            return (1..=31).contains(&day).then_some(Self::MonthDay(day));
+        }
+
+        let (count, unit) = match phrase.split_once(' ') {
+            Some((count, unit)) => (count.parse::<u32>().ok()?, unit.trim()),
+            None => (1, phrase),
+        };
+        let unit = match unit.strip_suffix('s').unwrap_or(unit) {
+            "day" => IntervalUnit::Day,
+            "week" => IntervalUnit::Week,
+            "month" => IntervalUnit::Month,
+            "year" => IntervalUnit::Year,
+            "weekday" if count == 1 => return Self::parse("weekdays"),
+            _ if count == 1 => return parse_weekday_list(phrase),
+            _ => return None,
+        };
+        (count > 0).then_some(Self::interval(count, unit))
+    }
+
+    fn interval(count: u32, unit: IntervalUnit) -> Self {
+        Self::Interval { count, unit }
+    }
+
+    /// First occurrence strictly after `date`.
+    pub fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Interval { count, unit } => {
+                let count = *count;
+                let next = match unit {
+                    IntervalUnit::Day => date.checked_add_signed(Duration::days(count as i64)),
+                    IntervalUnit::Week => date.checked_add_signed(Duration::weeks(count as i64)),
+                    IntervalUnit::Month => date.checked_add_months(Months::new(count)),
+                    IntervalUnit::Year => date.checked_add_months(Months::new(count * 12)),
+                };
+                next.unwrap_or(date)
+            }
+            Self::Weekdays(days) => {
+                let mut next = date + Duration::days(1);
+                while !days.contains(&next.weekday()) {
+                    next += Duration::days(1);
+                }
+                next
+            }
+            Self::MonthDay(day) => {
+                let in_month = |date: NaiveDate| {
+                    let last = last_day_of_month(date);
+                    date.with_day((*day).min(last)).unwrap_or(date)
+                };
+                let candidate = in_month(date);
+                if candidate > date {
+                    return candidate;
+                }
+                let next_month = date
+                    .with_day(1)
+                    .and_then(|first| first.checked_add_months(Months::new(1)))
+                    .unwrap_or(date);
+                in_month(next_month)
+            }
+        }
+    }
+
+    /// First occurrence after `anchor` that also falls after `today`, used when a
+    /// recurring task is completed so an overdue task does not spawn another overdue one.
+    pub fn next_occurrence(&self, anchor: NaiveDate, today: NaiveDate) -> NaiveDate {
+        let mut anchor = anchor;
+        // Fixed-length intervals jump straight to the last occurrence on or before today.
+        if let Self::Interval { count, unit } = self {
+            let step = match unit {
+                IntervalUnit::Day => Some(*count as i64),
+                IntervalUnit::Week => Some(*count as i64 * 7),
+                IntervalUnit::Month | IntervalUnit::Year => None,
+            };
+            if let Some(step) = step
+                && anchor < today
+            {
+                let periods = (today - anchor).num_days() / step;
+                anchor = anchor
+                    .checked_add_signed(Duration::days(periods * step))
+                    .unwrap_or(anchor);
+            }
+        }
+        let mut next = self.next_after(anchor);
+        while next <= today {
+            let following = self.next_after(next);
+            if following <= next {
+                break;
+            }
+            next = following;
+        }
+        next
+    }
+
+    /// Occurrences after `anchor` (and after `today`) that fall within `start..=end`.
+    pub fn occurrences_between(
+        &self,
+        anchor: NaiveDate,
+        today: NaiveDate,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        let mut next = self.next_occurrence(anchor, today);
+        while next <= end && dates.len() < MAX_OCCURRENCES {
+            if next >= start {
+                dates.push(next);
+            }
+            let following = self.next_after(next);
+            if following <= next {
+                break;
+            }
+            next = following;
+        }
+        dates
+    }
+
+    /// The rule as Obsidian Tasks writes it after its 🔁 marker.
+    pub fn obsidian_text(&self) -> String {
+        match self {
+            Self::Interval { count, unit } => {
+                let unit = match unit {
+                    IntervalUnit::Day => "day",
+                    IntervalUnit::Week => "week",
+                    IntervalUnit::Month => "month",
+                    IntervalUnit::Year => "year",
+                };
+                if *count == 1 {
+                    format!("every {unit}")
+                } else {
+                    format!("every {count} {unit}s")
+                }
+            }
+            Self::Weekdays(days) if days.len() == 5 && days.iter().all(is_workday) => {
+                "every weekday".to_string()
+            }
+            Self::Weekdays(days) => {
+                let names: Vec<&str> = days.iter().map(|day| weekday_name(*day)).collect();
+                format!("every week on {}", names.join(", "))
+            }
+            Self::MonthDay(day) => {
+                let suffix = match (day % 10, day % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("every month on the {day}{suffix}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interval { count, unit } => {
+                let unit = match unit {
+                    IntervalUnit::Day => 'd',
+                    IntervalUnit::Week => 'w',
+                    IntervalUnit::Month => 'm',
+                    IntervalUnit::Year => 'y',
+                };
+                write!(f, "{count}{unit}")
+            }
+            Self::Weekdays(days) if days.len() == 5 && days.iter().all(is_workday) => {
+                write!(f, "weekdays")
+            }
+            Self::Weekdays(days) => {
+                let names: Vec<String> = days
+                    .iter()
+                    .map(|day| day.to_string().to_lowercase())
+                    .collect();
+                write!(f, "{}", names.join(","))
+            }
+            Self::MonthDay(day) => write!(f, "month {day}"),
+        }
+    }
+}
+
+impl Serialize for Recurrence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Parses `mon,thu` or `Monday, Thursday and Friday` into a sorted weekday rule.
+fn parse_weekday_list(value: &str) -> Option<Recurrence> {
+    let mut days: Vec<Weekday> = Vec::new();
+    for part in value.replace(" and ", ",").split(',') {
+        let day = part.trim().parse::<Weekday>().ok()?;
+        if !days.contains(&day) {
+            days.push(day);
+        }
+    }
+    days.sort_by_key(|day| day.num_days_from_monday());
+    (!days.is_empty()).then_some(Recurrence::Weekdays(days))
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn is_workday(day: &Weekday) -> bool {
+    !matches!(day, Weekday::Sat | Weekday::Sun)
+}
+
+fn interval_unit(c: char) -> Option<IntervalUnit> {
+    match c {
+        'd' => Some(IntervalUnit::Day),
+        'w' => Some(IntervalUnit::Week),
+        'm' => Some(IntervalUnit::Month),
+        'y' => Some(IntervalUnit::Year),
+        _ => None,
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).unwrap_or(date);
+    first
+        .checked_add_months(Months::new(1))
+        .map(|next| (next - Duration::days(1)).day())
+        .unwrap_or(28)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("valid date")
+    }
+
+    #[test]
+    fn parses_and_rolls_forward_rules() {
+        let weekly = Recurrence::parse("1w").expect("1w");
+        assert_eq!(weekly.next_after(date("2025-01-06")), date("2025-01-13"));
+        assert_eq!(Recurrence::parse("weekly"), Some(weekly.clone()));
+        assert_eq!(weekly.to_string(), "1w");
+
+        let monthly = Recurrence::parse("1m").expect("1m");
+        assert_eq!(monthly.next_after(date("2025-01-31")), date("2025-02-28"));
+
+        let days = Recurrence::parse("thu, Mon").expect("weekdays");
+        assert_eq!(days.to_string(), "mon,thu");
+        assert_eq!(days.next_after(date("2025-01-06")), date("2025-01-09"));
+        assert_eq!(days.next_after(date("2025-01-09")), date("2025-01-13"));
+        let workdays = Recurrence::parse("fri,mon,tue,wed,thu").expect("workdays");
+        assert_eq!(workdays.to_string(), "weekdays");
+        assert_eq!(workdays.next_after(date("2025-01-10")), date("2025-01-13"));
+
+        let month_day = Recurrence::parse("month 31").expect("month day");
+        assert_eq!(month_day.next_after(date("2025-01-31")), date("2025-02-28"));
+        assert_eq!(month_day.next_after(date("2025-02-10")), date("2025-02-28"));
+        assert_eq!(month_day.to_string(), "month 31");
+
+        // Overdue tasks skip occurrences that are already in the past.
+        assert_eq!(
+            weekly.next_occurrence(date("2025-01-06"), date("2025-01-22")),
+            date("2025-01-27")
+        );
+        assert_eq!(
+            weekly.occurrences_between(
+                date("2025-01-06"),
+                date("2025-01-01"),
+                date("2025-01-10"),
+                date("2025-01-31")
+            ),
+            [date("2025-01-13"), date("2025-01-20"), date("2025-01-27")]
+        );
+
+        let phrase = Recurrence::parse("every 2 weeks").expect("every 2 weeks");
+        assert_eq!(phrase.to_string(), "2w");
+        assert_eq!(phrase.obsidian_text(), "every 2 weeks");
+        let phrase = Recurrence::parse("every week on Monday, Thursday").expect("week on");
+        assert_eq!(phrase, days);
+        assert_eq!(phrase.obsidian_text(), "every week on Monday, Thursday");
+        let phrase = Recurrence::parse("every month on the 22nd when done").expect("month on");
+        assert_eq!(phrase, Recurrence::MonthDay(22));
+        assert_eq!(phrase.obsidian_text(), "every month on the 22nd");
+        assert_eq!(Recurrence::parse("every weekday"), Some(workdays.clone()));
+        assert_eq!(workdays.obsidian_text(), "every weekday");
+        assert_eq!(weekly.obsidian_text(), "every week");
+
+        assert_eq!(Recurrence::parse("0d"), None);
+        assert_eq!(Recurrence::parse("month 32"), None);
+        assert_eq!(Recurrence::parse("someday"), None);
+    }
+
+    #[test]
+    fn next_occurrence_catches_up_with_old_anchors() {
+        let daily = Recurrence::parse("1d").expect("1d");
+        assert_eq!(
+            daily.next_occurrence(date("2020-01-01"), date("2025-06-01")),
+            date("2025-06-02")
+        );
+        let fortnightly = Recurrence::parse("2w").expect("2w");
+        assert_eq!(
+            fortnightly.next_occurrence(date("2020-01-06"), date("2025-06-01")),
+            date("2025-06-02")
+        );
+        let workdays = Recurrence::parse("weekdays").expect("weekdays");
+        assert_eq!(
+            workdays.next_occurrence(date("2020-01-01"), date("2025-06-06")),
+            date("2025-06-09")
+        );
+    }
+}
@@ -7,10 +7,12 @@ use crate::{
     storage,
 };
 use chrono::{Duration, Local};
+use std::path::Path;
 use std::sync::mpsc::TryRecvError;
 
 pub fn tick(app: &mut App) {
     handle_day_rollover(app);
+    handle_external_changes(app);
     handle_google_sync(app);
     handle_google_auth(app);
     handle_ai_search(app);
@@ -18,28 +20,24 @@ pub fn tick(app: &mut App) {
     if let Some(end_time) = app.pomodoro_end
         && Local::now() >= end_time
     {
+        app.log_pomodoro_session(end_time, false);
         app.pomodoro_end = None;
         app.pomodoro_start = None;
+        // The completion prompt is shown in the normal layout, and focus mode would keep
+        // swallowing the Enter/Esc that answers it.
+        app.show_focus_mode = false;
+        app.focus_entry = None;
 
-        if let Some(models::PomodoroTarget::Task {
-            text,
-            file_path,
-            line_number,
-        }) = app.pomodoro_target.take()
-        {
-            let _ = storage::append_tomato_to_line(&file_path, line_number);
-            app.update_logs();
-            app.pomodoro_alert_message =
-                Some(format!("Pomodoro complete: 🍅 added to \"{}\".", text));
+        if app.pomodoro_phase.is_break() {
+            finish_pomodoro_break(app);
         } else {
-            app.pomodoro_alert_message = Some("Pomodoro complete.".to_string());
+            finish_pomodoro_work(app);
         }
-
-        let alert_seconds = app.config.pomodoro.alert_seconds.max(1) as i64;
-        app.pomodoro_alert_expiry = Some(Local::now() + Duration::seconds(alert_seconds));
     }
 
+    // An offered break waits for Enter or Esc instead of timing out.
     if let Some(expiry) = app.pomodoro_alert_expiry
+        && app.pomodoro_pending_break.is_none()
         && Local::now() >= expiry
     {
         app.pomodoro_alert_expiry = None;
@@ -60,6 +58,29 @@ pub fn tick(app: &mut App) {
     }
 }
 
+fn handle_external_changes(app: &mut App) {
+    let changed = app.log_watcher.poll();
+    if changed.is_empty() {
+        return;
+    }
+
+    let editing_changed = app.editing_entry.as_ref().is_some_and(|editing| {
+        !editing.is_raw
+            && !editing.changed_on_disk
+            && changed
+                .iter()
+                .any(|path| Path::new(path) == Path::new(&editing.file_path))
+    });
+    if editing_changed && app.editing_conflicts_with_disk() {
+        if let Some(editing) = app.editing_entry.as_mut() {
+            editing.changed_on_disk = true;
+        }
+        app.toast("This entry was changed on disk. Saving will not overwrite it.");
+    }
+
+    app.reload_from_disk();
+}
+
 fn handle_ai_search(app: &mut App) {
     let result = {
         let Some(receiver) = app.ai_search_receiver.as_ref() else {
@@ -79,6 +100,7 @@ fn handle_ai_search(app: &mut App) {
 
             if !response.entries.is_empty() {
                 app.logs = response.entries.clone();
+                app.search_snippets.clear();
                 app.is_search_result = true;
                 app.logs_state.select(Some(0));
 
@@ -184,22 +206,150 @@ fn handle_google_auth(app: &mut App) {
     }
 }
 
+/// Restores the pomodoro saved by the previous run. A work session that ended while MemoLog was
+/// closed gets its tomato now; a break that ended is dropped.
+pub fn resume_pomodoro(app: &mut App) {
+    let Ok(state) = storage::load_pomodoro_state(&app.config.data.log_path) else {
+        return;
+    };
+    if state.sessions_date.as_deref() == Some(app.active_date.as_str()) {
+        app.pomodoro_sessions_today = state.sessions;
+    }
+    let Some(timer) = state.timer else {
+        return;
+    };
+
+    app.pomodoro_start = Some(timer.start);
+    app.pomodoro_end = Some(timer.end);
+    app.pomodoro_target = timer.target;
+    app.pomodoro_phase = timer.phase;
+
+    let now = Local::now();
+    if now < timer.end {
+        let remaining = timer.end - now;
+        app.toast(format!(
+            "Resumed {}: {:02}:{:02} left.",
+            timer.phase.label().to_lowercase(),
+            remaining.num_minutes(),
+            remaining.num_seconds() % 60
+        ));
+        return;
+    }
+
+    app.log_pomodoro_session(timer.end, false);
+    app.pomodoro_start = None;
+    app.pomodoro_end = None;
+    app.pomodoro_phase = models::PomodoroPhase::Work;
+    let target = app.pomodoro_target.take();
+    if !timer.phase.is_break() {
+        if let Some(models::PomodoroTarget::Task {
+            text,
+            file_path,
+            line_number,
+            task_identity,
+            ..
+        }) = target
+        {
+            let result = storage::append_tomato_to_line(&file_path, line_number, &task_identity);
+            app.update_logs();
+            app.toast(match result {
+                Ok(()) => format!(
+                    "Pomodoro finished while MemoLog was closed: 🍅 added to \"{}\".",
+                    text
+                ),
+                Err(_) => format!(
+                    "Pomodoro finished while MemoLog was closed, but \"{}\" changed on disk: \
+                     no 🍅 added.",
+                    text
+                ),
+            });
+        }
+        if timer.end.format("%Y-%m-%d").to_string() == app.active_date {
+            app.pomodoro_sessions_today += 1;
+        }
+    }
+    app.save_pomodoro_state();
+}
+
+fn finish_pomodoro_work(app: &mut App) {
+    let mut message = if let Some(models::PomodoroTarget::Task {
+        text,
+        file_path,
+        line_number,
+        task_identity,
+        ..
+    }) = app.pomodoro_target.take()
+    {
+        let result = storage::append_tomato_to_line(&file_path, line_number, &task_identity);
+        app.update_logs();
+        match result {
+            Ok(()) => format!("Pomodoro complete: 🍅 added to \"{}\".", text),
+            Err(_) => format!(
+                "Pomodoro complete, but \"{}\" changed on disk: no 🍅 added.",
+                text
+            ),
+        }
+    } else {
+        "Pomodoro complete.".to_string()
+    };
+
+    app.pomodoro_sessions_today += 1;
+    let next = models::PomodoroPhase::break_after(
+        app.pomodoro_sessions_today,
+        app.config.pomodoro.long_break_every,
+    );
+    if app.config.pomodoro.auto_start_breaks {
+        actions::start_pomodoro_break(app, next);
+        message.push_str(&format!(" {} started.", next.label()));
+    } else {
+        app.pomodoro_phase = models::PomodoroPhase::Work;
+        app.pomodoro_pending_break = Some(next);
+        app.save_pomodoro_state();
+    }
+    app.pomodoro_alert_message = Some(message);
+
+    let alert_seconds = app.config.pomodoro.alert_seconds.max(1) as i64;
+    app.pomodoro_alert_expiry = Some(Local::now() + Duration::seconds(alert_seconds));
+}
+
+fn finish_pomodoro_break(app: &mut App) {
+    let phase = app.pomodoro_phase;
+    app.pomodoro_phase = models::PomodoroPhase::Work;
+    app.save_pomodoro_state();
+    app.pomodoro_alert_message = Some(format!(
+        "{} over. Pick a task for the next pomodoro.",
+        phase.label()
+    ));
+
+    let alert_seconds = app.config.pomodoro.alert_seconds.max(1) as i64;
+    app.pomodoro_alert_expiry = Some(Local::now() + Duration::seconds(alert_seconds));
+}
+
 fn handle_day_rollover(app: &mut App) {
     let today = Local::now().format("%Y-%m-%d").to_string();
     if today == app.active_date {
         return;
     }
 
-    // Policy: Pomodoro timers are in-memory only. On day change, running timers are reset.
+    // Policy: On day change, running pomodoro timers and the session count are reset.
+    if app.pomodoro_end.is_some_and(|end| Local::now() < end) {
+        app.log_pomodoro_session(Local::now(), true);
+    }
     app.active_date = today;
     app.pomodoro_end = None;
     app.pomodoro_start = None;
     app.pomodoro_target = None;
+    app.pomodoro_phase = models::PomodoroPhase::Work;
+    app.pomodoro_sessions_today = 0;
+    app.pomodoro_pending_break = None;
     app.pomodoro_alert_expiry = None;
     app.pomodoro_alert_message = None;
     app.show_pomodoro_popup = false;
     app.pomodoro_pending_task = None;
     app.pomodoro_minutes_input.clear();
+    app.show_focus_mode = false;
+    app.focus_entry = None;
+    app.save_pomodoro_state();
 
     // Day change invalidates search context in practice (different file set).
     app.is_search_result = false;
@@ -0,0 +1,357 @@
+//! On-disk cache of parsed log files, stored under `.memolog/` and keyed by path + mtime.
+//!
+//! Search, tags, activity stats and the Gemini keyword search read from this index instead of
+//! re-parsing every Markdown file. Files whose mtime or size changed are re-parsed on the next
+//! lookup, so writes made by MemoLog or an external editor are picked up incrementally. Files
+//! modified shortly before they were indexed are re-read and compared by content hash, since a
+//! same-size edit (e.g. `[ ]` → `[x]`) can land within one tick of a coarse filesystem mtime.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
+
+use crate::backups;
+use crate::file_layout::{self, parse_day_heading};
+use crate::models::{LogEntry, heading_format};
+use crate::search_rank::TermStats;
+use crate::storage;
+
+/// Bump when the cached layout or parsing rules change so stale indexes are rebuilt.
+const INDEX_VERSION: u32 = 6;
+const INDEX_FILE_NAME: &str = "search_index.json";
+/// Files indexed less than this long after their mtime are re-checked by content hash.
+const MTIME_SETTLE_NS: u64 = 2_000_000_000;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SearchIndex {
+    version: u32,
+    /// `data.file_layout` the dates were resolved with; a different layout rebuilds the index.
+    layout: String,
+    /// Entry heading template and time format (`HeadingFormat::key`) entries were split with.
+    heading: String,
+    files: BTreeMap<String, IndexedFile>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IndexedFile {
+    mtime_ns: u64,
+    size: u64,
+    /// FNV-1a hash of the file content, to detect edits that keep mtime and size.
+    content_hash: u64,
+    /// When the file was last parsed or verified against `content_hash`.
+    indexed_ns: u64,
+    pub entries: Vec<IndexedEntry>,
+    pub tags: Vec<(String, usize)>,
+    pub task_lines: Vec<IndexedTaskLine>,
+    /// Non-empty lines per day, excluding carryover bookkeeping lines and day headings.
+    pub line_counts: Vec<(NaiveDate, usize)>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IndexedEntry {
+    pub content: String,
+    pub line_number: usize,
+    pub end_line: usize,
+    pub date: Option<NaiveDate>,
+    /// Term counts for ranked search, so queries don't re-tokenize the corpus.
+    pub stats: TermStats,
+}
+
+impl IndexedEntry {
+    fn to_log_entry(&self, path: &str) -> LogEntry {
+        LogEntry {
+            content: self.content.clone(),
+            file_path: path.to_string(),
+            line_number: self.line_number,
+            end_line: self.end_line,
+            date: self.date,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IndexedTaskLine {
+    pub line_number: usize,
+    pub line: String,
+    pub date: Option<NaiveDate>,
+}
+
+impl SearchIndex {
+    /// Iterates over indexed files in path order.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &IndexedFile)> {
+        self.files.iter().map(|(path, file)| (path.as_str(), file))
+    }
+
+    /// Iterates over every cached entry as a `LogEntry`, in path order.
+    pub fn entries(&self) -> impl Iterator<Item = LogEntry> + '_ {
+        self.files.iter().flat_map(|(path, file)| {
+            file.entries
+                .iter()
+                .map(move |entry| entry.to_log_entry(path))
+        })
+    }
+
+    /// Every cached entry paired with its term statistics, for `search_rank::rank_entries`.
+    pub fn ranking_corpus(&self) -> Vec<(LogEntry, &TermStats)> {
+        let stats = self
+            .files
+            .values()
+            .flat_map(|file| file.entries.iter().map(|entry| &entry.stats));
+        self.entries().zip(stats).collect()
+    }
+
+    /// Re-parses files whose mtime/size changed and drops files that disappeared.
+    /// Files whose mtime was recent when they were indexed are re-read and re-parsed if their
+    /// content hash changed. Returns true when the index changed.
+    fn refresh(&mut self, log_path: &Path) -> io::Result<bool> {
+        let mut changed = false;
+        let layout = file_layout::current();
+        let heading = heading_format().key();
+        if self.version != INDEX_VERSION
+            || self.layout != layout.pattern()
+            || self.heading != heading
+        {
+            self.version = INDEX_VERSION;
+            self.layout = layout.pattern().to_string();
+            self.heading = heading;
+            self.files.clear();
+            changed = true;
+        }
+
+        let mut seen: HashSet<String> = HashSet::new();
+        storage::ensure_log_dir(log_path)?;
+        for path in file_layout::markdown_files(log_path)? {
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let path_str = path.to_string_lossy().to_string();
+            let mtime_ns = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0);
+            let size = metadata.len();
+            seen.insert(path_str.clone());
+
+            let cached = self
+                .files
+                .get_mut(&path_str)
+                .filter(|file| file.mtime_ns == mtime_ns && file.size == size);
+            if cached
+                .as_ref()
+                .is_some_and(|file| file.indexed_ns.saturating_sub(mtime_ns) >= MTIME_SETTLE_NS)
+            {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let content_hash = content_hash(&content);
+            if let Some(file) = cached
+                && file.content_hash == content_hash
+            {
+                // Persist only once the mtime has settled; until then the file is re-checked.
+                file.indexed_ns = now_ns();
+                changed |= file.indexed_ns.saturating_sub(mtime_ns) >= MTIME_SETTLE_NS;
+                continue;
+            }
+            self.files
+                .insert(path_str.clone(), index_file(&content, &path_str, mtime_ns, size));
+            changed = true;
+        }
+
+        let before = self.files.len();
+        self.files.retain(|path, _| seen.contains(path));
+        Ok(changed || self.files.len() != before)
+    }
+}
+
+/// Runs `f` against an up-to-date index for `log_path`.
+/// The index is kept in memory between calls and persisted when it changes.
+pub fn with_index<T>(log_path: &Path, f: impl FnOnce(&SearchIndex) -> T) -> io::Result<T> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, SearchIndex>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut guard = cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let index = guard
+        .entry(log_path.to_path_buf())
+        .or_insert_with(|| load_index(log_path));
+    if index.refresh(log_path)? {
+        // The cache is an optimization; a failed save only costs a re-parse next launch.
+        let _ = save_index(log_path, index);
+    }
+    Ok(f(index))
+}
+
+fn index_file(content: &str, path_str: &str, mtime_ns: u64, size: u64) -> IndexedFile {
+    let entries = storage::parse_log_content(content, path_str)
+        .into_iter()
+        .map(|entry| IndexedEntry {
+            stats: TermStats::from_text(&entry.content),
+            content: entry.content,
+            line_number: entry.line_number,
+            end_line: entry.end_line,
+            date: entry.date,
+        })
+        .collect();
+
+    let shared = file_layout::current().is_shared();
+    let dates = storage::line_dates(content, path_str);
+    let mut tag_counts: HashMap<String, usize> = HashMap::new();
+    let mut task_lines = Vec::new();
+    let mut line_counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for ((idx, line), date) in content.lines().enumerate().zip(dates) {
+        for word in line.split_whitespace() {
+            if word.starts_with('#') && word.len() > 1 {
+                *tag_counts.entry(word.to_string()).or_insert(0) += 1;
+            }
+        }
+        if line.trim().is_empty() || line.contains("System: Carryover Checked") {
+            continue;
+        }
+        if shared && parse_day_heading(line).is_some() {
+            continue;
+        }
+        if let Some(date) = date {
+            *line_counts.entry(date).or_insert(0) += 1;
+        }
+        if storage::is_task_line(line) {
+            task_lines.push(IndexedTaskLine {
+                line_number: idx,
+                line: line.to_string(),
+                date,
+            });
+        }
+    }
+    let mut tags: Vec<(String, usize)> = tag_counts.into_iter().collect();
+    tags.sort();
+
+    IndexedFile {
+        mtime_ns,
+        size,
+        content_hash: content_hash(content),
+        indexed_ns: now_ns(),
+        entries,
+        tags,
+        task_lines,
+        line_counts: line_counts.into_iter().collect(),
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in content.as_bytes() {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn now_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn index_file_path(log_path: &Path) -> PathBuf {
+    let mut path = storage::state_dir_path(log_path);
+    path.push(INDEX_FILE_NAME);
+    path
+}
+
+fn load_index(log_path: &Path) -> SearchIndex {
+    fs::read_to_string(index_file_path(log_path))
+        .ok()
+        .and_then(|content| serde_json::from_str::<SearchIndex>(&content).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .unwrap_or_default()
+}
+
+fn save_index(log_path: &Path, index: &SearchIndex) -> io::Result<()> {
+    let content = serde_json::to_string(index).map_err(io::Error::other)?;
+    // A crash mid-write must not leave a truncated cache behind.
+    backups::write_atomic(&index_file_path(log_path), &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log_dir() -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        let stamp = std::time::SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        dir.push(format!("memolog-index-test-{}-{}", std::process::id(), stamp));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn index_tracks_changed_and_removed_files() {
+        let dir = temp_log_dir();
+        let first = dir.join("2025-01-01.md");
+        let second = dir.join("2025-01-02.md");
+        fs::write(&first, "## [09:00:00]\nAlpha #work\n- [ ] task 🍅\n").expect("write first");
+        fs::write(&second, "## [10:00:00]\nBeta\n").expect("write second");
+
+        let count = with_index(&dir, |index| index.entries().count()).expect("index");
+        assert_eq!(count, 2);
+        assert!(index_file_path(&dir).exists());
+
+        fs::write(&first, "## [09:00:00]\nAlpha #work\n\n## [11:00:00]\nGamma #work\n")
+            .expect("rewrite first");
+        fs::remove_file(&second).expect("remove second");
+
+        let (contents, tags, tasks) = with_index(&dir, |index| {
+            let contents: Vec<String> = index.entries().map(|e| e.content).collect();
+            let (_, file) = index.files().next().expect("indexed file");
+            (contents, file.tags.clone(), file.task_lines.len())
+        })
+        .expect("index");
+        assert_eq!(contents, vec!["## [09:00:00]\nAlpha #work", "## [11:00:00]\nGamma #work"]);
+        assert!(tags.contains(&("#work".to_string(), 2)));
+        assert_eq!(tasks, 0);
+
+        let reloaded = load_index(&dir);
+        assert_eq!(reloaded.files().count(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn index_rereads_same_size_edit_with_unchanged_mtime() {
+        let dir = temp_log_dir();
+        let path = dir.join("2025-01-01.md");
+        fs::write(&path, "## [09:00:00]\n- [ ] task\n").expect("write log");
+        let mtime = fs::metadata(&path).and_then(|m| m.modified()).expect("mtime");
+
+        let tasks = |dir: &Path| {
+            with_index(dir, |index| {
+                let (_, file) = index.files().next().expect("indexed file");
+                file.task_lines[0].line.clone()
+            })
+            .expect("index")
+        };
+        assert_eq!(tasks(&dir), "- [ ] task");
+
+        fs::write(&path, "## [09:00:00]\n- [x] task\n").expect("toggle task");
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(mtime))
+            .expect("restore mtime");
+        assert_eq!(tasks(&dir), "- [x] task");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
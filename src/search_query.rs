@@ -0,0 +1,380 @@
+//! Structured search queries: free text, quoted phrases, field filters and exclusions.
+//!
+//! Example: `tag:#work is:open priority:A due:<2025-02-01 after:2025-01-01 "exact phrase" -draft`
+//!
+//! Text matching is case-insensitive. Task filters (`is:`, `priority:`, `due:`) must all match
+//! the same task inside an entry; `after:`/`before:` compare against the entry's log date.
+
+use chrono::NaiveDate;
+
+use crate::date_input::parse_relative_date_input;
+use crate::models::{LogEntry, Priority, TaskItem, TaskStatus};
+use crate::storage;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Clause {
+    pub negated: bool,
+    pub term: Term,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+    /// Lowercased word or phrase matched anywhere in the entry.
+    Text(String),
+    /// Lowercased tag without the leading `#`.
+    Tag(String),
+    Status(TaskStatusFilter),
+    Priority(Priority),
+    Due(Comparison, NaiveDate),
+    /// Entry date on or after the given date.
+    After(NaiveDate),
+    /// Entry date strictly before the given date.
+    Before(NaiveDate),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskStatusFilter {
+    /// Not done or cancelled.
+    Open,
+    /// Done or cancelled.
+    Closed,
+    Is(TaskStatus),
+    Any,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Comparison {
+    fn holds(self, left: NaiveDate, right: NaiveDate) -> bool {
+        match self {
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Eq => left == right,
+            Comparison::Ge => left >= right,
+            Comparison::Gt => left > right,
+        }
+    }
+}
+
+impl Term {
+    fn is_task_filter(&self) -> bool {
+        matches!(self, Term::Status(_) | Term::Priority(_) | Term::Due(..))
+    }
+}
+
+/// Parses a query string. Unknown fields and unparsable values fall back to plain text,
+/// so any input is a valid query.
+pub fn parse_query(input: &str, today: NaiveDate) -> Query {
+    let clauses = split_tokens(input)
+        .into_iter()
+        .filter_map(|raw| parse_clause(&raw, today))
+        .collect();
+    Query { clauses }
+}
+
+impl Query {
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let content = entry.content.to_lowercase();
+        let entry_date = entry.date;
+
+        let mut has_task_filters = false;
+        for clause in &self.clauses {
+            let hit = match &clause.term {
+                Term::Text(text) => content.contains(text.as_str()),
+                Term::Tag(tag) => content_has_tag(&content, tag),
+                Term::After(date) => entry_date.is_some_and(|d| d >= *date),
+                Term::Before(date) => entry_date.is_some_and(|d| d < *date),
+                Term::Status(_) | Term::Priority(_) | Term::Due(..) => {
+                    has_task_filters = true;
+                    continue;
+                }
+            };
+            if hit == clause.negated {
+                return false;
+            }
+        }
+
+        if !has_task_filters {
+            return true;
+        }
+        storage::parse_task_content(&entry.content, &entry.file_path)
+            .iter()
+            .any(|task| self.task_matches(task))
+    }
+
+    fn task_matches(&self, task: &TaskItem) -> bool {
+        self.clauses
+            .iter()
+            .filter(|clause| clause.term.is_task_filter())
+            .all(|clause| {
+                let hit = match &clause.term {
+                    Term::Status(TaskStatusFilter::Open) => !task.status.is_closed(),
+                    Term::Status(TaskStatusFilter::Closed) => task.status.is_closed(),
+                    Term::Status(TaskStatusFilter::Is(status)) => task.status == *status,
+                    Term::Status(TaskStatusFilter::Any) => true,
+                    Term::Priority(priority) => task.priority == Some(*priority),
+                    Term::Due(cmp, date) => task.schedule.due.is_some_and(|d| cmp.holds(d, *date)),
+                    _ => true,
+                };
+                hit != clause.negated
+            })
+    }
+
+    /// Positive text and tag terms, suitable for highlighting matches.
+    pub fn highlight_terms(&self) -> Vec<String> {
+        self.clauses
+            .iter()
+            .filter(|clause| !clause.negated)
+            .filter_map(|clause| match &clause.term {
+                Term::Text(text) => Some(text.clone()),
+                Term::Tag(tag) => Some(format!("#{tag}")),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Builds a case-insensitive regex pattern that highlights the query's text terms.
+pub fn highlight_pattern(input: &str, today: NaiveDate) -> Option<String> {
+    let terms = parse_query(input, today).highlight_terms();
+    if terms.is_empty() {
+        return None;
+    }
+    let alternatives: Vec<String> = terms.iter().map(|t| regex::escape(t)).collect();
+    Some(format!("(?i){}", alternatives.join("|")))
+}
+
+/// Splits on whitespace while keeping double-quoted sections together (quotes are kept).
+fn split_tokens(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for ch in input.chars() {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+            current.push(ch);
+        } else if ch.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(ch);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn parse_clause(raw: &str, today: NaiveDate) -> Option<Clause> {
+    let (negated, body) = match raw.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, raw),
+    };
+
+    let term = if body.starts_with('"') {
+        Term::Text(unquote(body).to_lowercase())
+    } else if let Some((field, value)) = body.split_once(':')
+        && let Some(term) = parse_field(&field.to_lowercase(), &unquote(value), today)
+    {
+        term
+    } else {
+        Term::Text(body.to_lowercase())
+    };
+
+    if matches!(&term, Term::Text(text) | Term::Tag(text) if text.is_empty()) {
+        return None;
+    }
+    Some(Clause { negated, term })
+}
+
+fn parse_field(field: &str, value: &str, today: NaiveDate) -> Option<Term> {
+    let value = value.trim();
+    match field {
+        "tag" => Some(Term::Tag(value.trim_start_matches('#').to_lowercase())),
+        "is" => match value.to_lowercase().as_str() {
+            "open" | "todo" => Some(Term::Status(TaskStatusFilter::Open)),
+            "closed" => Some(Term::Status(TaskStatusFilter::Closed)),
+            "done" => Some(Term::Status(TaskStatusFilter::Is(TaskStatus::Done))),
+            "doing" | "wip" | "in-progress" => {
+                Some(Term::Status(TaskStatusFilter::Is(TaskStatus::InProgress)))
+            }
+            "cancelled" | "canceled" => {
+                Some(Term::Status(TaskStatusFilter::Is(TaskStatus::Cancelled)))
+            }
+            "deferred" => Some(Term::Status(TaskStatusFilter::Is(TaskStatus::Deferred))),
+            "task" => Some(Term::Status(TaskStatusFilter::Any)),
+            _ => None,
+        },
+        "priority" | "pri" => match value.to_lowercase().as_str() {
+            "high" => Some(Priority::High),
+            "medium" => Some(Priority::Medium),
+            "low" => Some(Priority::Low),
+            other => {
+                let mut chars = other.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Priority::from_char(c),
+                    _ => None,
+                }
+            }
+        }
+        .map(Term::Priority),
+        "due" => {
+            let (cmp, rest) = parse_comparison(value);
+            parse_relative_date_input(rest, today).map(|date| Term::Due(cmp, date))
+        }
+        "after" => parse_relative_date_input(value, today).map(Term::After),
+        "before" => parse_relative_date_input(value, today).map(Term::Before),
+        _ => None,
+    }
+}
+
+fn parse_comparison(value: &str) -> (Comparison, &str) {
+    for (prefix, cmp) in [
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+        ("=", Comparison::Eq),
+    ] {
+        if let Some(rest) = value.strip_prefix(prefix) {
+            return (cmp, rest);
+        }
+    }
+    (Comparison::Eq, value)
+}
+
+fn unquote(value: &str) -> String {
+    value.replace('"', "")
+}
+
+fn content_has_tag(content: &str, tag: &str) -> bool {
+    content.split_whitespace().any(|word| {
+        let word = word.trim_end_matches(|c: char| ",.;:!?)]".contains(c));
+        let Some(name) = word.strip_prefix('#') else {
+            return false;
+        };
+        name == tag
+            || name
+                .strip_prefix(tag)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("valid date")
+    }
+
+    fn entry(file_date: &str, content: &str) -> LogEntry {
+        LogEntry {
+            content: content.to_string(),
+            file_path: format!("/logs/{file_date}.md"),
+            line_number: 0,
+            end_line: content.lines().count().saturating_sub(1),
+            date: Some(date(file_date)),
+        }
+    }
+
+    #[test]
+    fn parse_query_builds_ast() {
+        let today = date("2025-01-15");
+        let query = parse_query(
+            r#"tag:#Work is:open priority:A due:<2025-02-01 after:2025-01-01 "Exact Phrase" -draft"#,
+            today,
+        );
+        let terms: Vec<(bool, Term)> = query
+            .clauses
+            .into_iter()
+            .map(|c| (c.negated, c.term))
+            .collect();
+        assert_eq!(
+            terms,
+            vec![
+                (false, Term::Tag("work".to_string())),
+                (false, Term::Status(TaskStatusFilter::Open)),
+                (false, Term::Priority(Priority::High)),
+                (false, Term::Due(Comparison::Lt, date("2025-02-01"))),
+                (false, Term::After(date("2025-01-01"))),
+                (false, Term::Text("exact phrase".to_string())),
+                (true, Term::Text("draft".to_string())),
+            ]
+        );
+
+        let fallback = parse_query("url:http://x priority:Z", today);
+        assert_eq!(
+            fallback.clauses[0].term,
+            Term::Text("url:http://x".to_string())
+        );
+        assert_eq!(
+            fallback.clauses[1].term,
+            Term::Text("priority:z".to_string())
+        );
+    }
+
+    #[test]
+    fn query_matches_text_tags_and_dates() {
+        let today = date("2025-01-15");
+        let e = entry(
+            "2025-01-10",
+            "## [09:00:00]\nShipped the Login fix #work/backend",
+        );
+
+        assert!(parse_query("login", today).matches(&e));
+        assert!(parse_query("\"the login\"", today).matches(&e));
+        assert!(!parse_query("\"login the\"", today).matches(&e));
+        assert!(parse_query("tag:work", today).matches(&e));
+        assert!(!parse_query("tag:wor", today).matches(&e));
+        assert!(!parse_query("login -shipped", today).matches(&e));
+        assert!(parse_query("after:2025-01-10 before:2025-01-11", today).matches(&e));
+        assert!(!parse_query("after:2025-01-11", today).matches(&e));
+    }
+
+    #[test]
+    fn task_filters_must_match_same_task() {
+        let today = date("2025-01-15");
+        let e = entry(
+            "2025-01-10",
+            "## [09:00:00]\n- [x] [#A] done thing\n- [ ] [#C] open thing @due(2025-01-20)",
+        );
+
+        assert!(parse_query("is:open priority:C", today).matches(&e));
+        assert!(!parse_query("is:open priority:A", today).matches(&e));
+        assert!(parse_query("is:done priority:A", today).matches(&e));
+        assert!(parse_query("due:<=2025-01-20 is:open", today).matches(&e));
+        assert!(!parse_query("due:<2025-01-20", today).matches(&e));
+        assert!(parse_query("is:task -is:done", today).matches(&e));
+        assert!(!parse_query("is:open", today).matches(&entry("2025-01-10", "no tasks")));
+    }
+
+    #[test]
+    fn highlight_pattern_uses_positive_text_terms() {
+        let today = date("2025-01-15");
+        assert_eq!(
+            highlight_pattern("is:open a.b -skip tag:x", today).as_deref(),
+            Some(r"(?i)a\.b|\#x")
+        );
+        assert_eq!(highlight_pattern("is:open", today), None);
+    }
+}
@@ -0,0 +1,447 @@
+//! Ranked full-text search: tokenization, BM25 scoring with a recency boost, and snippets.
+//!
+//! Latin/numeric text is split into lowercase words. Korean, Chinese and Japanese runs are
+//! indexed as overlapping character bigrams, which matches across particles and compounds
+//! without needing a dictionary. Single CJK characters are also counted so one-character
+//! queries still match inside longer runs.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::models::LogEntry;
+
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+/// Maximum extra weight given to today's entries; it halves every `RECENCY_HALF_LIFE_DAYS`.
+const RECENCY_WEIGHT: f64 = 0.3;
+const RECENCY_HALF_LIFE_DAYS: f64 = 180.0;
+const SNIPPET_CHARS: usize = 120;
+const SNIPPET_LEAD_CHARS: usize = 30;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub term: String,
+    /// Byte range of the token in the source text.
+    pub range: Range<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snippet {
+    pub text: String,
+    /// Byte offset of `text` within the entry content.
+    pub offset: usize,
+    /// Byte ranges of matched terms, relative to `text`.
+    pub matches: Vec<Range<usize>>,
+}
+
+/// Per-entry term counts, computed once when a file is indexed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermStats {
+    /// Number of tokens in the entry (BM25 document length). CJK unigrams are not counted.
+    pub len: usize,
+    pub terms: HashMap<String, u32>,
+}
+
+impl TermStats {
+    pub fn from_text(text: &str) -> Self {
+        let tokens = tokenize(text);
+        let mut terms: HashMap<String, u32> = HashMap::new();
+        for token in &tokens {
+            *terms.entry(token.term.clone()).or_insert(0) += 1;
+        }
+        for token in cjk_unigrams(text) {
+            // Lone CJK characters are already emitted by `tokenize` as their own term.
+            if !is_lone_cjk(text, &token.range) {
+                *terms.entry(token.term).or_insert(0) += 1;
+            }
+        }
+        Self {
+            len: tokens.len(),
+            terms,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SearchHit {
+    pub entry: LogEntry,
+    pub score: f64,
+    pub snippet: Snippet,
+}
+
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word_start: Option<usize> = None;
+    let mut cjk_run: Vec<(usize, char)> = Vec::new();
+
+    let flush_word = |tokens: &mut Vec<Token>, start: &mut Option<usize>, end: usize| {
+        if let Some(s) = start.take() {
+            tokens.push(Token {
+                term: text[s..end].to_lowercase(),
+                range: s..end,
+            });
+        }
+    };
+
+    for (idx, ch) in text.char_indices() {
+        if is_cjk(ch) {
+            flush_word(&mut tokens, &mut word_start, idx);
+            cjk_run.push((idx, ch));
+            continue;
+        }
+        flush_cjk_run(&mut tokens, &mut cjk_run);
+        if ch.is_alphanumeric() {
+            word_start.get_or_insert(idx);
+        } else {
+            flush_word(&mut tokens, &mut word_start, idx);
+        }
+    }
+    flush_word(&mut tokens, &mut word_start, text.len());
+    flush_cjk_run(&mut tokens, &mut cjk_run);
+    tokens
+}
+
+fn flush_cjk_run(tokens: &mut Vec<Token>, run: &mut Vec<(usize, char)>) {
+    match run.len() {
+        0 => {}
+        1 => {
+            let (idx, ch) = run[0];
+            tokens.push(Token {
+                term: ch.to_string(),
+                range: idx..idx + ch.len_utf8(),
+            });
+        }
+        _ => {
+            for pair in run.windows(2) {
+                let (start, first) = pair[0];
+                let (second_idx, second) = pair[1];
+                tokens.push(Token {
+                    term: format!("{first}{second}"),
+                    range: start..second_idx + second.len_utf8(),
+                });
+            }
+        }
+    }
+    run.clear();
+}
+
+/// Every CJK character in `text` as a single-character token.
+fn cjk_unigrams(text: &str) -> impl Iterator<Item = Token> + '_ {
+    text.char_indices()
+        .filter(|(_, ch)| is_cjk(*ch))
+        .map(|(idx, ch)| Token {
+            term: ch.to_string(),
+            range: idx..idx + ch.len_utf8(),
+        })
+}
+
+fn is_lone_cjk(text: &str, range: &Range<usize>) -> bool {
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+    !before.is_some_and(is_cjk) && !after.is_some_and(is_cjk)
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{1100}'..='\u{11FF}'   // Hangul Jamo
+            | '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+            | '\u{3130}'..='\u{318F}' // Hangul Compatibility Jamo
+            | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+            | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+            | '\u{AC00}'..='\u{D7A3}' // Hangul Syllables
+            | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+    )
+}
+
+/// Unique query terms from free-form text (keywords, phrases, tag names).
+pub fn query_terms<S: AsRef<str>>(parts: &[S]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    parts
+        .iter()
+        .flat_map(|part| tokenize(part.as_ref()))
+        .map(|token| token.term)
+        .filter(|term| seen.insert(term.clone()))
+        .collect()
+}
+
+/// Scores `corpus` against `terms` with BM25 and a recency boost.
+///
+/// Each entry comes with its cached `TermStats`. Corpus statistics cover every entry; only
+/// entries accepted by `include` are returned. With `require_match`, entries without any
+/// query term are dropped. Results are sorted by score, then newest first.
+pub fn rank_entries(
+    corpus: Vec<(LogEntry, &TermStats)>,
+    terms: &[String],
+    today: NaiveDate,
+    require_match: bool,
+    include: impl Fn(&LogEntry) -> bool,
+) -> Vec<SearchHit> {
+    let term_set: HashSet<&str> = terms.iter().map(|t| t.as_str()).collect();
+    let doc_count = corpus.len().max(1) as f64;
+    let avg_len = corpus.iter().map(|(_, stats)| stats.len).sum::<usize>() as f64 / doc_count;
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for (_, stats) in &corpus {
+        for term in &term_set {
+            if stats.terms.contains_key(*term) {
+                *doc_freq.entry(*term).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut hits: Vec<(SearchHit, Option<NaiveDate>)> = Vec::new();
+    for (entry, stats) in corpus {
+        if !include(&entry) {
+            continue;
+        }
+        let tf: Vec<(&str, u32)> = term_set
+            .iter()
+            .filter_map(|term| stats.terms.get(*term).map(|freq| (*term, *freq)))
+            .collect();
+        if require_match && tf.is_empty() {
+            continue;
+        }
+
+        let doc_len = stats.len as f64;
+        let mut score = 0.0;
+        for (term, freq) in &tf {
+            let df = doc_freq.get(term).copied().unwrap_or(0) as f64;
+            let idf = (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln();
+            let freq = *freq as f64;
+            let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * doc_len / avg_len.max(1.0));
+            score += idf * freq * (BM25_K1 + 1.0) / (freq + norm);
+        }
+
+        let date = entry.date;
+        if let Some(date) = date {
+            let age_days = (today - date).num_days().max(0) as f64;
+            score *= 1.0 + RECENCY_WEIGHT * 0.5f64.powf(age_days / RECENCY_HALF_LIFE_DAYS);
+        }
+
+        let snippet = build_snippet(&entry.content, &match_tokens(&entry.content), &term_set);
+        hits.push((
+            SearchHit {
+                entry,
+                score,
+                snippet,
+            },
+            date,
+        ));
+    }
+
+    hits.sort_by(|(a, date_a), (b, date_b)| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| date_b.cmp(date_a))
+            .then_with(|| b.entry.line_number.cmp(&a.entry.line_number))
+    });
+    hits.into_iter().map(|(hit, _)| hit).collect()
+}
+
+/// Tokens used to locate matches in a snippet, including CJK unigrams for one-character terms.
+fn match_tokens(content: &str) -> Vec<Token> {
+    let mut tokens = tokenize(content);
+    tokens.extend(cjk_unigrams(content).filter(|t| !is_lone_cjk(content, &t.range)));
+    tokens.sort_by_key(|t| t.range.start);
+    tokens
+}
+
+/// Picks the line with the most distinct matched terms and cuts a window around the first match.
+fn build_snippet(content: &str, tokens: &[Token], terms: &HashSet<&str>) -> Snippet {
+    let matched: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| terms.contains(t.term.as_str()))
+        .collect();
+
+    let mut best: Option<(usize, Range<usize>)> = None;
+    let mut line_start = 0usize;
+    for line in content.split('\n') {
+        let line_range = line_start..line_start + line.len();
+        line_start = line_range.end + 1;
+        let distinct: HashSet<&str> = matched
+            .iter()
+            .filter(|t| line_range.contains(&t.range.start))
+            .map(|t| t.term.as_str())
+            .collect();
+        let is_better = match &best {
+            Some((count, _)) => distinct.len() > *count,
+            None => !line.trim().is_empty() && !crate::models::is_timestamped_line(line),
+        };
+        if is_better {
+            best = Some((distinct.len(), line_range));
+        }
+    }
+    let Some((_, line_range)) = best else {
+        return Snippet::default();
+    };
+
+    let first_match = matched
+        .iter()
+        .find(|t| line_range.contains(&t.range.start))
+        .map(|t| t.range.start)
+        .unwrap_or(line_range.start);
+    let line = &content[line_range.clone()];
+    let lead_start = line[..first_match - line_range.start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_LEAD_CHARS - 1)
+        .map(|(idx, _)| line_range.start + idx)
+        .unwrap_or(line_range.start);
+    let start = if first_match - line_range.start > SNIPPET_LEAD_CHARS * 2 {
+        lead_start
+    } else {
+        line_range.start
+    };
+    let end = content[start..line_range.end]
+        .char_indices()
+        .nth(SNIPPET_CHARS)
+        .map(|(idx, _)| start + idx)
+        .unwrap_or(line_range.end);
+
+    let matches = matched
+        .iter()
+        .filter(|t| t.range.start >= start && t.range.end <= end)
+        .map(|t| t.range.start - start..t.range.end - start)
+        .collect();
+    Snippet {
+        text: content[start..end].to_string(),
+        offset: start,
+        matches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file_date: &str, line: usize, content: &str) -> LogEntry {
+        LogEntry {
+            content: content.to_string(),
+            file_path: format!("/logs/{file_date}.md"),
+            line_number: line,
+            end_line: line + content.lines().count().saturating_sub(1),
+            date: Some(date(file_date)),
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("valid date")
+    }
+
+    fn rank(
+        corpus: Vec<LogEntry>,
+        terms: &[String],
+        today: NaiveDate,
+        require_match: bool,
+    ) -> Vec<SearchHit> {
+        let stats: Vec<TermStats> = corpus
+            .iter()
+            .map(|entry| TermStats::from_text(&entry.content))
+            .collect();
+        rank_entries(
+            corpus.into_iter().zip(&stats).collect(),
+            terms,
+            today,
+            require_match,
+            |_| true,
+        )
+    }
+
+    #[test]
+    fn tokenize_handles_latin_and_cjk() {
+        let terms: Vec<String> = tokenize("Deploy v2 회의록을 정리")
+            .into_iter()
+            .map(|t| t.term)
+            .collect();
+        assert_eq!(terms, vec!["deploy", "v2", "회의", "의록", "록을", "정리"]);
+
+        let text = "a 東京";
+        let tokens = tokenize(text);
+        assert_eq!(&text[tokens[1].range.clone()], "東京");
+        assert_eq!(
+            query_terms(&["회의록", "Deploy deploy"]),
+            vec!["회의", "의록", "deploy"]
+        );
+    }
+
+    #[test]
+    fn rank_entries_prefers_relevant_and_recent_entries() {
+        let today = date("2025-06-01");
+        let corpus = vec![
+            entry("2025-05-30", 0, "## [09:00:00]\nLunch with the team"),
+            entry(
+                "2024-01-10",
+                0,
+                "## [09:00:00]\nRelease notes for the release",
+            ),
+            entry(
+                "2025-05-31",
+                0,
+                "## [09:00:00]\nRelease notes for the release",
+            ),
+            entry("2025-05-31", 4, "## [10:00:00]\nRelease party"),
+        ];
+        let hits = rank(corpus, &query_terms(&["release"]), today, true);
+        let order: Vec<(String, usize)> = hits
+            .iter()
+            .map(|h| (h.entry.file_path.clone(), h.entry.line_number))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("/logs/2025-05-31.md".to_string(), 0),
+                ("/logs/2025-05-31.md".to_string(), 4),
+                ("/logs/2024-01-10.md".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn snippet_reports_match_offsets() {
+        let content = "## [09:00:00]\nIntro line\nWe discussed the 회의록 and the budget";
+        let hits = rank(
+            vec![entry("2025-06-01", 0, content)],
+            &query_terms(&["budget", "회의록"]),
+            date("2025-06-01"),
+            true,
+        );
+        let snippet = &hits[0].snippet;
+        assert_eq!(snippet.text, "We discussed the 회의록 and the budget");
+        assert_eq!(&content[snippet.offset..snippet.offset + 2], "We");
+        let matched: Vec<&str> = snippet
+            .matches
+            .iter()
+            .map(|r| &snippet.text[r.clone()])
+            .collect();
+        assert_eq!(matched, vec!["회의", "의록", "budget"]);
+    }
+
+    #[test]
+    fn single_cjk_character_matches_inside_longer_runs() {
+        let content = "## [09:00:00]\n회의록 정리";
+        let hits = rank(
+            vec![
+                entry("2025-06-01", 0, content),
+                entry("2025-06-01", 3, "## [10:00:00]\n점심 약속"),
+            ],
+            &query_terms(&["록"]),
+            date("2025-06-01"),
+            true,
+        );
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entry.line_number, 0);
+        let snippet = &hits[0].snippet;
+        let matched: Vec<&str> = snippet
+            .matches
+            .iter()
+            .map(|r| &snippet.text[r.clone()])
+            .collect();
+        assert_eq!(matched, vec!["록"]);
+
+        // A lone CJK character is counted once, not as both a token and a unigram.
+        assert_eq!(TermStats::from_text("a 東 b").terms.get("東"), Some(&1));
+        assert_eq!(TermStats::from_text("東京").len, 1);
+    }
+}
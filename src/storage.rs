@@ -1,16 +1,54 @@
 use crate::models::{
-    AgendaItem, AgendaItemKind, FoldOverride, Priority, LogEntry, TaskItem, TaskSchedule,
+    self, AgendaItem, AgendaItemKind, FoldOverride, HeadingFormat, Priority, LogEntry, TaskItem,
+    TaskInstance, TaskRef, TaskSchedule, TaskStatus, TimeSpent, split_task_checkbox,
     count_trailing_tomatoes,
     is_heading_timestamp_line, is_timestamped_line, strip_timestamp_prefix, strip_trailing_tomatoes,
 };
+use crate::backups;
+use crate::block_ids::{self, split_block_id, strip_block_id, with_block_id};
+use crate::config::DataConfig;
+use crate::file_layout::{self, FileLayout, parse_day_heading};
+use crate::recurrence::Recurrence;
+use crate::search_index;
+use crate::search_query;
+use crate::search_rank::{self, SearchHit};
 use crate::task_metadata::{
-    TaskMetadataKey, parse_task_metadata, strip_task_metadata_tokens, upsert_task_metadata_token,
+    DONE_FORMAT, TaskMetadataKey, emoji_syntax, format_due, parse_priority_emoji,
+    parse_task_metadata, remove_task_metadata_token, strip_task_metadata_tokens,
+    upsert_task_metadata_token,
 };
-use chrono::{Duration, Local, NaiveDate};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
-use std::io::{self, Write};
+use std::fmt;
+use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DONE_TIMESTAMPS: AtomicBool = AtomicBool::new(false);
+
+/// Whether completed tasks get a `@done(...)` stamp (`data.done_timestamps`).
+pub fn done_timestamps_enabled() -> bool {
+    DONE_TIMESTAMPS.load(Ordering::Relaxed)
+}
+
+/// Applies the `[data]` settings that control how log files are written.
+pub fn configure(data: &DataConfig) {
+    backups::configure(&data.log_path, data.backup_count);
+    block_ids::set_enabled(data.block_ids);
+    DONE_TIMESTAMPS.store(data.done_timestamps, Ordering::Relaxed);
+    let layout = FileLayout::parse(&data.file_layout).unwrap_or_else(|err| {
+        eprintln!("Invalid data.file_layout ({err}); using the default layout.");
+        FileLayout::default()
+    });
+    file_layout::configure(layout);
+    let heading = HeadingFormat::new(&data.entry_heading, &data.time_format)
+        .unwrap_or_else(|err| {
+            eprintln!("Invalid entry heading format ({err}); using `## [HH:MM:SS]`.");
+            HeadingFormat::default()
+        });
+    models::configure_heading_format(heading);
+}
 
 pub fn ensure_log_dir(log_path: &Path) -> io::Result<()> {
     let path = PathBuf::from(log_path);
@@ -21,16 +59,15 @@ pub fn ensure_log_dir(log_path: &Path) -> io::Result<()> {
 }
 
 fn get_today_file_path(log_path: &Path) -> PathBuf {
-    let today = Local::now().format("%Y-%m-%d").to_string();
-    let mut path = PathBuf::from(log_path);
-    path.push(format!("{}.md", today));
-    path
+    file_layout::current().path_for_date(log_path, Local::now().date_naive())
 }
 
-fn get_file_path_for_date(log_path: &Path, date: &str) -> PathBuf {
-    let mut path = PathBuf::from(log_path);
-    path.push(format!("{date}.md"));
-    path
+/// Path of the file holding `date` (`YYYY-MM-DD`) under the configured file layout.
+pub(crate) fn get_file_path_for_date(log_path: &Path, date: &str) -> PathBuf {
+    match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(date) => file_layout::current().path_for_date(log_path, date),
+        Err(_) => log_path.join(format!("{date}.md")),
+    }
 }
 
 pub fn append_entry(log_path: &Path, content: &str) -> io::Result<()> {
@@ -47,11 +84,21 @@ pub fn append_entry_to_date(
     let date_str = date.format("%Y-%m-%d").to_string();
     let path = get_file_path_for_date(log_path, &date_str);
 
-    let time = Local::now().format("%H:%M:%S").to_string();
-    let entry_body = content.trim_end_matches('\n');
-    let mut entry = format!("## [{}]\n", time);
+    let mut existing = fs::read_to_string(&path).unwrap_or_default();
+
+    let mut heading = models::heading_format().heading(Local::now().time());
+    let mut entry_body = content.trim_end_matches('\n').to_string();
+    if block_ids::enabled() {
+        let mut lines: Vec<String> = std::iter::once(heading)
+            .chain(entry_body.lines().map(str::to_string))
+            .collect();
+        block_ids::assign_missing(&mut lines, &existing);
+        heading = lines.remove(0);
+        entry_body = lines.join("\n");
+    }
+    let mut entry = format!("{heading}\n");
     if !entry_body.is_empty() {
-        entry.push_str(entry_body);
+        entry.push_str(&entry_body);
         if !entry.ends_with('\n') {
             entry.push('\n');
         }
@@ -63,20 +110,55 @@ pub fn append_entry_to_date(
         entry.push('\n');
     }
 
-    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
-    if path.exists() && path.metadata()?.len() > 0 {
-        let existing = fs::read_to_string(&path).unwrap_or_default();
-        if !existing.ends_with("\n\n") {
-            if existing.ends_with('\n') {
-                file.write_all(b"\n")?;
-            } else {
-                file.write_all(b"\n\n")?;
-            }
-        }
+    if file_layout::current().is_shared() {
+        let content = insert_into_day_section(&existing, date, &entry);
+        return backups::write_log_file(&path, &content);
     }
 
-    file.write_all(entry.as_bytes())?;
-    Ok(())
+    if !existing.is_empty() && !existing.ends_with("\n\n") {
+        if existing.ends_with('\n') {
+            existing.push('\n');
+        } else {
+            existing.push_str("\n\n");
+        }
+    }
+    existing.push_str(&entry);
+    backups::write_log_file(&path, &existing)
+}
+
+/// Inserts `entry` at the end of `date`'s section of a weekly or monthly file, adding the
+/// day heading (in date order) when the file has no section for that day yet.
+fn insert_into_day_section(existing: &str, date: NaiveDate, entry: &str) -> String {
+    let lines: Vec<&str> = existing.lines().collect();
+    let headings: Vec<(usize, NaiveDate)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| parse_day_heading(line).map(|day| (idx, day)))
+        .collect();
+    let section = headings.iter().position(|(_, day)| *day == date);
+    let insert_at = match section {
+        Some(pos) => headings.get(pos + 1).map(|(idx, _)| *idx),
+        None => headings
+            .iter()
+            .find(|(_, day)| *day > date)
+            .map(|(idx, _)| *idx),
+    }
+    .unwrap_or(lines.len());
+
+    let mut content = lines[..insert_at].join("\n").trim_end().to_string();
+    if !content.is_empty() {
+        content.push_str("\n\n");
+    }
+    if section.is_none() {
+        content.push_str(&file_layout::day_heading(date));
+        content.push_str("\n\n");
+    }
+    content.push_str(entry);
+    if insert_at < lines.len() {
+        content.push_str(&lines[insert_at..].join("\n"));
+        content.push('\n');
+    }
+    content
 }
 
 pub fn read_today_entries(log_path: &Path) -> io::Result<Vec<LogEntry>> {
@@ -89,8 +171,12 @@ pub fn read_today_entries(log_path: &Path) -> io::Result<Vec<LogEntry>> {
 
     let path_str = path.to_string_lossy().to_string();
     let content = fs::read_to_string(&path)?;
+    let today = Local::now().date_naive();
 
-    Ok(parse_log_content(&content, &path_str))
+    Ok(parse_log_content(&content, &path_str)
+        .into_iter()
+        .filter(|entry| entry.date == Some(today))
+        .collect())
 }
 
 /// Reads log entries for a date range (inclusive).
@@ -103,19 +189,23 @@ pub fn read_entries_for_date_range(
     ensure_log_dir(log_path)?;
     let mut all_entries = Vec::new();
 
-    let mut current = start_date;
+    let layout = file_layout::current();
+    let mut current = layout.period_start(start_date);
     while current <= end_date {
-        let date_str = current.format("%Y-%m-%d").to_string();
-        let path = get_file_path_for_date(log_path, &date_str);
+        let path = layout.path_for_date(log_path, current);
 
         if path.exists() {
             let path_str = path.to_string_lossy().to_string();
             if let Ok(content) = fs::read_to_string(&path) {
                 let entries = parse_log_content(&content, &path_str);
-                all_entries.extend(entries);
+                all_entries.extend(entries.into_iter().filter(|entry| {
+                    entry
+                        .date
+                        .is_some_and(|date| date >= start_date && date <= end_date)
+                }));
             }
         }
-        current += Duration::days(1);
+        current = next_period_start(&layout, current);
     }
 
     Ok(all_entries)
@@ -132,28 +222,67 @@ pub fn read_entry_containing_line(
         .find(|entry| entry.line_number <= line_number && line_number <= entry.end_line))
 }
 
+/// First day of the file after the one starting at `start`.
+fn next_period_start(layout: &FileLayout, start: NaiveDate) -> NaiveDate {
+    let mut next = start + Duration::days(1);
+    while layout.period_start(next) == start {
+        next += Duration::days(1);
+    }
+    next
+}
+
 /// Returns a list of available log dates in the log directory (sorted ascending).
+/// Weekly and monthly files contribute every day that has a day heading.
 pub fn get_available_log_dates(log_path: &Path) -> io::Result<Vec<NaiveDate>> {
     ensure_log_dir(log_path)?;
-    let dir = PathBuf::from(log_path);
+    let layout = file_layout::current();
     let mut dates = Vec::new();
 
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("md")
-                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
-                && let Ok(date) = NaiveDate::parse_from_str(stem, "%Y-%m-%d")
-            {
-                dates.push(date);
-            }
+    for (date, path) in layout.list_files(log_path)? {
+        if !layout.is_shared() {
+            dates.push(date);
+            continue;
+        }
+        if let Ok(content) = fs::read_to_string(&path) {
+            dates.extend(content.lines().filter_map(parse_day_heading));
         }
     }
 
     dates.sort();
+    dates.dedup();
     Ok(dates)
 }
 
+/// Day each line of a log file belongs to: the date in the file name for daily layouts,
+/// or the nearest day heading above the line in weekly and monthly files.
+pub(crate) fn line_dates(content: &str, path_str: &str) -> Vec<Option<NaiveDate>> {
+    let layout = file_layout::current();
+    if !layout.is_shared() {
+        let date = layout.date_for_path(Path::new(path_str));
+        return vec![date; content.lines().count()];
+    }
+    let mut current = None;
+    content
+        .lines()
+        .map(|line| {
+            if let Some(date) = parse_day_heading(line) {
+                current = Some(date);
+            }
+            current
+        })
+        .collect()
+}
+
+/// Lines of `content` that belong to `date`; all of them for daily layouts.
+fn lines_for_date<'a>(content: &'a str, path_str: &str, date: NaiveDate) -> Vec<&'a str> {
+    content
+        .lines()
+        .zip(line_dates(content, path_str))
+        .filter(|(_, line_date)| *line_date == Some(date))
+        .map(|(line, _)| line)
+        .collect()
+}
+
 /// Returns the earliest available log date, if any.
 pub fn get_earliest_log_date(log_path: &Path) -> io::Result<Option<NaiveDate>> {
     let dates = get_available_log_dates(log_path)?;
@@ -170,7 +299,11 @@ pub fn read_today_tasks(log_path: &Path) -> io::Result<Vec<TaskItem>> {
 
     let path_str = path.to_string_lossy().to_string();
     let content = fs::read_to_string(&path)?;
-    Ok(parse_task_content(&content, &path_str))
+    let today = Local::now().date_naive();
+    Ok(parse_task_content(&content, &path_str)
+        .into_iter()
+        .filter(|task| task.date == Some(today))
+        .collect())
 }
 
 /// Reads task items for a date range (inclusive), returning agenda items.
@@ -180,19 +313,26 @@ pub fn read_tasks_for_date_range(
     end_date: NaiveDate,
 ) -> io::Result<Vec<AgendaItem>> {
     ensure_log_dir(log_path)?;
+    let today = Local::now().date_naive();
     let mut items = Vec::new();
-    let dates = get_available_log_dates(log_path).unwrap_or_default();
-    for date in dates {
-        let date_str = date.format("%Y-%m-%d").to_string();
-        let path = get_file_path_for_date(log_path, &date_str);
-        if !path.exists() {
-            continue;
-        }
+    let files = file_layout::current()
+        .list_files(log_path)
+        .unwrap_or_default();
+    for (file_date, path) in files {
         let path_str = path.to_string_lossy().to_string();
         if let Ok(content) = fs::read_to_string(&path) {
             let tasks = parse_task_content(&content, &path_str);
             for task in tasks {
-                let agenda_date = agenda_date_for_task(&task, date);
+                let agenda_date = agenda_date_for_task(&task, task.date.unwrap_or(file_date));
+                if !task.status.is_closed() {
+                    items.extend(projected_occurrences(
+                        &task,
+                        agenda_date,
+                        today,
+                        start_date,
+                        end_date,
+                    ));
+                }
                 let is_unscheduled = task.schedule.is_empty();
                 if !is_unscheduled && (agenda_date < start_date || agenda_date > end_date) {
                     continue;
@@ -204,11 +344,15 @@ pub fn read_tasks_for_date_range(
                     duration_minutes: task.schedule.duration_minutes,
                     text: task.text,
                     indent: task.indent,
-                    is_done: task.is_done,
+                    status: task.status,
                     priority: task.priority,
                     schedule: task.schedule.clone(),
                     file_path: task.file_path,
                     line_number: task.line_number,
+                    block_id: task.block_id,
+                    projected: false,
+                    tomato_count: task.tomato_count,
+                    line_date: task.date,
                 });
             }
         }
@@ -217,6 +361,44 @@ pub fn read_tasks_for_date_range(
     Ok(items)
 }
 
+/// Future occurrences of an open recurring task within `start_date..=end_date`. They point
+/// at the task's own line; completing it is what writes the next one to the log.
+fn projected_occurrences(
+    task: &TaskItem,
+    anchor: NaiveDate,
+    today: NaiveDate,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Vec<AgendaItem> {
+    let Some(recurrence) = task.schedule.recurrence.as_ref() else {
+        return Vec::new();
+    };
+    recurrence
+        .occurrences_between(anchor, today, start_date, end_date)
+        .into_iter()
+        .map(|date| {
+            let schedule = roll_schedule(&task.schedule, anchor, date);
+            AgendaItem {
+                kind: AgendaItemKind::Task,
+                date,
+                time: schedule.time,
+                duration_minutes: schedule.duration_minutes,
+                text: task.text.clone(),
+                indent: task.indent,
+                status: TaskStatus::Open,
+                priority: task.priority,
+                schedule,
+                file_path: task.file_path.clone(),
+                line_number: task.line_number,
+                block_id: task.block_id.clone(),
+                projected: true,
+                tomato_count: 0,
+                line_date: task.date,
+            }
+        })
+        .collect()
+}
+
 pub fn read_agenda_entries(
     log_path: &Path,
     start_date: NaiveDate,
@@ -242,18 +424,17 @@ fn read_note_entries(
 ) -> io::Result<Vec<AgendaItem>> {
     ensure_log_dir(log_path)?;
     let mut items = Vec::new();
-    let dates = get_available_log_dates(log_path).unwrap_or_default();
+    let files = file_layout::current()
+        .list_files(log_path)
+        .unwrap_or_default();
 
-    for date in dates {
-        let date_str = date.format("%Y-%m-%d").to_string();
-        let path = get_file_path_for_date(log_path, &date_str);
-        if !path.exists() {
-            continue;
-        }
+    for (file_date, path) in files {
         let content = fs::read_to_string(&path)?;
         let path_str = path.to_string_lossy().to_string();
+        let dates = line_dates(&content, &path_str);
 
         for (idx, line) in content.lines().enumerate() {
+            let date = dates.get(idx).copied().flatten().unwrap_or(file_date);
             if line.contains("System: Carryover Checked") {
                 continue;
             }
@@ -264,7 +445,7 @@ fn read_note_entries(
                 continue;
             }
 
-            let stripped = strip_timestamp_prefix(line);
+            let (stripped, block_id) = split_block_id(strip_timestamp_prefix(line));
             if stripped.trim().is_empty() {
                 continue;
             }
@@ -294,11 +475,15 @@ fn read_note_entries(
                 duration_minutes: schedule.duration_minutes,
                 text: text.trim().to_string(),
                 indent: indent_spaces.div_ceil(2),
-                is_done: false,
+                status: TaskStatus::Open,
                 priority: None,
                 schedule,
                 file_path: path_str.clone(),
                 line_number: idx,
+                block_id: block_id.map(str::to_string),
+                projected: false,
+                tomato_count: 0,
+                line_date: Some(date),
             });
         }
     }
@@ -306,122 +491,72 @@ fn read_note_entries(
     Ok(items)
 }
 
-pub fn search_entries(log_path: &Path, query: &str) -> io::Result<Vec<LogEntry>> {
-    ensure_log_dir(log_path)?;
-    let dir = PathBuf::from(log_path);
-    let mut results = Vec::new();
-
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                let path_str = path.to_string_lossy().to_string();
-
-                if let Ok(content) = fs::read_to_string(&path) {
-                    let parsed_entries = parse_log_content(&content, &path_str);
-                    for entry in parsed_entries {
-                        if entry.content.contains(query) {
-                            results.push(LogEntry {
-                                content: entry.content,
-                                file_path: entry.file_path,
-                                line_number: entry.line_number,
-                                end_line: entry.end_line,
-                            });
-                        }
-                    }
-                }
-            }
-        }
+/// Searches entries with the structured query syntax (see `search_query`).
+/// Results are ranked by relevance to the query's text terms, newest first on ties.
+pub fn search_entries(log_path: &Path, query: &str) -> io::Result<Vec<SearchHit>> {
+    let today = Local::now().date_naive();
+    let query = search_query::parse_query(query, today);
+    if query.is_empty() {
+        return Ok(Vec::new());
     }
-
-    Ok(results)
+    let terms = search_rank::query_terms(&query.highlight_terms());
+    search_index::with_index(log_path, |index| {
+        search_rank::rank_entries(index.ranking_corpus(), &terms, today, false, |entry| {
+            query.matches(entry)
+        })
+    })
 }
 
+/// Ranks entries against free-form keywords; entries matching none of them are dropped.
 pub fn search_entries_by_keywords(
     log_path: &Path,
     keywords: &[String],
-) -> io::Result<Vec<LogEntry>> {
-    ensure_log_dir(log_path)?;
-    let mut results: Vec<(usize, LogEntry)> = Vec::new();
-    let mut normalized = Vec::new();
-
-    for keyword in keywords {
-        let trimmed = keyword.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        normalized.push(trimmed.to_lowercase());
-    }
-
-    if normalized.is_empty() {
+) -> io::Result<Vec<SearchHit>> {
+    let terms = search_rank::query_terms(keywords);
+    if terms.is_empty() {
         return Ok(Vec::new());
     }
-
-    let mut paths: Vec<PathBuf> = Vec::new();
-    if let Ok(entries) = fs::read_dir(log_path) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                paths.push(path);
-            }
-        }
-    }
-    paths.sort();
-
-    for path in paths {
-        let path_str = path.to_string_lossy().to_string();
-        if let Ok(content) = fs::read_to_string(&path) {
-            let parsed_entries = parse_log_content(&content, &path_str);
-            for entry in parsed_entries {
-                let haystack = entry.content.to_lowercase();
-                let mut score = 0usize;
-                for keyword in &normalized {
-                    if haystack.contains(keyword) {
-                        score += 1;
-                    }
-                }
-                if score > 0 {
-                    results.push((score, entry));
-                }
-            }
-        }
-    }
-
-    results.sort_by(|(score_a, entry_a), (score_b, entry_b)| {
-        score_b
-            .cmp(score_a)
-            .then_with(|| entry_b.file_path.cmp(&entry_a.file_path))
-            .then_with(|| entry_b.line_number.cmp(&entry_a.line_number))
-    });
-
-    Ok(results.into_iter().map(|(_, entry)| entry).collect())
+    let today = Local::now().date_naive();
+    search_index::with_index(log_path, |index| {
+        search_rank::rank_entries(index.ranking_corpus(), &terms, today, true, |_| true)
+    })
 }
 
-fn parse_log_content(content: &str, path_str: &str) -> Vec<LogEntry> {
+pub(crate) fn parse_log_content(content: &str, path_str: &str) -> Vec<LogEntry> {
     let mut entries: Vec<LogEntry> = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
+    let dates = line_dates(content, path_str);
+    let shared = file_layout::current().is_shared();
+    // Day headings of weekly/monthly files are not entries; the next line starts a new one.
+    let mut new_section = true;
 
     for (i, line) in lines.iter().enumerate() {
         if line.contains("System: Carryover Checked") {
             continue;
         }
+        if shared && parse_day_heading(line).is_some() {
+            new_section = true;
+            continue;
+        }
 
         if line.trim().is_empty() {
-            if entries.is_empty() {
+            if entries.is_empty() || new_section {
                 continue;
             }
-            if next_non_empty_is_timestamp(&lines, i + 1) {
+            if next_non_empty_is_timestamp(&lines, i + 1, shared) {
                 continue;
             }
         }
 
-        if is_timestamped_line(line) || entries.is_empty() {
+        if is_timestamped_line(line) || entries.is_empty() || new_section {
             entries.push(LogEntry {
                 content: (*line).to_string(),
                 file_path: path_str.to_string(),
                 line_number: i,
                 end_line: i,
+                date: dates.get(i).copied().flatten(),
             });
+            new_section = false;
             continue;
         }
 
@@ -434,7 +569,7 @@ fn parse_log_content(content: &str, path_str: &str) -> Vec<LogEntry> {
     entries
 }
 
-fn next_non_empty_is_timestamp(lines: &[&str], start: usize) -> bool {
+fn next_non_empty_is_timestamp(lines: &[&str], start: usize, shared: bool) -> bool {
     for line in lines.iter().skip(start) {
         if line.contains("System: Carryover Checked") {
             continue;
@@ -442,7 +577,7 @@ fn next_non_empty_is_timestamp(lines: &[&str], start: usize) -> bool {
         if line.trim().is_empty() {
             continue;
         }
-        return is_timestamped_line(line);
+        return is_timestamped_line(line) || (shared && parse_day_heading(line).is_some());
     }
     false
 }
@@ -555,12 +690,12 @@ fn trailing_context_tag_start(text: &str) -> usize {
     tail_start
 }
 
-fn split_trailing_context_tags(text: &str) -> (&str, &str) {
+pub(crate) fn split_trailing_context_tags(text: &str) -> (&str, &str) {
     let start = trailing_context_tag_start(text);
     text.split_at(start)
 }
 
-fn strip_carryover_marker_at_end(text: &str) -> (String, Option<String>) {
+pub(crate) fn strip_carryover_marker_at_end(text: &str) -> (String, Option<String>) {
     let trimmed = text.trim_end();
     let open = '⟦';
     let close = '⟧';
@@ -631,6 +766,11 @@ fn strip_priority_marker(text: &str) -> String {
     chars.as_str().trim_start().to_string()
 }
 
+/// Identity of a task from its display text, as in `TaskItem::task_identity`.
+pub(crate) fn task_identity(text: &str) -> String {
+    task_identity_from_text(text).0
+}
+
 fn task_identity_from_text(text: &str) -> (String, Option<String>) {
     let without_priority = strip_priority_marker(text);
     let without_metadata = strip_task_metadata_tokens(&without_priority);
@@ -639,8 +779,9 @@ fn task_identity_from_text(text: &str) -> (String, Option<String>) {
     (normalize_task_text(&base), carryover_from)
 }
 
-fn parse_task_content(content: &str, path_str: &str) -> Vec<TaskItem> {
+pub(crate) fn parse_task_content(content: &str, path_str: &str) -> Vec<TaskItem> {
     let mut tasks: Vec<TaskItem> = Vec::new();
+    let dates = line_dates(content, path_str);
 
     for (i, line) in content.lines().enumerate() {
         if line.contains("System: Carryover Checked") {
@@ -652,19 +793,14 @@ fn parse_task_content(content: &str, path_str: &str) -> Vec<TaskItem> {
         let (indent_bytes, indent_spaces) = parse_indent(s);
         let s = &s[indent_bytes..];
 
-        let (is_done, text) = if let Some(text) = s.strip_prefix("- [ ] ") {
-            (false, text)
-        } else if let Some(text) = s.strip_prefix("- [x] ") {
-            (true, text)
-        } else if let Some(text) = s.strip_prefix("- [X] ") {
-            (true, text)
-        } else {
+        let Some((status, text)) = split_task_checkbox(s) else {
             continue;
         };
 
+        let (text, block_id) = split_block_id(text);
         let (text, tomato_count) = strip_trailing_tomatoes(text);
         let text = text.trim();
-        let priority = parse_priority_marker(text);
+        let priority = parse_priority_marker(text).or_else(|| parse_priority_emoji(text));
         let (schedule, display_text) = parse_task_metadata(text);
         let (task_identity, carryover_from) = task_identity_from_text(text);
         tasks.push(TaskItem {
@@ -673,11 +809,13 @@ fn parse_task_content(content: &str, path_str: &str) -> Vec<TaskItem> {
             tomato_count,
             file_path: path_str.to_string(),
             line_number: i,
-            is_done,
+            status,
             priority,
             schedule,
             task_identity,
             carryover_from,
+            block_id: block_id.map(str::to_string),
+            date: dates.get(i).copied().flatten(),
         });
     }
 
@@ -707,7 +845,7 @@ fn parse_indent(s: &str) -> (usize, usize) {
 struct ParsedTaskLine {
     identity: String,
     carryover_from: Option<String>,
-    is_done: bool,
+    status: TaskStatus,
     indent_level: usize,
     raw_text: String,
 }
@@ -721,17 +859,9 @@ fn parse_task_line(line: &str) -> Option<ParsedTaskLine> {
     let (indent_bytes, indent_spaces) = parse_indent(s);
     let s = &s[indent_bytes..];
 
-    let (is_done, text) = if let Some(text) = s.strip_prefix("- [ ] ") {
-        (false, text)
-    } else if let Some(text) = s.strip_prefix("- [x] ") {
-        (true, text)
-    } else if let Some(text) = s.strip_prefix("- [X] ") {
-        (true, text)
-    } else {
-        return None;
-    };
+    let (status, text) = split_task_checkbox(s)?;
 
-    let (text, _) = strip_trailing_tomatoes(text);
+    let (text, _) = strip_trailing_tomatoes(strip_block_id(text));
     let text = text.trim();
     let (raw_text, carryover_from) = strip_carryover_marker(text);
     let (identity, _) = task_identity_from_text(text);
@@ -739,21 +869,34 @@ fn parse_task_line(line: &str) -> Option<ParsedTaskLine> {
     Some(ParsedTaskLine {
         identity,
         carryover_from,
-        is_done,
+        status,
         indent_level: indent_spaces.div_ceil(2),
         raw_text: raw_text.to_string(),
     })
 }
 
 fn format_task_body(update: &TaskLineUpdate) -> String {
-    let checkbox = if update.is_done { "- [x] " } else { "- [ ] " };
-    let mut body = String::new();
-    body.push_str(checkbox);
-    if let Some(priority) = update.priority {
+    let mut body = update.status.checkbox().to_string();
+    if let Some(priority) = update.priority
+        && !emoji_syntax()
+    {
         body.push_str(&format!("[#{}] ", priority.as_char()));
     }
     body.push_str(update.text.trim());
-    apply_schedule_tokens(&body, &update.schedule)
+    let mut schedule = update.schedule.clone();
+    if !update.status.is_done() {
+        schedule.done = None;
+        schedule.done_time = None;
+    }
+    let body = apply_schedule_tokens(&body, &schedule);
+    match update.priority {
+        Some(priority) if emoji_syntax() => upsert_task_metadata_token(
+            &body,
+            TaskMetadataKey::Priority,
+            &priority.as_char().to_string(),
+        ),
+        _ => body,
+    }
 }
 
 fn apply_schedule_tokens(text: &str, schedule: &TaskSchedule) -> String {
@@ -765,7 +908,7 @@ fn apply_schedule_tokens(text: &str, schedule: &TaskSchedule) -> String {
     output = upsert_task_metadata_token(&output, TaskMetadataKey::Scheduled, &sched);
     let due = schedule
         .due
-        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map(|d| format_due(d, schedule.due_warning_days))
         .unwrap_or_default();
     output = upsert_task_metadata_token(&output, TaskMetadataKey::Due, &due);
     let start = schedule
@@ -783,9 +926,106 @@ fn apply_schedule_tokens(text: &str, schedule: &TaskSchedule) -> String {
         .map(|m| m.to_string())
         .unwrap_or_default();
     output = upsert_task_metadata_token(&output, TaskMetadataKey::Duration, &duration);
+    let every = schedule
+        .recurrence
+        .as_ref()
+        .map(Recurrence::to_string)
+        .unwrap_or_default();
+    output = upsert_task_metadata_token(&output, TaskMetadataKey::Every, &every);
+    let done = match (schedule.done, schedule.done_time) {
+        (Some(date), Some(time)) => date.and_time(time).format(DONE_FORMAT).to_string(),
+        (Some(date), None) => date.format("%Y-%m-%d").to_string(),
+        (None, _) => String::new(),
+    };
+    output = upsert_task_metadata_token(&output, TaskMetadataKey::Done, &done);
     output
 }
 
+/// Date a recurring task's rule counts from: its scheduled, due or start date, falling back
+/// to the day it was written.
+fn recurrence_anchor(schedule: &TaskSchedule, line_date: NaiveDate) -> NaiveDate {
+    schedule
+        .scheduled
+        .or(schedule.due)
+        .or(schedule.start)
+        .unwrap_or(line_date)
+}
+
+/// Moves every date in `schedule` by the distance from `anchor` to `next`. A schedule without
+/// dates gets `next` as its scheduled date.
+fn roll_schedule(schedule: &TaskSchedule, anchor: NaiveDate, next: NaiveDate) -> TaskSchedule {
+    let delta = next - anchor;
+    let mut rolled = schedule.clone();
+    for date in [&mut rolled.scheduled, &mut rolled.due, &mut rolled.start] {
+        *date = date.map(|date| date + delta);
+    }
+    if rolled.scheduled.is_none() && rolled.due.is_none() && rolled.start.is_none() {
+        rolled.scheduled = Some(next);
+    }
+    rolled
+}
+
+/// Open task line for the occurrence after the recurring task `line`, or `None` if the line
+/// is not a task with a valid `@every` rule. Tomatoes, carryover markers and block IDs stay
+/// with the completed line.
+fn next_occurrence_line(line: &str, line_date: NaiveDate, today: NaiveDate) -> Option<String> {
+    let stripped = strip_timestamp_prefix(line);
+    let (indent_bytes, _) = parse_indent(stripped);
+    let indent = &stripped[..indent_bytes];
+    let rest = &stripped[indent_bytes..];
+    let (_, text) = split_task_checkbox(rest)?;
+
+    let (text, _) = strip_trailing_tomatoes(strip_block_id(text));
+    let (text, _) = strip_carryover_marker(text.trim());
+    let text = remove_task_metadata_token(&text, TaskMetadataKey::Done);
+    let (schedule, _) = parse_task_metadata(&text);
+    let recurrence = schedule.recurrence.as_ref()?;
+    let anchor = recurrence_anchor(&schedule, line_date);
+    let next = recurrence.next_occurrence(anchor, today);
+    let rolled = roll_schedule(&schedule, anchor, next);
+    Some(format!(
+        "{indent}{}",
+        apply_schedule_tokens(&format!("- [ ] {text}"), &rolled)
+    ))
+}
+
+/// Inserts the next occurrence of the recurring task at `idx` below it and its subtasks.
+/// Nothing is added if that occurrence is already in the file, so toggling a task back and
+/// forth does not pile up copies.
+fn insert_next_occurrence(
+    lines: &mut Vec<String>,
+    idx: usize,
+    line_date: NaiveDate,
+    today: NaiveDate,
+) -> bool {
+    let Some(next_line) = next_occurrence_line(&lines[idx], line_date, today) else {
+        return false;
+    };
+    if lines
+        .iter()
+        .any(|line| strip_block_id(line).trim_end() == next_line)
+    {
+        return false;
+    }
+
+    let (_, task_indent) = parse_indent(strip_timestamp_prefix(&lines[idx]));
+    let mut insert_at = idx + 1;
+    while let Some(line) = lines.get(insert_at) {
+        let (_, indent) = parse_indent(line);
+        if line.trim().is_empty() || indent <= task_indent {
+            break;
+        }
+        insert_at += 1;
+    }
+
+    let mut new_lines = vec![next_line];
+    if block_ids::enabled() {
+        block_ids::assign_missing(&mut new_lines, &lines.join("\n"));
+    }
+    lines.splice(insert_at..insert_at, new_lines);
+    true
+}
+
 fn extract_carryover_marker(text: &str) -> Option<String> {
     let trimmed = text.trim_end();
     let (head, _) = split_trailing_context_tags(trimmed);
@@ -819,24 +1059,91 @@ fn split_note_prefix(text: &str) -> (String, &str) {
     (String::new(), text)
 }
 
+/// Status of the task checkbox on `line`, after any heading prefix and indentation.
+fn task_line_status(line: &str) -> Option<TaskStatus> {
+    let stripped = strip_timestamp_prefix(line);
+    let (indent_bytes, _) = parse_indent(stripped);
+    split_task_checkbox(&stripped[indent_bytes..]).map(|(status, _)| status)
+}
+
+/// `line` with its task checkbox rewritten to `status`; `None` if it is not a task line.
+/// With `data.done_timestamps`, completing a task stamps it with `@done(...)`. Any other
+/// status removes the stamp.
+fn with_task_status(line: &str, status: TaskStatus) -> Option<String> {
+    let now = done_timestamps_enabled().then(|| Local::now().naive_local());
+    with_task_status_at(line, status, now)
+}
+
+fn with_task_status_at(
+    line: &str,
+    status: TaskStatus,
+    now: Option<NaiveDateTime>,
+) -> Option<String> {
+    let stripped = strip_timestamp_prefix(line);
+    let (indent_bytes, _) = parse_indent(stripped);
+    let (prefix, body) = line.split_at(line.len() - stripped.len() + indent_bytes);
+    let (_, rest) = split_task_checkbox(body)?;
+    let stamped = parse_task_metadata(rest).0.done.is_some();
+    let rest = match (status.is_done(), stamped) {
+        (true, false) if now.is_some() => with_done_token(rest, now),
+        (false, true) => with_done_token(rest, None),
+        _ => rest.to_string(),
+    };
+    Some(format!("{prefix}{}{rest}", status.checkbox()))
+}
+
+/// Sets or removes the `@done(...)` token of a task body, keeping the carryover marker,
+/// context tags, tomatoes and block ID at the end of the line.
+fn with_done_token(body: &str, stamp: Option<NaiveDateTime>) -> String {
+    let (text, block_id) = split_block_id(body);
+    let (text, tomato_count) = strip_trailing_tomatoes(text);
+    let (head, tags) = split_trailing_context_tags(text);
+    let (base, carryover) = strip_carryover_marker_at_end(head);
+
+    let base = remove_task_metadata_token(&base, TaskMetadataKey::Done);
+    let mut output = match stamp {
+        Some(stamp) => {
+            let value = stamp.format(DONE_FORMAT).to_string();
+            upsert_task_metadata_token(&base, TaskMetadataKey::Done, &value)
+        }
+        None => base,
+    };
+    if let Some(carryover) = carryover {
+        output.push_str(&format!(" ⟦{carryover}⟧"));
+    }
+    output.push_str(tags);
+    if tomato_count > 0 {
+        output.push(' ');
+        output.push_str(&"🍅".repeat(tomato_count));
+    }
+    with_block_id(&output, block_id)
+}
+
 fn mark_task_completed_line(line: &str) -> Option<String> {
-    if line.contains("- [ ]") {
-        Some(line.replacen("- [ ]", "- [x]", 1))
-    } else {
-        None
+    if task_line_status(line)?.is_closed() {
+        return None;
     }
+    with_task_status(line, TaskStatus::Done)
 }
 
-fn mark_task_completed_at_line(file_path: &str, line_number: usize) -> io::Result<bool> {
+fn mark_task_completed_at_line(
+    file_path: &str,
+    line_number: usize,
+    task_identity: &str,
+) -> io::Result<bool> {
     let content = fs::read_to_string(file_path)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
-    if line_number >= lines.len() {
-        return Ok(false);
-    }
+    let line_number = locate_line(&lines, line_number, task_identity, LineKind::Task)?;
 
     let updated = if let Some(new_line) = mark_task_completed_line(&lines[line_number]) {
         lines[line_number] = new_line;
+        let today = Local::now().date_naive();
+        let line_date = line_dates(&content, file_path)
+            .get(line_number)
+            .copied()
+            .flatten()
+            .unwrap_or(today);
+        insert_next_occurrence(&mut lines, line_number, line_date, today);
         true
     } else {
         false
@@ -847,49 +1154,92 @@ fn mark_task_completed_at_line(file_path: &str, line_number: usize) -> io::Resul
         if !new_content.ends_with('\n') {
             new_content.push('\n');
         }
-        let mut file = fs::File::create(file_path)?;
-        file.write_all(new_content.as_bytes())?;
+        backups::write_log_file(Path::new(file_path), &new_content)?;
     }
 
     Ok(updated)
 }
 
-/// Toggles the status of a task checkbox ([ ] <-> [x]) at the given line.
+/// Toggles a task between done and open at the given line. In-progress and deferred tasks
+/// are completed; cancelled ones are reopened. Completing a recurring task (`@every`) adds its next occurrence below it.
 /// This reads the entire file and rewrites it, which is inefficient for large files
 /// but acceptable for daily memo scale.
-pub fn toggle_task_status(file_path: &str, line_number: usize) -> io::Result<()> {
+pub fn toggle_task_status(
+    file_path: &str,
+    line_number: usize,
+    task_identity: &str,
+) -> io::Result<()> {
     let content = fs::read_to_string(file_path)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let line_number = locate_line(&lines, line_number, task_identity, LineKind::Task)?;
 
-    if line_number < lines.len() {
-        let line = &lines[line_number];
-        let new_line = if line.contains("- [ ]") {
-            line.replacen("- [ ]", "- [x]", 1)
-        } else if line.contains("- [x]") {
-            line.replacen("- [x]", "- [ ]", 1)
-        } else if line.contains("- [X]") {
-            line.replacen("- [X]", "- [ ]", 1)
-        } else {
-            line.clone()
-        };
+    let line = &lines[line_number];
+    let Some(status) = task_line_status(line) else {
+        return Ok(());
+    };
+    let completed = !status.is_closed();
+    let next = if completed {
+        TaskStatus::Done
+    } else {
+        TaskStatus::Open
+    };
+    if let Some(new_line) = with_task_status(line, next) {
         lines[line_number] = new_line;
     }
 
+    if completed {
+        let today = Local::now().date_naive();
+        let line_date = line_dates(&content, file_path)
+            .get(line_number)
+            .copied()
+            .flatten()
+            .unwrap_or(today);
+        insert_next_occurrence(&mut lines, line_number, line_date, today);
+    }
+
     let mut new_content = lines.join("\n");
     // Ensure file ends with newline (prevents issues with append operations)
     if !new_content.ends_with('\n') {
         new_content.push('\n');
     }
 
-    let mut file = fs::File::create(file_path)?;
-    file.write_all(new_content.as_bytes())?;
+    backups::write_log_file(Path::new(file_path), &new_content)?;
 
     Ok(())
 }
 
+/// Sets the checkbox of the task at the given line to `status`. Returns false if it already
+/// had that status.
+pub fn set_task_status(
+    file_path: &str,
+    line_number: usize,
+    task_identity: &str,
+    status: TaskStatus,
+) -> io::Result<bool> {
+    let content = fs::read_to_string(file_path)?;
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let line_number = locate_line(&lines, line_number, task_identity, LineKind::Task)?;
+
+    let line = &lines[line_number];
+    if task_line_status(line) == Some(status) {
+        return Ok(false);
+    }
+    let Some(new_line) = with_task_status(line, status) else {
+        return Ok(false);
+    };
+    lines[line_number] = new_line;
+
+    let mut new_content = lines.join("\n");
+    if !new_content.ends_with('\n') {
+        new_content.push('\n');
+    }
+    backups::write_log_file(Path::new(file_path), &new_content)?;
+    Ok(true)
+}
+
 pub struct TaskLineUpdate {
     pub text: String,
-    pub is_done: bool,
+    pub status: TaskStatus,
     pub priority: Option<Priority>,
     pub schedule: TaskSchedule,
 }
@@ -902,25 +1252,36 @@ pub struct NoteLineUpdate {
 pub fn update_task_line(
     file_path: &str,
     line_number: usize,
+    task_identity: &str,
     update: TaskLineUpdate,
 ) -> io::Result<bool> {
     let content = fs::read_to_string(file_path)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let line_number = locate_line(&lines, line_number, task_identity, LineKind::Task)?;
+    lines[line_number] = rewrite_task_line(&lines[line_number], &update);
 
-    if line_number >= lines.len() {
-        return Ok(false);
+    let mut new_content = lines.join("\n");
+    if !new_content.ends_with('\n') {
+        new_content.push('\n');
     }
+    backups::write_log_file(Path::new(file_path), &new_content)?;
 
-    let line = lines[line_number].clone();
-    let stripped = strip_timestamp_prefix(&line);
+    Ok(true)
+}
+
+/// `line` rewritten from `update`, keeping its timestamp prefix, indent, carry-over marker,
+/// tomatoes and block ID.
+fn rewrite_task_line(line: &str, update: &TaskLineUpdate) -> String {
+    let stripped = strip_timestamp_prefix(line);
     let prefix_len = line.len().saturating_sub(stripped.len());
     let (indent_bytes, _) = parse_indent(stripped);
     let prefix = &line[..prefix_len.saturating_add(indent_bytes)];
 
+    let (stripped, block_id) = split_block_id(stripped);
     let (without_tomatoes, tomato_count) = strip_trailing_tomatoes(stripped);
     let carryover = extract_carryover_marker(without_tomatoes);
 
-    let mut body = format_task_body(&update);
+    let mut body = format_task_body(update);
     if let Some(carryover) = carryover {
         body.push_str(&format!(" ⟦{carryover}⟧"));
     }
@@ -929,56 +1290,244 @@ pub fn update_task_line(
         body.push_str(&"🍅".repeat(tomato_count));
     }
 
-    lines[line_number] = format!("{prefix}{body}");
-
-    let mut new_content = lines.join("\n");
-    if !new_content.ends_with('\n') {
-        new_content.push('\n');
-    }
-    let mut file = fs::File::create(file_path)?;
-    file.write_all(new_content.as_bytes())?;
-
-    Ok(true)
+    with_block_id(&format!("{prefix}{body}"), block_id)
 }
 
-pub fn update_note_line(
+/// Moves the task at the given line to `date`. The scheduled date is moved if the task has one
+/// (or no dates at all); a task with only a due date has its deadline moved instead. Returns
+/// false if the task is already on that day.
+pub fn reschedule_task(
     file_path: &str,
     line_number: usize,
-    update: NoteLineUpdate,
+    task_identity: &str,
+    date: NaiveDate,
 ) -> io::Result<bool> {
     let content = fs::read_to_string(file_path)?;
-    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
-    if line_number >= lines.len() {
+    let lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let line_number = locate_line(&lines, line_number, task_identity, LineKind::Task)?;
+    let Some(mut update) = task_line_update(&lines[line_number]) else {
+        return Ok(false);
+    };
+    if !move_task_date(&mut update.schedule, date) {
         return Ok(false);
     }
 
-    let line = lines[line_number].clone();
-    let stripped = strip_timestamp_prefix(&line);
-    let prefix_len = line.len().saturating_sub(stripped.len());
-    let (indent_bytes, _) = parse_indent(stripped);
-    let indent = &stripped[..indent_bytes];
-    let rest = &stripped[indent_bytes..];
-    let (list_prefix, _) = split_note_prefix(rest);
-
-    let mut body = String::new();
-    body.push_str(update.text.trim());
-    body = apply_schedule_tokens(&body, &update.schedule);
+    update_task_line(file_path, line_number, task_identity, update)
+}
 
-    let prefix = format!("{}{}{}", &line[..prefix_len], indent, list_prefix);
-    lines[line_number] = format!("{prefix}{body}");
+fn move_task_date(schedule: &mut TaskSchedule, date: NaiveDate) -> bool {
+    let target = if schedule.scheduled.is_some() || schedule.due.is_none() {
+        &mut schedule.scheduled
+    } else {
+        &mut schedule.due
+    };
+    if *target == Some(date) {
+        return false;
+    }
+    *target = Some(date);
+    true
+}
+
+/// An edit applied to several tasks at once by [`edit_tasks`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TaskBatchEdit {
+    Complete,
+    SetPriority(Option<Priority>),
+    Reschedule(NaiveDate),
+    AddTag(String),
+    Delete,
+}
+
+/// Applies `edit` to every task in `targets`, reading and writing each file once. All targets
+/// are located before anything is written, so a stale target leaves every file untouched; if a
+/// later write fails, the files already written are restored to their previous content.
+/// Returns the number of tasks changed.
+pub fn edit_tasks(targets: &[TaskRef], edit: &TaskBatchEdit) -> io::Result<usize> {
+    let mut by_file: Vec<(&str, Vec<&TaskRef>)> = Vec::new();
+    for target in targets {
+        match by_file.iter_mut().find(|(path, _)| *path == target.file_path) {
+            Some((_, group)) => group.push(target),
+            None => by_file.push((&target.file_path, vec![target])),
+        }
+    }
 
-    let mut new_content = lines.join("\n");
-    if !new_content.ends_with('\n') {
-        new_content.push('\n');
+    let today = Local::now().date_naive();
+    // (path, content before the edit, content after it)
+    let mut writes: Vec<(&str, String, String)> = Vec::new();
+    let mut changed = 0usize;
+    for (file_path, group) in by_file {
+        let content = fs::read_to_string(file_path)?;
+        let dates = line_dates(&content, file_path);
+        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+        let mut indices = group
+            .iter()
+            .map(|t| locate_line(&lines, t.line_number, &t.task_identity, LineKind::Task))
+            .collect::<io::Result<Vec<usize>>>()?;
+        // Bottom-up, so inserted repeats and deleted lines don't shift the lines still to come.
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        indices.dedup();
+
+        let before = changed;
+        for idx in indices {
+            if edit_task_line(&mut lines, idx, edit, dates.get(idx).copied().flatten(), today) {
+                changed += 1;
+            }
+        }
+        if changed > before {
+            let mut new_content = lines.join("\n");
+            if !new_content.ends_with('\n') {
+                new_content.push('\n');
+            }
+            writes.push((file_path, content, new_content));
+        }
     }
-    let mut file = fs::File::create(file_path)?;
-    file.write_all(new_content.as_bytes())?;
 
-    Ok(true)
+    for (written, (file_path, _, content)) in writes.iter().enumerate() {
+        if let Err(err) = backups::write_log_file(Path::new(file_path), content) {
+            for (file_path, original, _) in &writes[..written] {
+                let _ = backups::write_atomic(Path::new(file_path), original);
+            }
+            return Err(err);
+        }
+    }
+    Ok(changed)
 }
 
-pub fn compose_task_line(update: &TaskLineUpdate) -> String {
+fn edit_task_line(
+    lines: &mut Vec<String>,
+    idx: usize,
+    edit: &TaskBatchEdit,
+    line_date: Option<NaiveDate>,
+    today: NaiveDate,
+) -> bool {
+    match edit {
+        TaskBatchEdit::Complete => {
+            if task_line_status(&lines[idx]).is_none_or(|status| status.is_closed()) {
+                return false;
+            }
+            let Some(new_line) = with_task_status(&lines[idx], TaskStatus::Done) else {
+                return false;
+            };
+            lines[idx] = new_line;
+            insert_next_occurrence(lines, idx, line_date.unwrap_or(today), today);
+            true
+        }
+        TaskBatchEdit::Delete => {
+            delete_task_line(lines, idx);
+            true
+        }
+        TaskBatchEdit::SetPriority(_) | TaskBatchEdit::Reschedule(_) | TaskBatchEdit::AddTag(_) => {
+            let Some(mut update) = task_line_update(&lines[idx]) else {
+                return false;
+            };
+            let changed = match edit {
+                TaskBatchEdit::SetPriority(priority) => {
+                    let changed = update.priority != *priority;
+                    update.priority = *priority;
+                    changed
+                }
+                TaskBatchEdit::Reschedule(date) => move_task_date(&mut update.schedule, *date),
+                TaskBatchEdit::AddTag(tag) => add_tag(&mut update.text, tag),
+                _ => false,
+            };
+            if changed {
+                lines[idx] = rewrite_task_line(&lines[idx], &update);
+            }
+            changed
+        }
+    }
+}
+
+/// Removes a task together with its indented subtasks. A task written on an entry heading or
+/// timestamp line keeps that prefix, so the entry doesn't merge into the one above it.
+fn delete_task_line(lines: &mut Vec<String>, idx: usize) {
+    let (_, task_indent) = parse_indent(strip_timestamp_prefix(&lines[idx]));
+    let mut end = idx + 1;
+    while let Some(line) = lines.get(end) {
+        let (_, indent) = parse_indent(line);
+        if line.trim().is_empty() || indent <= task_indent {
+            break;
+        }
+        end += 1;
+    }
+
+    let line = &lines[idx];
+    let prefix = line[..line.len() - strip_timestamp_prefix(line).len()].trim_end();
+    if prefix.is_empty() {
+        lines.drain(idx..end);
+    } else {
+        lines[idx] = prefix.to_string();
+        lines.drain(idx + 1..end);
+    }
+}
+
+/// Appends `#tag` to `text` unless it is already there.
+fn add_tag(text: &mut String, tag: &str) -> bool {
+    let tag = format!("#{}", tag.trim().trim_start_matches('#'));
+    if tag.len() == 1 || text.split_whitespace().any(|word| word == tag) {
+        return false;
+    }
+    text.push(' ');
+    text.push_str(&tag);
+    true
+}
+
+/// Reads a task line back into the parts `update_task_line` writes. Tomatoes, the carry-over
+/// marker and the block ID are left out; `update_task_line` keeps them from the existing line.
+fn task_line_update(line: &str) -> Option<TaskLineUpdate> {
+    let stripped = strip_timestamp_prefix(line);
+    let (indent_bytes, _) = parse_indent(stripped);
+    let (status, text) = split_task_checkbox(&stripped[indent_bytes..])?;
+    let (text, _) = split_block_id(text);
+    let (text, _) = strip_trailing_tomatoes(text);
+    let text = text.trim();
+    let priority = parse_priority_marker(text).or_else(|| parse_priority_emoji(text));
+    let (schedule, display_text) = parse_task_metadata(text);
+    let (text, _) = strip_carryover_marker(&strip_priority_marker(&display_text));
+    Some(TaskLineUpdate {
+        text,
+        status,
+        priority,
+        schedule,
+    })
+}
+
+pub fn update_note_line(
+    file_path: &str,
+    line_number: usize,
+    note_identity: &str,
+    update: NoteLineUpdate,
+) -> io::Result<bool> {
+    let content = fs::read_to_string(file_path)?;
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let line_number = locate_line(&lines, line_number, note_identity, LineKind::Note)?;
+
+    let line = lines[line_number].clone();
+    let stripped = strip_timestamp_prefix(&line);
+    let prefix_len = line.len().saturating_sub(stripped.len());
+    let (indent_bytes, _) = parse_indent(stripped);
+    let indent = &stripped[..indent_bytes];
+    let rest = &stripped[indent_bytes..];
+    let (list_prefix, _) = split_note_prefix(rest);
+
+    let mut body = String::new();
+    body.push_str(update.text.trim());
+    body = apply_schedule_tokens(&body, &update.schedule);
+
+    let prefix = format!("{}{}{}", &line[..prefix_len], indent, list_prefix);
+    let block_id = split_block_id(stripped).1;
+    lines[line_number] = with_block_id(&format!("{prefix}{body}"), block_id);
+
+    let mut new_content = lines.join("\n");
+    if !new_content.ends_with('\n') {
+        new_content.push('\n');
+    }
+    backups::write_log_file(Path::new(file_path), &new_content)?;
+
+    Ok(true)
+}
+
+pub fn compose_task_line(update: &TaskLineUpdate) -> String {
     format_task_body(update)
 }
 
@@ -989,13 +1538,14 @@ pub fn compose_note_line(update: &NoteLineUpdate) -> String {
 }
 
 /// Cycles task priority marker (None -> A -> B -> C -> None) at the given line.
-pub fn cycle_task_priority(file_path: &str, line_number: usize) -> io::Result<bool> {
+pub fn cycle_task_priority(
+    file_path: &str,
+    line_number: usize,
+    task_identity: &str,
+) -> io::Result<bool> {
     let content = fs::read_to_string(file_path)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
-    if line_number >= lines.len() {
-        return Ok(false);
-    }
+    let line_number = locate_line(&lines, line_number, task_identity, LineKind::Task)?;
 
     let line = lines[line_number].clone();
     let stripped = strip_timestamp_prefix(&line);
@@ -1007,19 +1557,15 @@ pub fn cycle_task_priority(file_path: &str, line_number: usize) -> io::Result<bo
     }
     let (prefix, body) = line.split_at(body_start);
 
-    let (checkbox, after_checkbox) = if let Some(text) = body.strip_prefix("- [ ] ") {
-        ("- [ ] ", text)
-    } else if let Some(text) = body.strip_prefix("- [x] ") {
-        ("- [x] ", text)
-    } else if let Some(text) = body.strip_prefix("- [X] ") {
-        ("- [X] ", text)
-    } else {
+    let Some((_, after_checkbox)) = split_task_checkbox(body) else {
         return Ok(false);
     };
+    let checkbox = &body[..body.len() - after_checkbox.len()];
 
     let trimmed = after_checkbox.trim_start();
-    let current = parse_priority_marker(trimmed);
+    let current = parse_priority_marker(trimmed).or_else(|| parse_priority_emoji(trimmed));
     let base_text = strip_priority_marker(trimmed);
+    let base_text = remove_task_metadata_token(&base_text, TaskMetadataKey::Priority);
     let next = match current {
         None => Some(Priority::High),
         Some(Priority::High) => Some(Priority::Medium),
@@ -1029,15 +1575,30 @@ pub fn cycle_task_priority(file_path: &str, line_number: usize) -> io::Result<bo
 
     let mut new_body = String::new();
     new_body.push_str(checkbox);
-    if let Some(priority) = next {
-        new_body.push_str("[#");
-        new_body.push(priority.as_char());
-        new_body.push(']');
-        if !base_text.is_empty() {
-            new_body.push(' ');
+    match next {
+        Some(priority) if emoji_syntax() => {
+            // The emoji goes before trailing tomatoes and the block ID so both still parse.
+            let letter = priority.as_char().to_string();
+            let (text, block_id) = split_block_id(&base_text);
+            let (text, tomato_count) = strip_trailing_tomatoes(text);
+            let mut text = upsert_task_metadata_token(text, TaskMetadataKey::Priority, &letter);
+            if tomato_count > 0 {
+                text.push(' ');
+                text.push_str(&"🍅".repeat(tomato_count));
+            }
+            new_body.push_str(&with_block_id(&text, block_id));
+        }
+        Some(priority) => {
+            new_body.push_str("[#");
+            new_body.push(priority.as_char());
+            new_body.push(']');
+            if !base_text.is_empty() {
+                new_body.push(' ');
+            }
+            new_body.push_str(&base_text);
         }
+        None => new_body.push_str(&base_text),
     }
-    new_body.push_str(&base_text);
 
     let updated_line = format!("{prefix}{new_body}");
     if updated_line == line {
@@ -1049,28 +1610,151 @@ pub fn cycle_task_priority(file_path: &str, line_number: usize) -> io::Result<bo
     if !new_content.ends_with('\n') {
         new_content.push('\n');
     }
-    let mut file = fs::File::create(file_path)?;
-    file.write_all(new_content.as_bytes())?;
+    backups::write_log_file(Path::new(file_path), &new_content)?;
 
     Ok(true)
 }
 
+/// Error payload for mutations whose target no longer matches the file on disk.
+#[derive(Debug)]
+pub struct StaleTargetError;
+
+impl fmt::Display for StaleTargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "target changed on disk")
+    }
+}
+
+impl std::error::Error for StaleTargetError {}
+
+/// True when `err` means the line or entry changed on disk since it was loaded.
+pub fn is_stale_target(err: &io::Error) -> bool {
+    err.get_ref()
+        .is_some_and(|inner| inner.is::<StaleTargetError>())
+}
+
+fn stale_target() -> io::Error {
+    io::Error::other(StaleTargetError)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Task,
+    Note,
+}
+
+/// Identity of a task or scheduled note text, as used to re-locate lines after edits.
+/// Ignores checkbox state, priority, metadata tokens, context tags and carryover markers.
+pub fn text_identity(text: &str) -> String {
+    task_identity_from_text(text).0
+}
+
+fn line_identity(line: &str, kind: LineKind) -> Option<String> {
+    let task = parse_task_line(line);
+    match kind {
+        LineKind::Task => task.map(|parsed| parsed.identity),
+        LineKind::Note => {
+            if task.is_some() || is_timestamped_line(line) {
+                return None;
+            }
+            let (_, text) = parse_task_metadata(strip_block_id(strip_timestamp_prefix(line)));
+            (!text.trim().is_empty()).then(|| text_identity(&text))
+        }
+    }
+}
+
+/// Finds the line captured as `line_number` with `identity`. If it moved, it is followed
+/// only when exactly one line in the file carries the same identity.
+fn locate_line(
+    lines: &[String],
+    line_number: usize,
+    identity: &str,
+    kind: LineKind,
+) -> io::Result<usize> {
+    let matches = |line: &String| line_identity(line, kind).as_deref() == Some(identity);
+    if lines.get(line_number).is_some_and(matches) {
+        return Ok(line_number);
+    }
+    let mut found = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| matches(line))
+        .map(|(idx, _)| idx);
+    match (found.next(), found.next()) {
+        (Some(idx), None) => Ok(idx),
+        _ => Err(stale_target()),
+    }
+}
+
+/// Finds the entry whose content equals `expected`, preferring the one at `line_number`.
+/// Fold markers, bookkeeping lines and whitespace are ignored when comparing.
+fn locate_entry(
+    content: &str,
+    file_path: &str,
+    line_number: usize,
+    expected: &str,
+) -> io::Result<(usize, usize)> {
+    let expected = entry_fingerprint(expected);
+    let candidates: Vec<(usize, usize)> = parse_log_content(content, file_path)
+        .into_iter()
+        .filter(|entry| entry_fingerprint(&entry.content) == expected)
+        .map(|entry| (entry.line_number, entry.end_line))
+        .collect();
+    if let Some(range) = candidates.iter().find(|(start, _)| *start == line_number) {
+        return Ok(*range);
+    }
+    match candidates.as_slice() {
+        [range] => Ok(*range),
+        _ => Err(stale_target()),
+    }
+}
+
+fn entry_fingerprint(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = content
+        .lines()
+        .filter(|line| !line.contains("System: Carryover Checked"))
+        .filter_map(without_fold_marker)
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Removes an inline fold marker comment; `None` when the line held only the marker.
+fn without_fold_marker(line: &str) -> Option<String> {
+    let Some(start) = line.find("<!--") else {
+        return Some(line.to_string());
+    };
+    let Some(end) = line[start..].find("-->").map(|idx| start + idx + 3) else {
+        return Some(line.to_string());
+    };
+    if !is_fold_marker_line(&line[start..end]) {
+        return Some(line.to_string());
+    }
+    let cleaned = format!("{}{}", &line[..start], &line[end..]);
+    (!cleaned.trim().is_empty()).then_some(cleaned)
+}
+
+/// First day stored in `file_path` under the configured layout.
 fn extract_date_from_path(file_path: &str) -> Option<NaiveDate> {
-    let path = Path::new(file_path);
-    let stem = path.file_stem()?.to_str()?;
-    NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()
+    file_layout::current().date_for_path(Path::new(file_path))
 }
 
 pub fn complete_task_chain(log_path: &Path, task: &TaskItem) -> io::Result<usize> {
     let mut completed_count = 0usize;
-    if mark_task_completed_at_line(&task.file_path, task.line_number)? {
+    if mark_task_completed_at_line(&task.file_path, task.line_number, &task.task_identity)? {
         completed_count += 1;
     }
 
     let Some(from_date) = task.carryover_from.clone() else {
         return Ok(completed_count);
     };
-    let Some(current_date) = extract_date_from_path(&task.file_path) else {
+    let Some(current_date) = task
+        .date
+        .or_else(|| extract_date_from_path(&task.file_path))
+    else {
         return Ok(completed_count);
     };
 
@@ -1095,11 +1779,16 @@ pub fn complete_task_chain(log_path: &Path, task: &TaskItem) -> io::Result<usize
         }
 
         let content = fs::read_to_string(&path)?;
+        let path_str = path.to_string_lossy().to_string();
+        let dates = line_dates(&content, &path_str);
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
         let mut changed = false;
         let mut next_dates: Vec<String> = Vec::new();
 
-        for line in &mut lines {
+        for (idx, line) in lines.iter_mut().enumerate() {
+            if dates.get(idx).copied().flatten() != Some(date) {
+                continue;
+            }
             let Some(parsed) = parse_task_line(line) else {
                 continue;
             };
@@ -1107,8 +1796,7 @@ pub fn complete_task_chain(log_path: &Path, task: &TaskItem) -> io::Result<usize
                 continue;
             }
 
-            if !parsed.is_done
-                && let Some(new_line) = mark_task_completed_line(line)
+            if let Some(new_line) = mark_task_completed_line(line)
             {
                 *line = new_line;
                 completed_count += 1;
@@ -1125,7 +1813,7 @@ pub fn complete_task_chain(log_path: &Path, task: &TaskItem) -> io::Result<usize
             if !new_content.ends_with('\n') {
                 new_content.push('\n');
             }
-            fs::write(&path, new_content)?;
+            backups::write_log_file(&path, &new_content)?;
         }
 
         for next_date in next_dates {
@@ -1140,15 +1828,12 @@ pub fn complete_task_chain(log_path: &Path, task: &TaskItem) -> io::Result<usize
 
 pub fn complete_entry_tasks(entry: &LogEntry) -> io::Result<usize> {
     let content = fs::read_to_string(&entry.file_path)?;
+    let (start_line, end_line) =
+        locate_entry(&content, &entry.file_path, entry.line_number, &entry.content)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
-    if entry.line_number >= lines.len() {
-        return Ok(0);
-    }
-
-    let end_line = entry.end_line.min(lines.len().saturating_sub(1));
     let mut updated = 0usize;
-    for line in lines.iter_mut().take(end_line + 1).skip(entry.line_number) {
+    for line in lines.iter_mut().take(end_line + 1).skip(start_line) {
         if let Some(new_line) = mark_task_completed_line(line) {
             *line = new_line;
             updated += 1;
@@ -1160,53 +1845,64 @@ pub fn complete_entry_tasks(entry: &LogEntry) -> io::Result<usize> {
         if !new_content.ends_with('\n') {
             new_content.push('\n');
         }
-        fs::write(&entry.file_path, new_content)?;
+        backups::write_log_file(Path::new(&entry.file_path), &new_content)?;
     }
 
     Ok(updated)
 }
 
+/// Replaces the entry that was loaded at `line_number` with `expected` content.
+/// Returns the line the entry started at, which differs from `line_number` if it moved.
 pub fn replace_entry_lines(
     file_path: &str,
-    start_line: usize,
-    end_line: usize,
+    line_number: usize,
+    expected: &str,
     new_lines: &[String],
-) -> io::Result<()> {
+) -> io::Result<usize> {
     let content = fs::read_to_string(file_path)?;
-    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
-    if start_line >= lines.len() {
-        return Ok(());
+    let (start_line, end_line) = locate_entry(&content, file_path, line_number, expected)?;
+    let mut new_lines = new_lines.to_vec();
+    if block_ids::enabled() {
+        block_ids::assign_missing(&mut new_lines, &content);
     }
-
-    let end_line = end_line.min(lines.len().saturating_sub(1));
-    lines.splice(start_line..(end_line + 1), new_lines.iter().cloned());
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    lines.splice(start_line..(end_line + 1), new_lines);
 
     let mut new_content = lines.join("\n");
     if !new_content.ends_with('\n') {
         new_content.push('\n');
     }
 
-    fs::write(file_path, new_content)
+    backups::write_log_file(Path::new(file_path), &new_content)?;
+    Ok(start_line)
 }
 
-pub fn delete_entry_lines(file_path: &str, start_line: usize, end_line: usize) -> io::Result<()> {
-    replace_entry_lines(file_path, start_line, end_line, &[])
+pub fn delete_entry_lines(file_path: &str, line_number: usize, expected: &str) -> io::Result<()> {
+    replace_entry_lines(file_path, line_number, expected, &[]).map(|_| ())
 }
 
-pub fn read_lines_range(
+/// Reads the raw lines (including fold markers) of an entry loaded earlier,
+/// following it if it moved within the file.
+pub fn read_entry_lines(entry: &LogEntry) -> io::Result<Vec<String>> {
+    let content = fs::read_to_string(&entry.file_path)?;
+    let (start_line, end_line) =
+        locate_entry(&content, &entry.file_path, entry.line_number, &entry.content)?;
+    Ok(content
+        .lines()
+        .skip(start_line)
+        .take(end_line + 1 - start_line)
+        .map(|s| s.to_string())
+        .collect())
+}
+
+/// Returns the current line range of the entry with `expected` content.
+pub fn locate_entry_range(
     file_path: &str,
-    start_line: usize,
-    end_line: usize,
-) -> io::Result<Vec<String>> {
+    line_number: usize,
+    expected: &str,
+) -> io::Result<(usize, usize)> {
     let content = fs::read_to_string(file_path)?;
-    let lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
-    if start_line >= lines.len() {
-        return Ok(Vec::new());
-    }
-    let end_line = end_line.min(lines.len().saturating_sub(1));
-    Ok(lines[start_line..=end_line].to_vec())
+    locate_entry(&content, file_path, line_number, expected)
 }
 
 pub fn write_file_lines(file_path: &str, lines: &[String]) -> io::Result<()> {
@@ -1221,21 +1917,140 @@ pub fn write_file_lines(file_path: &str, lines: &[String]) -> io::Result<()> {
     if !content.ends_with('\n') {
         content.push('\n');
     }
-    fs::write(path, content)
+    backups::write_atomic(path, &content)
+}
+
+/// Moves every day stored under the `from` layout into the files of the `to` layout.
+/// All new files are written before any old one is removed; a file of the `to` layout that
+/// already exists and is not being migrated aborts the move. Returns (days moved, files written).
+pub fn migrate_layout(
+    log_path: &Path,
+    from: &FileLayout,
+    to: &FileLayout,
+) -> io::Result<(usize, usize)> {
+    if from == to {
+        return Ok((0, 0));
+    }
+    let sources = from.list_files(log_path)?;
+
+    let mut days: std::collections::BTreeMap<NaiveDate, Vec<String>> =
+        std::collections::BTreeMap::new();
+    for (file_date, path) in &sources {
+        let content = fs::read_to_string(path)?;
+        if !from.is_shared() {
+            days.entry(*file_date).or_default().push(content);
+            continue;
+        }
+        // Anything above the first day heading stays with the first day of the file.
+        let mut current = *file_date;
+        let mut section = String::new();
+        for line in content.lines() {
+            if let Some(date) = parse_day_heading(line) {
+                days.entry(current).or_default().push(std::mem::take(&mut section));
+                current = date;
+                continue;
+            }
+            section.push_str(line);
+            section.push('\n');
+        }
+        days.entry(current).or_default().push(section);
+    }
+    for chunks in days.values_mut() {
+        chunks.retain(|chunk| !chunk.trim().is_empty());
+    }
+    days.retain(|_, chunks| !chunks.is_empty());
+
+    let mut targets: std::collections::BTreeMap<PathBuf, String> =
+        std::collections::BTreeMap::new();
+    for (date, chunks) in &days {
+        let target = targets
+            .entry(to.path_for_date(log_path, *date))
+            .or_default();
+        let body = match chunks.as_slice() {
+            [chunk] if !from.is_shared() && !to.is_shared() => chunk.clone(),
+            _ => chunks
+                .iter()
+                .map(|chunk| chunk.trim_matches('\n'))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        };
+        if to.is_shared() {
+            if !target.is_empty() {
+                target.push('\n');
+            }
+            target.push_str(&file_layout::day_heading(*date));
+            target.push_str("\n\n");
+        }
+        target.push_str(&body);
+        if !target.ends_with('\n') {
+            target.push('\n');
+        }
+    }
+
+    let source_paths: std::collections::HashSet<&PathBuf> =
+        sources.iter().map(|(_, path)| path).collect();
+    if let Some(existing) = targets
+        .keys()
+        .find(|path| path.exists() && !source_paths.contains(path))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", existing.display()),
+        ));
+    }
+
+    for (path, content) in &targets {
+        backups::write_log_file(path, content)?;
+    }
+    for (_, path) in &sources {
+        if !targets.contains_key(path) {
+            fs::remove_file(path)?;
+            // Drop folders the old layout leaves empty; non-empty ones fail and are kept.
+            let mut dir = path.parent();
+            while let Some(parent) = dir.filter(|dir| *dir != log_path) {
+                if fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                dir = parent.parent();
+            }
+        }
+    }
+    Ok((days.len(), targets.len()))
+}
+
+/// Adds block IDs to every entry heading and task line in the logs that lacks one.
+/// Returns (files changed, IDs added).
+pub fn backfill_block_ids(log_path: &Path) -> io::Result<(usize, usize)> {
+    let mut files = 0usize;
+    let mut added = 0usize;
+    for (_, path) in file_layout::current().list_files(log_path)? {
+        let content = fs::read_to_string(&path)?;
+        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+        let count = block_ids::assign_missing(&mut lines, "");
+        if count == 0 {
+            continue;
+        }
+        let mut new_content = lines.join("\n");
+        if !new_content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        backups::write_log_file(&path, &new_content)?;
+        files += 1;
+        added += count;
+    }
+    Ok((files, added))
 }
 
 pub fn update_fold_marker(
     file_path: &str,
-    start_line: usize,
+    line_number: usize,
+    expected: &str,
     state: FoldOverride,
 ) -> io::Result<()> {
     let content = fs::read_to_string(file_path)?;
+    let (start_line, _) = locate_entry(&content, file_path, line_number, expected)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
-    if start_line >= lines.len() {
-        return Ok(());
-    }
-
     if !is_heading_timestamp_line(&lines[start_line]) {
         return Ok(());
     }
@@ -1252,7 +2067,7 @@ pub fn update_fold_marker(
     if !new_content.ends_with('\n') {
         new_content.push('\n');
     }
-    fs::write(file_path, new_content)
+    backups::write_log_file(Path::new(file_path), &new_content)
 }
 
 fn fold_marker_line(state: FoldOverride) -> &'static str {
@@ -1277,22 +2092,24 @@ fn is_fold_marker_line(line: &str) -> bool {
     )
 }
 
-pub fn append_tomato_to_line(file_path: &str, line_number: usize) -> io::Result<()> {
+pub fn append_tomato_to_line(
+    file_path: &str,
+    line_number: usize,
+    task_identity: &str,
+) -> io::Result<()> {
     let content = fs::read_to_string(file_path)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let line_number = locate_line(&lines, line_number, task_identity, LineKind::Task)?;
 
-    if line_number < lines.len() {
-        let line = lines[line_number].trim_end().to_string();
-        lines[line_number] = format!("{line} 🍅");
-    }
+    let (line, block_id) = split_block_id(&lines[line_number]);
+    lines[line_number] = with_block_id(&format!("{} 🍅", line.trim_end()), block_id);
 
     let mut new_content = lines.join("\n");
     if !new_content.ends_with('\n') {
         new_content.push('\n');
     }
 
-    let mut file = fs::File::create(file_path)?;
-    file.write_all(new_content.as_bytes())?;
+    backups::write_log_file(Path::new(file_path), &new_content)?;
     Ok(())
 }
 
@@ -1306,7 +2123,8 @@ pub fn collect_carryover_tasks(log_path: &Path, today: &str) -> io::Result<Vec<S
     let today_path = get_file_path_for_date(log_path, today);
     if today_path.exists() {
         let content = fs::read_to_string(&today_path)?;
-        for line in content.lines() {
+        let path_str = today_path.to_string_lossy().to_string();
+        for line in lines_for_date(&content, &path_str, today_date) {
             if let Some(parsed) = parse_task_line(line) {
                 resolved.insert(parsed.identity);
             }
@@ -1324,11 +2142,12 @@ pub fn collect_carryover_tasks(log_path: &Path, today: &str) -> io::Result<Vec<S
             continue;
         }
         let content = fs::read_to_string(&path)?;
+        let path_str = path.to_string_lossy().to_string();
         let mut ordered: Vec<String> = Vec::new();
         let mut states: std::collections::HashMap<String, ParsedTaskLine> =
             std::collections::HashMap::new();
 
-        for line in content.lines() {
+        for line in lines_for_date(&content, &path_str, *date) {
             let Some(parsed) = parse_task_line(line) else {
                 continue;
             };
@@ -1339,8 +2158,8 @@ pub fn collect_carryover_tasks(log_path: &Path, today: &str) -> io::Result<Vec<S
                     entry.insert(parsed);
                 }
                 std::collections::hash_map::Entry::Occupied(mut entry) => {
-                    if parsed.is_done {
-                        entry.get_mut().is_done = true;
+                    if parsed.status.is_closed() {
+                        entry.get_mut().status = parsed.status;
                     }
                 }
             }
@@ -1354,7 +2173,9 @@ pub fn collect_carryover_tasks(log_path: &Path, today: &str) -> io::Result<Vec<S
                 continue;
             }
             resolved.insert(parsed.identity.clone());
-            if parsed.is_done {
+            // Done and cancelled tasks stay behind; in-progress and deferred ones keep
+            // their marker.
+            if parsed.status.is_closed() {
                 continue;
             }
             let raw_text = parsed.raw_text.trim();
@@ -1362,7 +2183,8 @@ pub fn collect_carryover_tasks(log_path: &Path, today: &str) -> io::Result<Vec<S
                 continue;
             }
             let indent = "  ".repeat(parsed.indent_level);
-            carryover.push(format!("{indent}- [ ] {raw_text} ⟦{date_str}⟧"));
+            let checkbox = parsed.status.checkbox();
+            carryover.push(format!("{indent}{checkbox}{raw_text} ⟦{date_str}⟧"));
         }
     }
 
@@ -1373,26 +2195,14 @@ pub fn collect_carryover_tasks(log_path: &Path, today: &str) -> io::Result<Vec<S
 pub fn get_all_tags(log_path: &Path) -> io::Result<Vec<(String, usize)>> {
     use std::collections::HashMap;
 
-    ensure_log_dir(log_path)?;
-    let dir = PathBuf::from(log_path);
     let mut tag_counts = HashMap::new();
-
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("md")
-                && let Ok(content) = fs::read_to_string(&path)
-            {
-                for line in content.lines() {
-                    for word in line.split_whitespace() {
-                        if word.starts_with('#') && word.len() > 1 {
-                            *tag_counts.entry(word.to_string()).or_insert(0) += 1;
-                        }
-                    }
-                }
+    search_index::with_index(log_path, |index| {
+        for (_, file) in index.files() {
+            for (tag, count) in &file.tags {
+                *tag_counts.entry(tag.clone()).or_insert(0) += count;
             }
         }
-    }
+    })?;
 
     let mut tags: Vec<(String, usize)> = tag_counts.into_iter().collect();
     // Sort by frequency (descending)
@@ -1413,62 +2223,166 @@ pub fn mark_carryover_done(log_path: &Path) -> io::Result<()> {
     save_state(log_path, &state)
 }
 
-/// Returns activity statistics for each date: (line_count, tomato_count).
-/// Tomato count excludes carryover tasks (marked with ⟦date⟧).
+pub fn load_pomodoro_state(log_path: &Path) -> io::Result<PomodoroState> {
+    Ok(load_state(log_path)?.pomodoro)
+}
+
+pub fn save_pomodoro_state(log_path: &Path, pomodoro: &PomodoroState) -> io::Result<()> {
+    let mut state = load_state(log_path)?;
+    state.pomodoro = pomodoro.clone();
+    save_state(log_path, &state)
+}
+
+/// A finished or interrupted work session, one JSON object per line in
+/// `.memolog/pomodoro.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PomodoroSession {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub task: String,
+    pub task_identity: String,
+    pub file_path: String,
+    /// Block ID of the task line, when it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    pub planned_minutes: u32,
+    pub actual_minutes: u32,
+    pub interrupted: bool,
+}
+
+fn pomodoro_log_path(log_path: &Path) -> PathBuf {
+    let mut path = state_dir_path(log_path);
+    path.push("pomodoro.jsonl");
+    path
+}
+
+pub fn append_pomodoro_session(log_path: &Path, session: &PomodoroSession) -> io::Result<()> {
+    use std::io::Write;
+
+    let dir = state_dir_path(log_path);
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+    }
+    let line = serde_json::to_string(session).map_err(io::Error::other)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(pomodoro_log_path(log_path))?;
+    writeln!(file, "{line}")
+}
+
+/// Time worked per task line: minutes recorded in `sessions`, else `work_minutes` per tomato.
+pub fn time_spent(sessions: &[PomodoroSession], work_minutes: u64) -> TimeSpent {
+    let mut spent = TimeSpent::new(work_minutes);
+    for session in sessions {
+        let task = TaskInstance {
+            file_path: &session.file_path,
+            task_identity: &session.task_identity,
+            block_id: session.block_id.as_deref(),
+            since: None,
+        };
+        spent.record(
+            &task,
+            session.start.date_naive(),
+            session.actual_minutes,
+            !session.interrupted,
+        );
+    }
+    spent
+}
+
+/// All recorded sessions, oldest first. Lines that do not parse are skipped.
+pub fn read_pomodoro_sessions(log_path: &Path) -> io::Result<Vec<PomodoroSession>> {
+    let content = match fs::read_to_string(pomodoro_log_path(log_path)) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Returns activity statistics for each date: (line_count, tomato_count, done_count).
+/// Carryover tasks (marked with ⟦date⟧) are skipped. Completed tasks count on the
+/// date of their `@done` stamp, or on the day they were written if they have none.
 pub fn get_activity_stats(
     log_path: &Path,
-) -> io::Result<std::collections::HashMap<String, (usize, usize)>> {
+) -> io::Result<std::collections::HashMap<String, (usize, usize, usize)>> {
     use std::collections::HashMap;
 
-    ensure_log_dir(log_path)?;
-    let dir = PathBuf::from(log_path);
-    let mut stats = HashMap::new();
-
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("md")
-                && let Some(filename) = path.file_stem().and_then(|s| s.to_str())
-                && let Ok(content) = fs::read_to_string(&path)
-            {
-                let mut line_count = 0usize;
-                let mut tomato_count = 0usize;
-
-                for line in content.lines() {
-                    if line.trim().is_empty() || line.contains("System: Carryover Checked") {
-                        continue;
-                    }
-                    line_count += 1;
-
-                    // Count tomatoes (only from non-carryover tasks)
-                    let s = strip_timestamp_prefix(line).trim_start();
-
-                    if let Some(text) = s
-                        .strip_prefix("- [ ] ")
-                        .or_else(|| s.strip_prefix("- [x] "))
-                        .or_else(|| s.strip_prefix("- [X] "))
-                    {
-                        // Only count tomatoes if not a carryover task
-                        if !text.contains("⟦") {
-                            tomato_count += count_trailing_tomatoes(text);
-                        }
-                    }
+    let mut stats: HashMap<String, (usize, usize, usize)> = HashMap::new();
+    search_index::with_index(log_path, |index| {
+        for (_, file) in index.files() {
+            for (date, line_count) in &file.line_counts {
+                let day = date.format("%Y-%m-%d").to_string();
+                stats.entry(day).or_default().0 += line_count;
+            }
+            // Count tomatoes only from non-carryover tasks (marked with ⟦date⟧)
+            for task in &file.task_lines {
+                let Some(date) = task.date else {
+                    continue;
+                };
+                let Some(text) = task_line_body(&task.line).filter(|text| !text.contains("⟦"))
+                else {
+                    continue;
+                };
+                if task_line_status(&task.line).is_some_and(TaskStatus::is_done) {
+                    let done = parse_task_metadata(strip_block_id(text)).0.done;
+                    let day = done.unwrap_or(date).format("%Y-%m-%d").to_string();
+                    stats.entry(day).or_default().2 += 1;
                 }
-
-                stats.insert(filename.to_string(), (line_count, tomato_count));
+                let day = date.format("%Y-%m-%d").to_string();
+                stats.entry(day).or_default().1 += count_trailing_tomatoes(strip_block_id(text));
             }
         }
-    }
+    })?;
     Ok(stats)
 }
 
+/// Returns true for checkbox task lines (`- [ ] `, `- [x] `, `- [/] `, ...), allowing a
+/// timestamp prefix.
+pub(crate) fn is_task_line(line: &str) -> bool {
+    task_line_body(line).is_some()
+}
+
+fn task_line_body(line: &str) -> Option<&str> {
+    let s = strip_timestamp_prefix(line).trim_start();
+    split_task_checkbox(s).map(|(_, body)| body)
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct AppState {
     #[serde(default)]
     carryover_checked_date: Option<String>,
+    #[serde(default)]
+    pomodoro: PomodoroState,
+}
+
+/// Pomodoro state kept across restarts: the running timer and the day's session count.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PomodoroState {
+    /// Day (`YYYY-MM-DD`) that `sessions` counts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions_date: Option<String>,
+    #[serde(default)]
+    pub sessions: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer: Option<PomodoroTimer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PomodoroTimer {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    #[serde(default)]
+    pub phase: models::PomodoroPhase,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<models::PomodoroTarget>,
 }
 
-fn state_dir_path(log_path: &Path) -> PathBuf {
+pub(crate) fn state_dir_path(log_path: &Path) -> PathBuf {
     let mut dir = PathBuf::from(log_path);
     dir.push(".memolog");
     dir
@@ -1508,7 +2422,7 @@ fn save_state(log_path: &Path, state: &AppState) -> io::Result<()> {
 
     let path = state_file_path(log_path);
     let content = toml::to_string(state).unwrap_or_default();
-    fs::write(path, content)
+    backups::write_atomic(&path, &content)
 }
 
 #[cfg(test)]
@@ -1585,6 +2499,26 @@ mod tests {
         assert_eq!(entries[1].line_number, 5);
     }
 
+    #[test]
+    fn parse_log_content_follows_configured_heading_format() {
+        models::set_test_heading_format(Some(
+            HeadingFormat::new("- {time}", "%H:%M").expect("heading format"),
+        ));
+        let dir = temp_log_dir();
+        append_entry(&dir, "Standup").expect("append");
+        let content = fs::read_to_string(get_today_file_path(&dir)).expect("read log");
+        assert!(models::parse_heading_time(content.lines().next().unwrap_or("")).is_some());
+        assert!(content.starts_with("- "));
+
+        let content = "## [08:00:00]\nOld style\n\n- 09:30\nNew style\n- bullet\n- 10:15 ^abc123\nLater";
+        let entries = parse_log_content(content, "test.md");
+        let headings: Vec<usize> = entries.iter().map(|entry| entry.line_number).collect();
+        assert_eq!(headings, [0, 3, 6]);
+        assert_eq!(entries[1].content, "- 09:30\nNew style\n- bullet");
+        assert_eq!(entries[2].block_id(), Some("abc123"));
+        models::set_test_heading_format(None);
+    }
+
     #[test]
     fn parse_task_content_reads_priority_marker() {
         let content = "- [ ] [#A] Important\n- [x] [#c] Later\n";
@@ -1622,23 +2556,204 @@ mod tests {
         fs::write(&path, "- [ ] Task\n").expect("write log");
         let path_str = path.to_string_lossy().to_string();
 
-        assert!(cycle_task_priority(&path_str, 0).expect("cycle A"));
+        assert!(cycle_task_priority(&path_str, 0, "task").expect("cycle A"));
         let content = fs::read_to_string(&path).expect("read log");
         assert_eq!(content.lines().next().unwrap_or(""), "- [ ] [#A] Task");
 
-        assert!(cycle_task_priority(&path_str, 0).expect("cycle B"));
+        assert!(cycle_task_priority(&path_str, 0, "task").expect("cycle B"));
         let content = fs::read_to_string(&path).expect("read log");
         assert_eq!(content.lines().next().unwrap_or(""), "- [ ] [#B] Task");
 
-        assert!(cycle_task_priority(&path_str, 0).expect("cycle C"));
+        assert!(cycle_task_priority(&path_str, 0, "task").expect("cycle C"));
         let content = fs::read_to_string(&path).expect("read log");
         assert_eq!(content.lines().next().unwrap_or(""), "- [ ] [#C] Task");
 
-        assert!(cycle_task_priority(&path_str, 0).expect("cycle clear"));
+        assert!(cycle_task_priority(&path_str, 0, "task").expect("cycle clear"));
         let content = fs::read_to_string(&path).expect("read log");
         assert_eq!(content.lines().next().unwrap_or(""), "- [ ] Task");
     }
 
+    #[test]
+    fn emoji_priority_parses_and_cycles() {
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2024-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(&path, "- [ ] Task 🔼 📅 2024-01-05 🍅 ^abc123\n").expect("write log");
+        let task = parse_task_content(&fs::read_to_string(&path).expect("read"), &path_str)
+            .remove(0);
+        assert_eq!(task.priority, Some(Priority::Medium));
+        assert_eq!(task.text, "Task");
+        assert_eq!(task.task_identity, "task");
+        assert_eq!(task.tomato_count, 1);
+
+        crate::task_metadata::set_test_emoji_syntax(Some(true));
+        let cycled = cycle_task_priority(&path_str, 0, "task").expect("cycle");
+        crate::task_metadata::set_test_emoji_syntax(None);
+        assert!(cycled);
+        let content = fs::read_to_string(&path).expect("read log");
+        assert_eq!(content, "- [ ] Task 📅 2024-01-05 🔽 🍅 ^abc123\n");
+
+        // Inline syntax replaces the emoji with a `[#X]` marker.
+        assert!(cycle_task_priority(&path_str, 0, "task").expect("cycle"));
+        let content = fs::read_to_string(&path).expect("read log");
+        assert_eq!(content, "- [ ] Task 📅 2024-01-05 🍅 ^abc123\n");
+        assert!(cycle_task_priority(&path_str, 0, "task").expect("cycle"));
+        let content = fs::read_to_string(&path).expect("read log");
+        assert_eq!(content, "- [ ] [#A] Task 📅 2024-01-05 🍅 ^abc123\n");
+    }
+
+    #[test]
+    fn completing_recurring_task_spawns_next_occurrence() {
+        let date = |value| NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date");
+        let mut lines = vec![
+            "- [x] Water plants @every(1w) @sched(2025-01-06) @due(2025-01-08) 🍅 ^abc123"
+                .to_string(),
+            "  - [ ] Fern".to_string(),
+            "- [ ] Other".to_string(),
+        ];
+        // Occurrences that already passed are skipped.
+        assert!(insert_next_occurrence(&mut lines, 0, date("2025-01-06"), date("2025-01-20")));
+        assert_eq!(
+            lines[2],
+            "- [ ] Water plants @sched(2025-01-27) @due(2025-01-29) @every(1w)"
+        );
+        assert!(!insert_next_occurrence(&mut lines, 0, date("2025-01-06"), date("2025-01-20")));
+        assert_eq!(lines.len(), 4);
+
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2999-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(&path, "- [ ] Pay rent @every(month 1) @due(2999-01-01)\n").expect("write");
+        let task = parse_task_content(&fs::read_to_string(&path).expect("read"), &path_str)
+            .remove(0);
+        assert_eq!(task.text, "Pay rent");
+        toggle_task_status(&path_str, 0, &task.task_identity).expect("toggle");
+        assert_eq!(
+            fs::read_to_string(&path).expect("read log"),
+            "- [x] Pay rent @every(month 1) @due(2999-01-01)\n\
+             - [ ] Pay rent @due(2999-02-01) @every(month 1)\n"
+        );
+
+        let projected = projected_occurrences(
+            &task,
+            date("2999-01-01"),
+            date("2998-12-01"),
+            date("2999-01-01"),
+            date("2999-04-15"),
+        );
+        let dates: Vec<NaiveDate> = projected.iter().map(|item| item.date).collect();
+        assert_eq!(dates, [date("2999-02-01"), date("2999-03-01"), date("2999-04-01")]);
+        assert!(projected.iter().all(|item| item.projected && item.line_number == 0));
+        assert_eq!(projected[0].schedule.due, Some(date("2999-02-01")));
+    }
+
+    #[test]
+    fn task_mutations_follow_moved_lines_and_refuse_stale_ones() {
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2024-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(&path, "## [09:00:00]\n- [ ] [#A] Ship it @due(2024-01-05) #work\n")
+            .expect("write log");
+        let task = parse_task_content(&fs::read_to_string(&path).expect("read"), &path_str)
+            .remove(0);
+        assert_eq!(text_identity(&task.text), task.task_identity);
+
+        fs::write(
+            &path,
+            "## [08:00:00]\nInserted elsewhere\n\n## [09:00:00]\n- [ ] [#A] Ship it @due(2024-01-05) #work\n",
+        )
+        .expect("shift log");
+        toggle_task_status(&path_str, task.line_number, &task.task_identity).expect("toggle");
+        let content = fs::read_to_string(&path).expect("read log");
+        assert!(content.contains("- [x] [#A] Ship it"));
+        assert!(content.contains("Inserted elsewhere"));
+
+        fs::write(&path, "- [ ] Ship it\n- [ ] ship   it\n- [ ] Other\n").expect("dupes");
+        let err = append_tomato_to_line(&path_str, 2, &task.task_identity).expect_err("ambiguous");
+        assert!(is_stale_target(&err));
+        assert_eq!(
+            fs::read_to_string(&path).expect("read log"),
+            "- [ ] Ship it\n- [ ] ship   it\n- [ ] Other\n"
+        );
+    }
+
+    #[test]
+    fn block_ids_are_backfilled_and_kept_by_task_mutations() {
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2024-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(&path, "## [09:00:00]\nNote line\n- [ ] Ship it #work\n").expect("write log");
+
+        assert_eq!(backfill_block_ids(&dir).expect("backfill"), (1, 2));
+        assert_eq!(backfill_block_ids(&dir).expect("backfill again"), (0, 0));
+        let content = fs::read_to_string(&path).expect("read log");
+        let entry = parse_log_content(&content, &path_str).remove(0);
+        assert!(entry.block_id().is_some());
+        assert_eq!(content.lines().nth(1), Some("Note line"));
+
+        let task = parse_task_content(&content, &path_str).remove(0);
+        let task_id = task.block_id.clone().expect("task id");
+        assert_eq!(task.text, "Ship it #work");
+        assert_eq!(task.task_identity, text_identity("Ship it"));
+
+        append_tomato_to_line(&path_str, task.line_number, &task.task_identity).expect("tomato");
+        update_task_line(
+            &path_str,
+            task.line_number,
+            &task.task_identity,
+            TaskLineUpdate {
+                text: "Ship it".to_string(),
+                status: TaskStatus::Open,
+                priority: Some(Priority::High),
+                schedule: TaskSchedule::default(),
+            },
+        )
+        .expect("update");
+        let content = fs::read_to_string(&path).expect("read log");
+        assert_eq!(
+            content.lines().nth(2),
+            Some(format!("- [ ] [#A] Ship it 🍅 ^{task_id}").as_str())
+        );
+        let task = parse_task_content(&content, &path_str).remove(0);
+        assert_eq!(task.tomato_count, 1);
+        assert_eq!(task.block_id.as_deref(), Some(task_id.as_str()));
+    }
+
+    #[test]
+    fn entry_mutations_verify_content() {
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2024-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(
+            &path,
+            "## [09:00:00]\n<!-- memolog:folded -->\nFirst\nmore\n\n## [10:00:00]\nSecond\n",
+        )
+        .expect("write log");
+        let expected = "## [10:00:00]\nSecond";
+
+        fs::write(
+            &path,
+            "## [08:00:00]\nNew\n\n## [09:00:00]\n<!-- memolog:folded -->\nFirst\nmore\n\n## [10:00:00]\nSecond\n",
+        )
+        .expect("shift log");
+        let start = replace_entry_lines(
+            &path_str,
+            5,
+            expected,
+            &["## [10:00:00]".to_string(), "Second edited".to_string()],
+        )
+        .expect("replace moved entry");
+        assert_eq!(start, 8);
+
+        let err = delete_entry_lines(&path_str, 8, expected).expect_err("content changed");
+        assert!(is_stale_target(&err));
+        delete_entry_lines(&path_str, 3, "## [09:00:00]\nFirst\nmore").expect("delete folded entry");
+        assert_eq!(
+            fs::read_to_string(&path).expect("read log"),
+            "## [08:00:00]\nNew\n\n\n## [10:00:00]\nSecond edited\n"
+        );
+    }
+
     fn write_log(dir: &Path, date: &str, content: &str) {
         let path = get_file_path_for_date(dir, date);
         fs::write(path, content).expect("write log");
@@ -1723,6 +2838,74 @@ mod tests {
         assert_eq!(tasks[1], "- [ ] Beta Task ⟦2024-12-20⟧");
     }
 
+    #[test]
+    fn pomodoro_state_round_trips_next_to_carryover_date() {
+        let dir = temp_log_dir();
+        mark_carryover_done(&dir).expect("mark carryover");
+        assert_eq!(
+            load_pomodoro_state(&dir).expect("load"),
+            PomodoroState::default()
+        );
+
+        let start = Local::now();
+        let state = PomodoroState {
+            sessions_date: Some("2025-01-15".to_string()),
+            sessions: 3,
+            timer: Some(PomodoroTimer {
+                start,
+                end: start + Duration::minutes(25),
+                phase: models::PomodoroPhase::Work,
+                target: Some(models::PomodoroTarget::Task {
+                    text: "Write report".to_string(),
+                    file_path: "/logs/2025-01-15.md".to_string(),
+                    line_number: 4,
+                    task_identity: "Write report".to_string(),
+                    block_id: Some("abc123".to_string()),
+                }),
+            }),
+        };
+        save_pomodoro_state(&dir, &state).expect("save");
+
+        assert_eq!(load_pomodoro_state(&dir).expect("load"), state);
+        assert!(is_carryover_done(&dir).expect("carryover"));
+    }
+
+    #[test]
+    fn pomodoro_sessions_append_and_skip_bad_lines() {
+        let dir = temp_log_dir();
+        assert!(read_pomodoro_sessions(&dir).expect("read").is_empty());
+
+        let start = Local::now();
+        let finished = PomodoroSession {
+            start,
+            end: start + Duration::minutes(25),
+            task: "Write report".to_string(),
+            task_identity: "Write report".to_string(),
+            file_path: "/logs/2025-01-15.md".to_string(),
+            block_id: Some("abc123".to_string()),
+            planned_minutes: 25,
+            actual_minutes: 25,
+            interrupted: false,
+        };
+        let interrupted = PomodoroSession {
+            end: start + Duration::minutes(10),
+            actual_minutes: 10,
+            interrupted: true,
+            ..finished.clone()
+        };
+        append_pomodoro_session(&dir, &finished).expect("append");
+        let path = pomodoro_log_path(&dir);
+        let mut content = fs::read_to_string(&path).expect("read log");
+        content.push_str("not json\n");
+        fs::write(&path, content).expect("write log");
+        append_pomodoro_session(&dir, &interrupted).expect("append");
+
+        assert_eq!(
+            read_pomodoro_sessions(&dir).expect("read"),
+            vec![finished, interrupted]
+        );
+    }
+
     #[test]
     fn collect_carryover_tasks_deduplicates_latest() {
         let dir = temp_log_dir();
@@ -1756,6 +2939,222 @@ mod tests {
         assert!(tasks.is_empty());
     }
 
+    #[test]
+    fn collect_carryover_tasks_keeps_open_states_and_skips_cancelled() {
+        let dir = temp_log_dir();
+        write_log(
+            &dir,
+            "2024-12-22",
+            "- [/] Doing Task\n- [>] Later Task\n- [-] Dropped Task\n",
+        );
+
+        let tasks = collect_carryover_tasks(&dir, "2024-12-25").expect("collect");
+
+        assert_eq!(
+            tasks,
+            vec![
+                "- [/] Doing Task ⟦2024-12-22⟧",
+                "- [>] Later Task ⟦2024-12-22⟧"
+            ]
+        );
+    }
+
+    #[test]
+    fn completion_stamps_done_and_reopening_clears_it() {
+        let now = NaiveDate::from_ymd_opt(2025, 1, 10)
+            .and_then(|date| date.and_hms_opt(17, 45, 0))
+            .expect("timestamp");
+        let line = "## [09:00:00] - [/] Ship it #work 🍅 ^abc123";
+        assert_eq!(
+            with_task_status_at(line, TaskStatus::Done, None).as_deref(),
+            Some("## [09:00:00] - [x] Ship it #work 🍅 ^abc123")
+        );
+        let now = Some(now);
+        let done = with_task_status_at(line, TaskStatus::Done, now).expect("task line");
+        assert_eq!(
+            done,
+            "## [09:00:00] - [x] Ship it @done(2025-01-10 17:45) #work 🍅 ^abc123"
+        );
+        let task = parse_task_content(&done, "2025-01-10.md").remove(0);
+        assert_eq!(task.text, "Ship it #work");
+        assert_eq!(task.schedule.done, now.map(|now| now.date()));
+        assert_eq!(task.schedule.done_time, now.map(|now| now.time()));
+        assert_eq!(task.tomato_count, 1);
+
+        // An existing stamp is kept, and any other status removes it.
+        let later = now.map(|now| now + Duration::days(1));
+        assert_eq!(with_task_status_at(&done, TaskStatus::Done, later), Some(done.clone()));
+        assert_eq!(
+            with_task_status_at(&done, TaskStatus::Open, later).as_deref(),
+            Some("## [09:00:00] - [ ] Ship it #work 🍅 ^abc123")
+        );
+
+        let carried = "- [ ] Ship it ⟦2025-01-08⟧";
+        assert_eq!(
+            with_task_status_at(carried, TaskStatus::Done, now).as_deref(),
+            Some("- [x] Ship it @done(2025-01-10 17:45) ⟦2025-01-08⟧")
+        );
+    }
+
+    #[test]
+    fn set_task_status_rewrites_checkbox() {
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2024-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(&path, "- [ ] Write report @due(2024-01-05)\n").expect("write log");
+        let task = parse_task_content(&fs::read_to_string(&path).expect("read"), &path_str)
+            .remove(0);
+        assert_eq!(task.status, TaskStatus::Open);
+
+        assert!(set_task_status(&path_str, 0, &task.task_identity, TaskStatus::InProgress)
+            .expect("set in progress"));
+        let content = fs::read_to_string(&path).expect("read log");
+        assert_eq!(content, "- [/] Write report @due(2024-01-05)\n");
+        let task = parse_task_content(&content, &path_str).remove(0);
+        assert_eq!(task.status, TaskStatus::InProgress);
+        assert_eq!(task.text, "Write report");
+
+        assert!(set_task_status(&path_str, 0, &task.task_identity, TaskStatus::Cancelled)
+            .expect("set cancelled"));
+        // Toggling a cancelled task reopens it instead of completing it.
+        toggle_task_status(&path_str, 0, &task.task_identity).expect("toggle");
+        let content = fs::read_to_string(&path).expect("read log");
+        assert_eq!(content, "- [ ] Write report @due(2024-01-05)\n");
+    }
+
+    #[test]
+    fn reschedule_task_moves_scheduled_or_due_date() {
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2024-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(
+            &path,
+            "- [ ] [#A] Plan trip ⟦2023-12-30⟧ #personal 🍅 ^abc123\n\
+             - [/] File taxes @due(2024-01-05, -3d)\n",
+        )
+        .expect("write log");
+        let tasks = parse_task_content(&fs::read_to_string(&path).expect("read"), &path_str);
+        let date = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+
+        assert!(reschedule_task(&path_str, 0, &tasks[0].task_identity, date).expect("defer"));
+        assert!(reschedule_task(&path_str, 1, &tasks[1].task_identity, date).expect("defer"));
+        let content = fs::read_to_string(&path).expect("read log");
+        assert_eq!(
+            content,
+            "- [ ] [#A] Plan trip #personal @sched(2024-01-08) ⟦2023-12-30⟧ 🍅 ^abc123\n\
+             - [/] File taxes @due(2024-01-08, -3d)\n"
+        );
+        assert!(!reschedule_task(&path_str, 1, &tasks[1].task_identity, date).expect("noop"));
+    }
+
+    #[test]
+    fn edit_tasks_applies_one_edit_per_file() {
+        let dir = temp_log_dir();
+        let first = get_file_path_for_date(&dir, "2024-01-01");
+        let second = get_file_path_for_date(&dir, "2024-01-02");
+        fs::write(&first, "- [ ] Call bank\nnote\n- [ ] [#C] Pay rent #home\n").expect("write");
+        fs::write(&second, "- [ ] Water plants @every(day)\n").expect("write");
+        let task_refs = || {
+            let mut targets = Vec::new();
+            for path in [&first, &second] {
+                let path_str = path.to_string_lossy().to_string();
+                let content = fs::read_to_string(path).expect("read");
+                targets.extend(parse_task_content(&content, &path_str).into_iter().map(|task| {
+                    TaskRef {
+                        file_path: task.file_path,
+                        line_number: task.line_number,
+                        task_identity: task.task_identity,
+                    }
+                }));
+            }
+            targets
+        };
+
+        let tag = TaskBatchEdit::AddTag("#home".to_string());
+        assert_eq!(edit_tasks(&task_refs(), &tag).expect("tag"), 2);
+        // Adding a tag changes the task text, so the targets are read again.
+        let targets = task_refs();
+        let high = TaskBatchEdit::SetPriority(Some(Priority::High));
+        assert_eq!(edit_tasks(&targets[..2], &high).expect("priority"), 2);
+        assert_eq!(
+            fs::read_to_string(&first).expect("read"),
+            "- [ ] [#A] Call bank #home\nnote\n- [ ] [#A] Pay rent #home\n"
+        );
+
+        assert_eq!(edit_tasks(&targets, &TaskBatchEdit::Complete).expect("complete"), 3);
+        let second_content = fs::read_to_string(&second).expect("read");
+        assert!(second_content.starts_with("- [x] Water plants #home @every(1d)\n- [ ] "));
+
+        let mut stale = targets.clone();
+        stale[1].task_identity = "gone".to_string();
+        assert!(edit_tasks(&stale, &TaskBatchEdit::Delete).is_err());
+        assert_eq!(edit_tasks(&targets[..2], &TaskBatchEdit::Delete).expect("delete"), 2);
+        assert_eq!(fs::read_to_string(&first).expect("read"), "note\n");
+    }
+
+    #[test]
+    fn edit_tasks_restores_written_files_when_a_write_fails() {
+        let dir = temp_log_dir();
+        let first = get_file_path_for_date(&dir, "2024-01-01");
+        let second = get_file_path_for_date(&dir, "2024-01-02");
+        fs::write(&first, "- [ ] Call bank\n").expect("write");
+        fs::write(&second, "- [ ] Pay rent\n").expect("write");
+        let targets: Vec<TaskRef> = [(&first, "Call bank"), (&second, "Pay rent")]
+            .into_iter()
+            .map(|(path, text)| TaskRef {
+                file_path: path.to_string_lossy().to_string(),
+                line_number: 0,
+                task_identity: task_identity(text),
+            })
+            .collect();
+
+        // A plain file where the second log's backup folder goes makes its write fail.
+        let backups = state_dir_path(&dir).join("backups");
+        fs::create_dir_all(&backups).expect("create backups dir");
+        fs::write(backups.join("2024-01-02"), "").expect("block backup dir");
+
+        assert!(edit_tasks(&targets, &TaskBatchEdit::Complete).is_err());
+        assert_eq!(fs::read_to_string(&first).expect("read"), "- [ ] Call bank\n");
+        assert_eq!(fs::read_to_string(&second).expect("read"), "- [ ] Pay rent\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn deleting_tasks_keeps_headings_and_drops_subtasks() {
+        let dir = temp_log_dir();
+        let path = get_file_path_for_date(&dir, "2024-01-01");
+        let path_str = path.to_string_lossy().to_string();
+        fs::write(
+            &path,
+            "## [08:00:00]\nMorning\n\n## [09:00:00] - [ ] Inline task\nDetails\n\n\
+             - [ ] Parent\n  - [ ] Child\n    - [x] Grandchild\n- [ ] Sibling\n",
+        )
+        .expect("write");
+        let targets: Vec<TaskRef> =
+            parse_task_content(&fs::read_to_string(&path).expect("read"), &path_str)
+                .into_iter()
+                .filter(|task| task.text == "Inline task" || task.text == "Parent")
+                .map(|task| TaskRef {
+                    file_path: task.file_path,
+                    line_number: task.line_number,
+                    task_identity: task.task_identity,
+                })
+                .collect();
+        assert_eq!(targets.len(), 2);
+
+        assert_eq!(edit_tasks(&targets, &TaskBatchEdit::Delete).expect("delete"), 2);
+        let content = fs::read_to_string(&path).expect("read");
+        assert_eq!(
+            content,
+            "## [08:00:00]\nMorning\n\n## [09:00:00]\nDetails\n\n- [ ] Sibling\n"
+        );
+        let entries = parse_log_content(&content, &path_str);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].content, "## [08:00:00]\nMorning");
+        assert!(entries[1].content.starts_with("## [09:00:00]\nDetails"));
+    }
+
     #[test]
     fn collect_carryover_tasks_skips_tasks_already_today() {
         let dir = temp_log_dir();
@@ -1766,4 +3165,51 @@ mod tests {
 
         assert!(tasks.is_empty());
     }
+
+    #[test]
+    fn weekly_layout_keeps_days_in_sections_and_migrates_back() {
+        let dir = temp_log_dir();
+        let weekly = FileLayout::parse("{yyyy}/W{ww}.md").expect("weekly layout");
+        file_layout::set_test_layout(Some(weekly.clone()));
+        let day = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("date");
+
+        append_entry_to_date(&dir, day("2025-01-07"), "Tuesday\n- [ ] Ship it").expect("tue");
+        append_entry_to_date(&dir, day("2025-01-06"), "Monday").expect("mon");
+        append_entry_to_date(&dir, day("2025-01-07"), "Tuesday again").expect("tue again");
+
+        let path = dir.join("2025").join("W02.md");
+        let content = fs::read_to_string(&path).expect("read week");
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(&lines[..2], ["# 2025-01-06", ""]);
+        assert_eq!(lines[3], "Monday");
+        assert_eq!(&lines[5..7], ["# 2025-01-07", ""]);
+        assert_eq!(lines.iter().filter(|line| is_timestamped_line(line)).count(), 3);
+
+        let entries =
+            read_entries_for_date_range(&dir, day("2025-01-07"), day("2025-01-07")).expect("read");
+        let bodies: Vec<&str> = entries
+            .iter()
+            .filter_map(|entry| entry.content.lines().nth(1))
+            .collect();
+        assert_eq!(bodies, ["Tuesday", "Tuesday again"]);
+        assert!(entries.iter().all(|entry| entry.date == Some(day("2025-01-07"))));
+        assert_eq!(
+            get_available_log_dates(&dir).expect("dates"),
+            [day("2025-01-06"), day("2025-01-07")]
+        );
+        let tasks = read_tasks_for_date_range(&dir, day("2025-01-06"), day("2025-01-12"))
+            .expect("tasks");
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].date, day("2025-01-07"));
+
+        let daily = FileLayout::default();
+        assert_eq!(migrate_layout(&dir, &weekly, &daily).expect("migrate"), (2, 2));
+        file_layout::set_test_layout(None);
+        assert!(!path.exists());
+        let monday = fs::read_to_string(dir.join("2025-01-06.md")).expect("read monday");
+        assert!(monday.ends_with("Monday\n"));
+        let entries =
+            read_entries_for_date_range(&dir, day("2025-01-06"), day("2025-01-07")).expect("read");
+        assert_eq!(entries.len(), 3);
+    }
 }
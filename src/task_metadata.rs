@@ -1,5 +1,12 @@
-use crate::models::TaskSchedule;
+//! Inline task metadata: `@key(value)` tokens, Dataview `key:: value` fields and Obsidian
+//! Tasks emoji signifiers (`📅 2025-01-10`, `🔁 every week`, `⏫`).
+//!
+//! All three syntaxes are always read; `ui.metadata_syntax` picks the one that is written.
+
+use crate::models::{Estimate, Priority, TaskSchedule};
+use crate::recurrence::Recurrence;
 use chrono::{NaiveDate, NaiveTime};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TaskMetadataKey {
@@ -8,6 +15,85 @@ pub enum TaskMetadataKey {
     Start,
     Time,
     Duration,
+    Every,
+    /// Completion date. Read from `@done(...)` and `✅`, written on completion.
+    Done,
+    /// Planned effort, `@est(4🍅)` or `@est(2h)`.
+    Estimate,
+    /// Priority signifier. Only the emoji form is a metadata token; `[#A]` markers at the
+    /// start of a task are handled by storage.
+    Priority,
+}
+
+/// Format of the `@done(...)` completion stamp.
+pub const DONE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+static EMOJI_SYNTAX: AtomicBool = AtomicBool::new(false);
+
+/// Selects the syntax written by [`upsert_task_metadata_token`]: `"inline"` (`@due(...)`,
+/// the default) or `"emoji"` (Obsidian Tasks signifiers).
+pub fn configure_syntax(syntax: &str) {
+    let emoji = match syntax.trim().to_ascii_lowercase().as_str() {
+        "emoji" | "obsidian" | "obsidian_tasks" => true,
+        "inline" | "" => false,
+        other => {
+            eprintln!("Unknown ui.metadata_syntax `{other}`; using `inline`.");
+            false
+        }
+    };
+    EMOJI_SYNTAX.store(emoji, Ordering::Relaxed);
+}
+
+/// Whether new metadata is written as Obsidian Tasks emoji.
+pub fn emoji_syntax() -> bool {
+    #[cfg(test)]
+    if let Some(emoji) = TEST_EMOJI_SYNTAX.with(|emoji| emoji.get()) {
+        return emoji;
+    }
+    EMOJI_SYNTAX.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_EMOJI_SYNTAX: std::cell::Cell<Option<bool>> = const { std::cell::Cell::new(None) };
+}
+
+/// Overrides the written syntax for the calling test thread only.
+#[cfg(test)]
+pub fn set_test_emoji_syntax(emoji: Option<bool>) {
+    TEST_EMOJI_SYNTAX.with(|current| current.set(emoji));
+}
+
+const EMOJI_SIGNIFIERS: [(char, TaskMetadataKey); 8] = [
+    ('📅', TaskMetadataKey::Due),
+    ('📆', TaskMetadataKey::Due),
+    ('🗓', TaskMetadataKey::Due),
+    ('⏳', TaskMetadataKey::Scheduled),
+    ('⌛', TaskMetadataKey::Scheduled),
+    ('🛫', TaskMetadataKey::Start),
+    ('🔁', TaskMetadataKey::Every),
+    ('✅', TaskMetadataKey::Done),
+];
+
+const PRIORITY_EMOJI: [(char, Priority); 5] = [
+    ('🔺', Priority::High),
+    ('⏫', Priority::High),
+    ('🔼', Priority::Medium),
+    ('🔽', Priority::Low),
+    ('⏬', Priority::Low),
+];
+
+fn priority_emoji(priority: Priority) -> char {
+    match priority {
+        Priority::High => '⏫',
+        Priority::Medium => '🔼',
+        Priority::Low => '🔽',
+    }
+}
+
+fn is_signifier(c: char) -> bool {
+    EMOJI_SIGNIFIERS.iter().any(|(emoji, _)| *emoji == c)
+        || PRIORITY_EMOJI.iter().any(|(emoji, _)| *emoji == c)
 }
 
 impl TaskMetadataKey {
@@ -18,6 +104,9 @@ impl TaskMetadataKey {
             "start" => Some(TaskMetadataKey::Start),
             "time" => Some(TaskMetadataKey::Time),
             "dur" | "duration" => Some(TaskMetadataKey::Duration),
+            "every" | "repeat" => Some(TaskMetadataKey::Every),
+            "done" => Some(TaskMetadataKey::Done),
+            "est" | "estimate" => Some(TaskMetadataKey::Estimate),
             _ => None,
         }
     }
@@ -29,6 +118,24 @@ impl TaskMetadataKey {
             TaskMetadataKey::Start => "start",
             TaskMetadataKey::Time => "time",
             TaskMetadataKey::Duration => "dur",
+            TaskMetadataKey::Every => "every",
+            TaskMetadataKey::Done => "done",
+            TaskMetadataKey::Estimate => "est",
+            TaskMetadataKey::Priority => "priority",
+        }
+    }
+
+    fn emoji(self) -> Option<char> {
+        match self {
+            TaskMetadataKey::Scheduled => Some('⏳'),
+            TaskMetadataKey::Due => Some('📅'),
+            TaskMetadataKey::Start => Some('🛫'),
+            TaskMetadataKey::Every => Some('🔁'),
+            TaskMetadataKey::Done => Some('✅'),
+            TaskMetadataKey::Time
+            | TaskMetadataKey::Duration
+            | TaskMetadataKey::Estimate
+            | TaskMetadataKey::Priority => None,
         }
     }
 }
@@ -51,9 +158,9 @@ pub fn parse_task_metadata(text: &str) -> (TaskSchedule, String) {
                 schedule.scheduled = Some(d);
                 ()
             }),
-            TaskMetadataKey::Due => parse_date(&token.value).map(|d| {
+            TaskMetadataKey::Due => parse_due(&token.value).map(|(d, warning)| {
                 schedule.due = Some(d);
-                ()
+                schedule.due_warning_days = warning;
             }),
             TaskMetadataKey::Start => parse_date(&token.value).map(|d| {
                 schedule.start = Some(d);
@@ -67,6 +174,17 @@ pub fn parse_task_metadata(text: &str) -> (TaskSchedule, String) {
                 schedule.duration_minutes = Some(m);
                 ()
             }),
+            TaskMetadataKey::Every => Recurrence::parse(&token.value).map(|r| {
+                schedule.recurrence = Some(r);
+            }),
+            TaskMetadataKey::Done => parse_done(&token.value).map(|(date, time)| {
+                schedule.done = Some(date);
+                schedule.done_time = time;
+            }),
+            TaskMetadataKey::Estimate => parse_estimate(&token.value).map(|estimate| {
+                schedule.estimate = Some(estimate);
+            }),
+            TaskMetadataKey::Priority => parse_priority_value(&token.value).map(|_| ()),
         };
 
         if parsed.is_some() {
@@ -83,32 +201,81 @@ pub fn strip_task_metadata_tokens(text: &str) -> String {
     let valid = tokens
         .into_iter()
         .filter(|token| match token.key {
-            TaskMetadataKey::Scheduled | TaskMetadataKey::Due | TaskMetadataKey::Start => {
+            TaskMetadataKey::Scheduled | TaskMetadataKey::Start => {
                 parse_date(&token.value).is_some()
             }
+            TaskMetadataKey::Due => parse_due(&token.value).is_some(),
+            TaskMetadataKey::Done => parse_done(&token.value).is_some(),
             TaskMetadataKey::Time => parse_time(&token.value).is_some(),
             TaskMetadataKey::Duration => parse_duration_minutes(&token.value).is_some(),
+            TaskMetadataKey::Every => Recurrence::parse(&token.value).is_some(),
+            TaskMetadataKey::Estimate => parse_estimate(&token.value).is_some(),
+            TaskMetadataKey::Priority => parse_priority_value(&token.value).is_some(),
         })
         .collect::<Vec<_>>();
     strip_tokens(text, &valid)
 }
 
+/// Priority from an Obsidian Tasks signifier (`⏫`, `🔼`, `🔽`, ...) anywhere in `text`.
+pub fn parse_priority_emoji(text: &str) -> Option<Priority> {
+    scan_emoji_tokens(text)
+        .into_iter()
+        .filter(|token| token.key == TaskMetadataKey::Priority)
+        .find_map(|token| parse_priority_value(&token.value))
+}
+
+fn parse_priority_value(value: &str) -> Option<Priority> {
+    let mut chars = value.chars();
+    let letter = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    Priority::from_char(letter)
+}
+
 pub fn upsert_task_metadata_token(text: &str, key: TaskMetadataKey, value: &str) -> String {
     let mut output = remove_tokens_by_key(text, key);
     let trimmed = output.trim_end();
     output = trimmed.to_string();
 
-    if !value.trim().is_empty() {
-        if !output.is_empty() {
-            output.push(' ');
+    let value = value.trim();
+    if value.is_empty() {
+        return output;
+    }
+    if !output.is_empty() {
+        output.push(' ');
+    }
+
+    if key == TaskMetadataKey::Priority {
+        if let Some(priority) = parse_priority_value(value) {
+            output.push(priority_emoji(priority));
+        }
+        return output.trim_end().to_string();
+    }
+    // Obsidian Tasks has no warning period, so a `@due(date, -3d)` stays inline.
+    if emoji_syntax()
+        && let Some(emoji) = key.emoji()
+        && !value.contains(',')
+    {
+        output.push(emoji);
+        output.push(' ');
+        match key {
+            TaskMetadataKey::Every => match Recurrence::parse(value) {
+                Some(rule) => output.push_str(&rule.obsidian_text()),
+                None => output.push_str(value),
+            },
+            // Obsidian Tasks only records the completion date.
+            TaskMetadataKey::Done => output.push_str(value.split_whitespace().next().unwrap_or("")),
+            _ => output.push_str(value),
         }
-        output.push('@');
-        output.push_str(key.as_token());
-        output.push('(');
-        output.push_str(value.trim());
-        output.push(')');
+        return output;
     }
 
+    output.push('@');
+    output.push_str(key.as_token());
+    output.push('(');
+    output.push_str(value);
+    output.push(')');
     output
 }
 
@@ -119,6 +286,7 @@ pub fn remove_task_metadata_token(text: &str, key: TaskMetadataKey) -> String {
 fn scan_tokens(text: &str) -> Vec<TokenMatch> {
     let mut tokens = scan_at_tokens(text);
     tokens.extend(scan_dataview_tokens(text));
+    tokens.extend(scan_emoji_tokens(text));
     tokens.sort_by_key(|token| token.range.start);
     tokens
 }
@@ -175,6 +343,8 @@ fn scan_dataview_tokens(text: &str) -> Vec<TokenMatch> {
         ("start", TaskMetadataKey::Start),
         ("time", TaskMetadataKey::Time),
         ("duration", TaskMetadataKey::Duration),
+        ("repeat", TaskMetadataKey::Every),
+        ("estimate", TaskMetadataKey::Estimate),
     ];
     let bytes = text.as_bytes();
     let mut tokens = Vec::new();
@@ -213,6 +383,56 @@ fn scan_dataview_tokens(text: &str) -> Vec<TokenMatch> {
     tokens
 }
 
+/// Obsidian Tasks signifiers. Dates take the following word; a `🔁` rule runs until the next
+/// signifier, tag or `@` token.
+fn scan_emoji_tokens(text: &str) -> Vec<TokenMatch> {
+    let mut tokens = Vec::new();
+
+    for (start, c) in text.char_indices() {
+        let mut end = start + c.len_utf8();
+        if text[end..].starts_with('\u{fe0f}') {
+            end += '\u{fe0f}'.len_utf8();
+        }
+
+        if let Some((_, priority)) = PRIORITY_EMOJI.iter().find(|(emoji, _)| *emoji == c) {
+            tokens.push(TokenMatch {
+                key: TaskMetadataKey::Priority,
+                range: start..end,
+                value: priority.as_char().to_string(),
+            });
+            continue;
+        }
+        let Some((_, key)) = EMOJI_SIGNIFIERS.iter().find(|(emoji, _)| *emoji == c) else {
+            continue;
+        };
+
+        let mut value_end = end;
+        let mut offset = end;
+        for word in text[end..].split_whitespace() {
+            let word_start = offset + text[offset..].find(word).unwrap_or(0);
+            let first = word.chars().next().unwrap_or(' ');
+            if is_signifier(first) || matches!(first, '#' | '@' | '⟦' | '[') {
+                break;
+            }
+            value_end = word_start + word.len();
+            offset = value_end;
+            if *key != TaskMetadataKey::Every {
+                break;
+            }
+        }
+        if value_end == end {
+            continue;
+        }
+        tokens.push(TokenMatch {
+            key: *key,
+            range: start..value_end,
+            value: text[end..value_end].trim().to_string(),
+        });
+    }
+
+    tokens
+}
+
 fn strip_tokens(text: &str, tokens: &[TokenMatch]) -> String {
     if tokens.is_empty() {
         return text.trim().to_string();
@@ -302,6 +522,45 @@ pub(crate) fn parse_date(value: &str) -> Option<NaiveDate> {
     NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
 }
 
+/// `YYYY-MM-DD` with an optional warning period: `2025-02-01, -3d` (or `-2w`).
+pub(crate) fn parse_due(value: &str) -> Option<(NaiveDate, Option<u32>)> {
+    let Some((date, warning)) = value.split_once(',') else {
+        return Some((parse_date(value.trim())?, None));
+    };
+    let warning = warning.trim().strip_prefix('-')?;
+    let unit = warning.chars().last()?;
+    let count: u32 = warning[..warning.len() - unit.len_utf8()].parse().ok()?;
+    let days = match unit {
+        'd' | 'D' => count,
+        'w' | 'W' => count.checked_mul(7)?,
+        _ => return None,
+    };
+    Some((parse_date(date.trim())?, Some(days)))
+}
+
+/// Value of a `@due(...)` token, including the warning period if there is one.
+pub fn format_due(date: NaiveDate, warning_days: Option<u32>) -> String {
+    let date = date.format("%Y-%m-%d");
+    match warning_days {
+        Some(days) => format!("{date}, -{days}d"),
+        None => date.to_string(),
+    }
+}
+
+/// `YYYY-MM-DD` with an optional `HH:MM` after a space.
+pub(crate) fn parse_done(value: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let mut parts = value.split_whitespace();
+    let date = parse_date(parts.next()?)?;
+    let time = match parts.next() {
+        Some(time) => Some(parse_time(time)?),
+        None => None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((date, time))
+}
+
 pub(crate) fn parse_time(value: &str) -> Option<NaiveTime> {
     let trimmed = value.trim();
     if trimmed.contains(':') {
@@ -333,6 +592,22 @@ pub(crate) fn parse_time(value: &str) -> Option<NaiveTime> {
     None
 }
 
+/// `4🍅`, `4 🍅` or a bare `4` count pomodoros; anything else is read as a duration (`2h`,
+/// `90m`, `1h30m`). Zero is not an estimate.
+pub(crate) fn parse_estimate(value: &str) -> Option<Estimate> {
+    let trimmed = value.trim();
+    let count = trimmed.trim_end_matches('🍅').trim_end();
+    let estimate = if !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()) {
+        Estimate::Pomodoros(count.parse().ok()?)
+    } else {
+        Estimate::Minutes(parse_duration_minutes(trimmed)?)
+    };
+    match estimate {
+        Estimate::Pomodoros(0) | Estimate::Minutes(0) => None,
+        estimate => Some(estimate),
+    }
+}
+
 pub(crate) fn parse_duration_minutes(value: &str) -> Option<u32> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -407,6 +682,86 @@ mod tests {
         assert_eq!(text, "Task @due(2025-99-01) something");
     }
 
+    #[test]
+    fn parses_recurrence_tokens() {
+        let (schedule, text) = parse_task_metadata("Standup @every(mon, thu) @due(2025-01-06)");
+        assert_eq!(text, "Standup");
+        assert_eq!(
+            schedule.recurrence.map(|r| r.to_string()).as_deref(),
+            Some("mon,thu")
+        );
+        let (schedule, text) = parse_task_metadata("Rent @every(fortnightly)");
+        assert_eq!(schedule.recurrence, None);
+        assert_eq!(text, "Rent @every(fortnightly)");
+    }
+
+    #[test]
+    fn parses_due_warning_period() {
+        let (schedule, text) = parse_task_metadata("File taxes @due(2025-04-15, -2w)");
+        assert_eq!(text, "File taxes");
+        assert_eq!(schedule.due, Some(NaiveDate::from_ymd_opt(2025, 4, 15).unwrap()));
+        assert_eq!(schedule.due_warning_days, Some(14));
+        assert_eq!(format_due(schedule.due.unwrap(), Some(3)), "2025-04-15, -3d");
+
+        let (schedule, text) = parse_task_metadata("File taxes @due(2025-04-15, soon)");
+        assert_eq!(schedule.due, None);
+        assert_eq!(text, "File taxes @due(2025-04-15, soon)");
+    }
+
+    #[test]
+    fn parses_estimate_tokens() {
+        let (schedule, text) = parse_task_metadata("Write report @est(4🍅) @due(2025-02-01)");
+        assert_eq!(text, "Write report");
+        assert_eq!(schedule.estimate, Some(Estimate::Pomodoros(4)));
+
+        let (schedule, text) = parse_task_metadata("Refactor estimate:: 1h30m");
+        assert_eq!(text, "Refactor");
+        assert_eq!(schedule.estimate, Some(Estimate::Minutes(90)));
+        assert!(schedule.is_empty());
+
+        let (schedule, text) = parse_task_metadata("Nap @est(0) @est(lots)");
+        assert_eq!(schedule.estimate, None);
+        assert_eq!(text, "Nap @est(0) @est(lots)");
+    }
+
+    #[test]
+    fn parses_obsidian_tasks_emoji() {
+        let input = "Pay rent ⏫ 🔁 every month on the 1st ⏳ 2025-01-28 📅 2025-02-01 #home";
+        let (schedule, text) = parse_task_metadata(input);
+        assert_eq!(text, "Pay rent #home");
+        assert_eq!(schedule.due, Some(NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()));
+        assert_eq!(schedule.scheduled, Some(NaiveDate::from_ymd_opt(2025, 1, 28).unwrap()));
+        assert_eq!(schedule.recurrence, Some(Recurrence::MonthDay(1)));
+        assert_eq!(parse_priority_emoji(input), Some(Priority::High));
+
+        let (schedule, text) = parse_task_metadata("Ship it @done(2025-01-03 17:45)");
+        assert_eq!(text, "Ship it");
+        assert_eq!(schedule.done_time, Some(NaiveTime::from_hms_opt(17, 45, 0).unwrap()));
+        let (schedule, text) = parse_task_metadata("Ship it ✅ 2025-01-03 🛫 2025-01-01");
+        assert_eq!(text, "Ship it");
+        assert_eq!(schedule.done, Some(NaiveDate::from_ymd_opt(2025, 1, 3).unwrap()));
+        assert_eq!(schedule.start, Some(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()));
+
+        let (schedule, text) = parse_task_metadata("Party 📅 someday");
+        assert_eq!(schedule.due, None);
+        assert_eq!(text, "Party 📅 someday");
+    }
+
+    #[test]
+    fn upsert_writes_configured_syntax() {
+        set_test_emoji_syntax(Some(true));
+        let input = "Task @due(2025-01-01)";
+        let updated = upsert_task_metadata_token(input, TaskMetadataKey::Due, "2025-01-02");
+        let updated = upsert_task_metadata_token(&updated, TaskMetadataKey::Every, "1w");
+        let updated = upsert_task_metadata_token(&updated, TaskMetadataKey::Time, "09:00");
+        let updated = upsert_task_metadata_token(&updated, TaskMetadataKey::Priority, "B");
+        set_test_emoji_syntax(None);
+        assert_eq!(updated, "Task 📅 2025-01-02 🔁 every week @time(09:00) 🔼");
+
+        let updated = upsert_task_metadata_token(&updated, TaskMetadataKey::Due, "2025-01-03");
+        assert_eq!(updated, "Task 🔁 every week @time(09:00) 🔼 @due(2025-01-03)");
+    }
+
     #[test]
     fn upsert_replaces_existing_token() {
         let input = "Task @due(2025-01-01) notes";
@@ -1,10 +1,12 @@
 use crate::config::Theme;
-use crate::models::Priority;
+use crate::models::{Priority, TaskStatus, split_task_checkbox};
 use crate::ui::color_parser::parse_color;
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::Span,
+    widgets::Widget,
 };
 use unicode_width::UnicodeWidthStr;
 
@@ -99,17 +101,25 @@ pub fn parse_markdown_spans(
 
     // TODO checkboxes at line start.
     // Keep display width comparable to the original "- [ ] " / "- [x] " prefix for cleaner wrapping.
-    let (content, todo_prefix) = if let Some(stripped) = content.strip_prefix("- [ ] ") {
-        let color = parse_color(&theme.todo_wip);
-        spans.push(Span::styled("• [ ] ", Style::default().fg(color)));
-        (stripped, true)
-    } else if let Some(stripped) = content.strip_prefix("- [x] ") {
-        let color = parse_color(&theme.todo_done);
-        spans.push(Span::styled("• [✓] ", Style::default().fg(color)));
-        (stripped, true)
-    } else if let Some(stripped) = content.strip_prefix("- [X] ") {
-        let color = parse_color(&theme.todo_done);
-        spans.push(Span::styled("• [✓] ", Style::default().fg(color)));
+    let (content, todo_prefix) = if let Some((status, stripped)) = split_task_checkbox(content) {
+        let (marker, style) = match status {
+            TaskStatus::Open => ("• [ ] ", Style::default().fg(parse_color(&theme.todo_wip))),
+            TaskStatus::InProgress => (
+                "• [/] ",
+                Style::default()
+                    .fg(parse_color(&theme.todo_wip))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            TaskStatus::Done => ("• [✓] ", Style::default().fg(parse_color(&theme.todo_done))),
+            TaskStatus::Cancelled => (
+                "• [✗] ",
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::CROSSED_OUT),
+            ),
+            TaskStatus::Deferred => ("• [>] ", Style::default().fg(Color::DarkGray)),
+        };
+        spans.push(Span::styled(marker, style));
         (stripped, true)
     } else {
         (content, false)
@@ -475,9 +485,7 @@ fn normalize_leading_whitespace(text: &str) -> (String, &str) {
 
     // Safe because i stops on ASCII whitespace boundaries.
     let rest = &text[i..];
-    let is_list = rest.starts_with("- [ ] ")
-        || rest.starts_with("- [x] ")
-        || rest.starts_with("- [X] ")
+    let is_list = split_task_checkbox(rest).is_some()
         || rest.starts_with("- ")
         || rest.starts_with("* ")
         || rest.starts_with("+ ")
@@ -496,14 +504,8 @@ fn normalize_leading_whitespace(text: &str) -> (String, &str) {
 }
 
 fn split_list_marker(text: &str) -> (&'static str, &str) {
-    if let Some(rest) = text.strip_prefix("- [ ] ") {
-        return ("- [ ] ", rest);
-    }
-    if let Some(rest) = text.strip_prefix("- [x] ") {
-        return ("- [x] ", rest);
-    }
-    if let Some(rest) = text.strip_prefix("- [X] ") {
-        return ("- [X] ", rest);
+    if let Some((status, rest)) = split_task_checkbox(text) {
+        return (status.checkbox(), rest);
     }
 
     if let Some(rest) = text.strip_prefix("- ") {
@@ -518,3 +520,76 @@ fn split_list_marker(text: &str) -> (&'static str, &str) {
 
     ("", text)
 }
+
+/// Five-row block digits for a clock such as `24:59`, centered in the area. Characters other
+/// than digits and `:` are skipped; nothing is drawn when the area is too small.
+pub struct BigDigits<'a> {
+    text: &'a str,
+    style: Style,
+}
+
+impl<'a> BigDigits<'a> {
+    pub const HEIGHT: u16 = 5;
+
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            style: Style::default(),
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Columns needed to draw `text`.
+    pub fn width(text: &str) -> u16 {
+        let glyphs: Vec<&[&str; 5]> = text.chars().filter_map(big_glyph).collect();
+        let columns: usize = glyphs.iter().map(|glyph| glyph[0].len() * 2).sum();
+        (columns + glyphs.len().saturating_sub(1) * 2) as u16
+    }
+}
+
+impl Widget for BigDigits<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = Self::width(self.text);
+        if area.width < width || area.height < Self::HEIGHT {
+            return;
+        }
+        let mut x = area.x + (area.width - width) / 2;
+        let y = area.y + (area.height - Self::HEIGHT) / 2;
+        for glyph in self.text.chars().filter_map(big_glyph) {
+            for (row, pattern) in glyph.iter().enumerate() {
+                for (col, cell) in pattern.chars().enumerate() {
+                    if cell == '#' {
+                        let cell_x = x + col as u16 * 2;
+                        buf.set_string(cell_x, y + row as u16, "██", self.style);
+                    }
+                }
+            }
+            x += glyph[0].len() as u16 * 2 + 2;
+        }
+    }
+}
+
+fn big_glyph(c: char) -> Option<&'static [&'static str; 5]> {
+    const DIGITS: [[&str; 5]; 10] = [
+        ["###", "# #", "# #", "# #", "###"],
+        [" # ", "## ", " # ", " # ", "###"],
+        ["###", "  #", "###", "#  ", "###"],
+        ["###", "  #", "###", "  #", "###"],
+        ["# #", "# #", "###", "  #", "  #"],
+        ["###", "#  ", "###", "  #", "###"],
+        ["###", "#  ", "###", "# #", "###"],
+        ["###", "  #", "  #", "  #", "  #"],
+        ["###", "# #", "###", "# #", "###"],
+        ["###", "# #", "###", "  #", "###"],
+    ];
+    const COLON: [&str; 5] = [" ", "#", " ", "#", " "];
+    match c {
+        '0'..='9' => DIGITS.get(c as usize - '0' as usize),
+        ':' => Some(&COLON),
+        _ => None,
+    }
+}
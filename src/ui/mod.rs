@@ -9,8 +9,9 @@ use ratatui::{
 
 use crate::app::{App, PLACEHOLDER_COMPOSE};
 use crate::config::{Theme, ThemePreset, ThemeToastOverrides, ThemeUiOverrides};
+use crate::storage;
 use crate::models::{
-    AgendaItemKind, EditorMode, InputMode, NavigateFocus, VisualKind,
+    AgendaItemKind, EditorMode, InputMode, LogEntry, NavigateFocus, VisualKind, heading_format,
     is_heading_timestamp_line, is_timestamped_line, split_timestamp_line,
 };
 use ratatui::style::Stylize;
@@ -29,11 +30,13 @@ pub mod theme;
 
 use components::{centered_column, markdown_prefix_width, parse_markdown_spans, wrap_markdown_line};
 use popups::{
-    render_ai_loading_popup, render_ai_response_popup, render_activity_popup,
-    render_date_picker_popup, render_delete_entry_popup, render_editor_style_popup,
-    render_exit_popup, render_google_auth_popup, render_help_popup, render_memo_preview_popup,
+    render_ai_loading_popup, render_ai_response_popup, render_activity_popup, render_bulk_popup,
+    render_date_picker_popup, render_defer_popup, render_delete_entry_popup,
+    render_editor_style_popup,
+    render_exit_popup, render_focus_screen, render_google_auth_popup, render_help_popup,
+    render_memo_preview_popup,
     render_mood_popup, render_path_popup, render_pomodoro_popup, render_siren_popup,
-    render_tag_popup, render_theme_switcher_popup, render_todo_popup,
+    render_backup_popup, render_tag_popup, render_theme_switcher_popup, render_todo_popup,
 };
 
 pub fn ui(f: &mut Frame, app: &mut App) {
@@ -91,7 +94,8 @@ pub fn ui(f: &mut Frame, app: &mut App) {
 
         // Timeline log view
         let list_area_width = timeline_inner.width.saturating_sub(1).max(1) as usize;
-        let timestamp_width: usize = 11; // "[HH:MM:SS] "
+        // "[HH:MM:SS] ", or wider when the configured time format needs it.
+        let timestamp_width: usize = heading_format().time_width().max(10) + 1;
         let blank_timestamp = " ".repeat(timestamp_width);
         let timestamp_color = tokens.content_timestamp;
 
@@ -110,11 +114,10 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         if app.is_search_result
             && highlight_ready
             && let Some(query) = app.search_highlight_query.as_deref()
+            && let Some(pattern) =
+                crate::search_query::highlight_pattern(query, Local::now().date_naive())
         {
-            let query = query.trim();
-            if !query.is_empty() {
-                search_regex = Regex::new(&format!("(?i){}", regex::escape(query))).ok();
-            }
+            search_regex = Regex::new(&pattern).ok();
         }
 
         let search_style = Style::default()
@@ -134,7 +137,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         let viewport_height = timeline_inner.height as usize;
 
         for (log_idx, entry) in app.logs.iter().enumerate() {
-            let entry_date = file_date(&entry.file_path);
+            let entry_date = entry_date(entry);
 
             // Insert date separator if date changed (only for non-search view)
             if !app.is_search_result
@@ -166,7 +169,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             let fence_style = code_fallback_style(code_bg).fg(tokens.ui_muted);
 
             let date_prefix = if app.is_search_result {
-                file_date(&entry.file_path)
+                entry_date.clone()
             } else {
                 None
             };
@@ -269,7 +272,8 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 let display_line = if line_in_code_block {
                     content_line.to_string()
                 } else {
-                    crate::app::strip_context_tags_from_line(content_line).0
+                    let without_id = crate::block_ids::strip_block_id(content_line);
+                    crate::app::strip_context_tags_from_line(without_id).0
                 };
                 let wrapped = wrap_markdown_line(&display_line, wrap_width);
                 let code_segments = if line_in_code_block {
@@ -320,10 +324,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
 
                     if entry_has_timestamp {
                         let ts_span = if line_idx == first_body_index && wrap_idx == 0 {
-                            let mut ts_text = ts_prefix.to_string();
-                            if !ts_text.is_empty() && !ts_text.ends_with(' ') {
-                                ts_text.push(' ');
-                            }
+                            let ts_text = format!("{:<timestamp_width$}", ts_prefix.trim_end());
                             Span::styled(ts_text, Style::default().fg(timestamp_color))
                         } else {
                             Span::raw(blank_timestamp.clone())
@@ -454,12 +455,13 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         // Collect status information (used in both search and normal mode)
         let focus_info = if let Some(selected_idx) = app.logs_state.selected() {
             if let Some(entry) = app.logs.get(selected_idx) {
-                let date = file_date(&entry.file_path).unwrap_or_else(|| "N/A".to_string());
+                let date = entry_date(entry).unwrap_or_else(|| "N/A".to_string());
                 let time_info = entry
                     .content
                     .lines()
                     .next()
-                    .and_then(|line| split_timestamp_line(line).map(|(prefix, _)| &prefix[1..9]))
+                    .and_then(|line| split_timestamp_line(line).map(|(prefix, _)| prefix))
+                    .map(|prefix| prefix.trim().trim_start_matches('[').trim_end_matches(']'))
                     .unwrap_or("--:--:--");
                 format!("📅 {} {}", date, time_info)
             } else {
@@ -517,9 +519,15 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                     "🟢"
                 };
 
+                let icon = if app.pomodoro_phase.is_break() {
+                    "☕"
+                } else {
+                    "🍅"
+                };
+
                 format!(
-                    " [{} 🍅 {:02}:{:02} {}{}]",
-                    urgency, mins, secs, progress_bar, target
+                    " [{} {} {:02}:{:02} {}{}]",
+                    urgency, icon, mins, secs, progress_bar, target
                 )
             } else {
                 String::new()
@@ -540,6 +548,11 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 && !app.logs.is_empty()
             {
                 parts.push(format!("Sel {}/{}", selected + 1, app.logs.len()));
+                if let Some(snippet) = app.search_snippets.get(selected)
+                    && !snippet.text.trim().is_empty()
+                {
+                    parts.push(format!("…{}…", snippet.text.trim()));
+                }
             }
             parts.push(stats_summary.clone());
             parts.join(" · ")
@@ -652,13 +665,16 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             .iter()
             .map(|task| {
                 let mut line = String::new();
-                line.push_str(&"  ".repeat(task.indent));
-                if task.is_done {
-                    line.push_str("- [x] ");
-                } else {
-                    line.push_str("- [ ] ");
+                if app.is_task_marked(&task.file_path, task.line_number) {
+                    line.push_str(MARK_PREFIX);
                 }
+                line.push_str(&"  ".repeat(task.indent));
+                line.push_str(task.status.checkbox());
                 line.push_str(&task.text);
+                if let Some(stamp) = done_stamp_label(&task.schedule, Local::now().date_naive()) {
+                    line.push(' ');
+                    line.push_str(&stamp);
+                }
 
                 let is_active_pomodoro = if let (
                     Some(end_time),
@@ -713,7 +729,10 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                     false
                 };
 
-                if task.tomato_count > 0 && !is_active_pomodoro {
+                if let Some(estimate) = task.schedule.estimate {
+                    let spent = app.time_spent.minutes(&task.task_instance(), task.tomato_count);
+                    line.push_str(&format!(" {}", estimate.badge(task.tomato_count, spent)));
+                } else if task.tomato_count > 0 && !is_active_pomodoro {
                     if task.tomato_count <= 3 {
                         line.push(' ');
                         line.push_str(&"🍅".repeat(task.tomato_count));
@@ -748,7 +767,10 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             open_count, done_count, app.today_tomatoes
         );
         let filter_label = app.task_filter_label();
-        let filter_summary = format!("{filter_label}: {}", app.tasks.len());
+        let mut filter_summary = format!("{filter_label}: {}", app.tasks.len());
+        if !app.marked_tasks.is_empty() {
+            filter_summary.push_str(&format!(" · {} marked", app.marked_tasks.len()));
+        }
         let tasks_title_text = format!("TASKS ({filter_summary}) — {tasks_summary}");
         let tasks_title = truncate(
             &tasks_title_text,
@@ -1042,6 +1064,10 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     render_status_bar(f, status_area, app, &tokens);
 
     // Render popups (order matters: later ones appear on top)
+    if app.show_focus_mode {
+        render_focus_screen(f, app);
+    }
+
     if app.show_activity_popup {
         render_activity_popup(f, app);
     }
@@ -1085,6 +1111,14 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         render_pomodoro_popup(f, app);
     }
 
+    if app.show_defer_popup {
+        render_defer_popup(f, app);
+    }
+
+    if app.show_bulk_popup {
+        render_bulk_popup(f, app);
+    }
+
     if app.pomodoro_alert_expiry.is_some() {
         render_siren_popup(f, app);
     }
@@ -1093,6 +1127,10 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         render_path_popup(f, app);
     }
 
+    if app.show_backup_popup {
+        render_backup_popup(f, app);
+    }
+
     if app.show_memo_preview_popup {
         render_memo_preview_popup(f, app);
     }
@@ -1908,6 +1946,9 @@ fn bullet_for_level(level: usize) -> char {
     }
 }
 
+/// Shown before tasks marked for a bulk action.
+const MARK_PREFIX: &str = "● ";
+
 fn render_agenda_panel(
     f: &mut Frame,
     app: &App,
@@ -1983,27 +2024,31 @@ fn render_agenda_panel(
     items.push(ListItem::new(Line::from("")));
     ui_index += 1;
 
-    if visible.is_empty() {
+    let sessions = pomodoro_session_blocks(app, app.agenda_selected_day);
+    if visible.is_empty() && sessions.is_empty() {
         items.push(ListItem::new(Line::from(Span::styled(
             "No agenda items.",
             Style::default().fg(tokens.ui_muted),
         ))));
     } else {
+        let day = app.agenda_selected_day;
+        let warning_days = app.config.ui.deadline_warning_days;
         let mut overdue = Vec::new();
+        let mut upcoming = Vec::new();
         let mut all_day = Vec::new();
         let mut timed = Vec::new();
         let mut unscheduled_items = Vec::new();
 
         for idx in visible {
             let item = &app.agenda_items[idx];
-            let is_overdue = item.kind == AgendaItemKind::Task
-                && item.schedule.due.is_some()
-                && item.schedule.due.unwrap_or(app.agenda_selected_day) < app.agenda_selected_day
-                && !item.is_done;
-            if is_overdue {
+            if item.is_overdue_on(day) {
                 overdue.push(idx);
                 continue;
             }
+            if item.date != day && item.is_deadline_upcoming(day, warning_days) {
+                upcoming.push(idx);
+                continue;
+            }
             if item.kind == AgendaItemKind::Task && item.schedule.is_empty() {
                 unscheduled_items.push(idx);
                 continue;
@@ -2029,6 +2074,17 @@ fn render_agenda_panel(
             list_width,
             tokens,
         );
+        push_agenda_section(
+            &mut items,
+            &mut ui_index,
+            "UPCOMING DEADLINES",
+            &upcoming,
+            selected,
+            &mut ui_selected_index,
+            app,
+            list_width,
+            tokens,
+        );
         push_agenda_section(
             &mut items,
             &mut ui_index,
@@ -2050,24 +2106,23 @@ fn render_agenda_panel(
         ui_index += 1;
 
         let slot_minutes: i32 = 30;
-        let window_start_min: i32 = 6 * 60;
-        let window_end_min: i32 = 22 * 60;
-        let row_count =
-            ((window_end_min - window_start_min) / slot_minutes).max(0) as usize + 1;
-
         let mut blocks = build_agenda_blocks(&timed, app, app.agenda_selected_day);
+        blocks.extend(sessions);
         blocks.sort_by_key(|block| (block.start_min, block.end_min, block.idx));
 
+        let (window_start_min, window_end_min) = agenda_grid_window(&blocks, slot_minutes);
+        let row_count =
+            ((window_end_min - window_start_min) / slot_minutes).max(0) as usize + 1;
+
         let mut row_blocks: Vec<Vec<usize>> = vec![Vec::new(); row_count];
-        let mut block_start_row: std::collections::HashMap<usize, usize> =
-            std::collections::HashMap::new();
+        let mut block_start_row: Vec<usize> = Vec::with_capacity(blocks.len());
 
         for (block_idx, block) in blocks.iter().enumerate() {
             let start_row = ((block.start_min - window_start_min).max(0) / slot_minutes) as usize;
             let end_row = ((block.end_min - window_start_min + slot_minutes - 1).max(0)
                 / slot_minutes) as usize;
             let end_row = end_row.max(start_row + 1);
-            block_start_row.insert(block.idx, start_row);
+            block_start_row.push(start_row);
 
             for row in start_row..end_row {
                 if row < row_count {
@@ -2098,15 +2153,13 @@ fn render_agenda_panel(
             {
                 let starting_blocks: Vec<usize> = block_indices
                     .iter()
-                    .filter(|block_idx| {
-                        block_start_row.get(&blocks[**block_idx].idx) == Some(&row)
-                    })
+                    .filter(|block_idx| block_start_row[**block_idx] == row)
                     .copied()
                     .collect();
                 let selected_block = selected.and_then(|selected_idx| {
                     starting_blocks
                         .iter()
-                        .find(|block_idx| blocks[**block_idx].idx == selected_idx)
+                        .find(|block_idx| blocks[**block_idx].idx == Some(selected_idx))
                         .copied()
                 });
                 let display_idx = selected_block
@@ -2119,9 +2172,9 @@ fn render_agenda_panel(
                 } else {
                     String::new()
                 };
-                if block_start_row.get(&block.idx) == Some(&row) {
+                if block_start_row[display_idx] == row {
                     content = format!("{prefix} {}{}", block.label, extra);
-                    if selected == Some(block.idx) {
+                    if block.idx.is_some() && selected == block.idx {
                         ui_selected_index = Some(ui_index);
                     }
                 } else {
@@ -2133,7 +2186,17 @@ fn render_agenda_panel(
 
             let content = truncate(&content, content_width);
             let line = format!("{time_label}{separator}{content}");
-            items.push(ListItem::new(Line::from(line)));
+            let style = row_blocks
+                .get(row)
+                .and_then(|block_indices| block_indices.first())
+                .map(|block_idx| match blocks[*block_idx].idx {
+                    Some(idx) => {
+                        deadline_style(&app.agenda_items[idx], day, warning_days, tokens)
+                    }
+                    None => Style::default().fg(tokens.ui_muted),
+                })
+                .unwrap_or_default();
+            items.push(ListItem::new(Line::from(line).style(style)));
             ui_index += 1;
         }
 
@@ -2172,7 +2235,8 @@ fn render_agenda_panel(
 }
 
 struct AgendaBlock {
-    idx: usize,
+    /// Agenda item the block belongs to; `None` for recorded pomodoro sessions.
+    idx: Option<usize>,
     start_min: i32,
     end_min: i32,
     label: String,
@@ -2194,9 +2258,14 @@ fn build_agenda_blocks(
             duration = 30;
         }
         let end_min = (start_min + duration).min(24 * 60);
+        let mark = if app.is_task_marked(&item.file_path, item.line_number) {
+            MARK_PREFIX
+        } else {
+            ""
+        };
         let label = format!(
-            "{} {}-{}",
-            agenda_item_label(item, day),
+            "{mark}{} {}-{}",
+            agenda_item_label(item, day, &app.time_spent),
             format_time(time),
             format_time_minutes(end_min)
         );
@@ -2205,7 +2274,7 @@ fn build_agenda_blocks(
             AgendaItemKind::Note => "....",
         };
         blocks.push(AgendaBlock {
-            idx: *idx,
+            idx: Some(*idx),
             start_min,
             end_min,
             label,
@@ -2215,6 +2284,61 @@ fn build_agenda_blocks(
     blocks
 }
 
+/// First and last row start of the agenda grid: 06:00-22:00, widened to fit early or late blocks.
+fn agenda_grid_window(blocks: &[AgendaBlock], slot_minutes: i32) -> (i32, i32) {
+    let floor_slot = |min: i32| min.div_euclid(slot_minutes) * slot_minutes;
+    let start = blocks
+        .iter()
+        .map(|block| floor_slot(block.start_min))
+        .fold(6 * 60, i32::min)
+        .max(0);
+    let end = blocks
+        .iter()
+        .map(|block| floor_slot((block.end_min - 1).max(block.start_min)))
+        .fold(22 * 60, i32::max)
+        .min(24 * 60 - slot_minutes);
+    (start, end)
+}
+
+/// Pomodoro sessions recorded on `day`, as `🍅 task 09:00-09:25` blocks.
+fn pomodoro_session_blocks(app: &App, day: chrono::NaiveDate) -> Vec<AgendaBlock> {
+    session_blocks(&app.pomodoro_sessions, day)
+}
+
+fn session_blocks(sessions: &[crate::storage::PomodoroSession], day: chrono::NaiveDate) -> Vec<AgendaBlock> {
+    sessions
+        .iter()
+        .filter(|session| session.start.date_naive() == day)
+        .map(|session| {
+            let start = session.start.time();
+            let start_min = start.hour() as i32 * 60 + start.minute() as i32;
+            let end_min = if session.end.date_naive() == day {
+                session.end.hour() as i32 * 60 + session.end.minute() as i32
+            } else {
+                24 * 60
+            };
+            let interrupted = if session.interrupted {
+                format!(" (stopped after {}m)", session.actual_minutes)
+            } else {
+                String::new()
+            };
+            let label = format!(
+                "🍅 {} {}-{}{interrupted}",
+                session.task,
+                format_time(start),
+                format_time_minutes(end_min)
+            );
+            AgendaBlock {
+                idx: None,
+                start_min,
+                end_min: end_min.max(start_min + 1),
+                label,
+                prefix: "::::",
+            }
+        })
+        .collect()
+}
+
 fn push_agenda_section(
     items: &mut Vec<ListItem>,
     ui_index: &mut usize,
@@ -2242,7 +2366,16 @@ fn push_agenda_section(
         if selected == Some(*idx) {
             *ui_selected_index = Some(*ui_index);
         }
-        let line = agenda_item_label(&app.agenda_items[*idx], app.agenda_selected_day);
+        let item = &app.agenda_items[*idx];
+        let day = app.agenda_selected_day;
+        let mut line = agenda_item_label(item, day, &app.time_spent);
+        if app.is_task_marked(&item.file_path, item.line_number) {
+            line.insert_str(0, MARK_PREFIX);
+        }
+        if let Some(hint) = deadline_hint(item, day) {
+            line.push_str(&format!(" ({hint})"));
+        }
+        let style = deadline_style(item, day, app.config.ui.deadline_warning_days, tokens);
         let wrapped = wrap_markdown_line(&line, list_width);
         let lines: Vec<Line<'static>> = wrapped
             .iter()
@@ -2254,6 +2387,7 @@ fn push_agenda_section(
                     None,
                     Style::default(),
                 ))
+                .style(style)
             })
             .collect();
         items.push(ListItem::new(Text::from(lines)));
@@ -2264,17 +2398,17 @@ fn push_agenda_section(
     *ui_index += 1;
 }
 
-fn agenda_item_label(item: &crate::models::AgendaItem, day: chrono::NaiveDate) -> String {
+fn agenda_item_label(
+    item: &crate::models::AgendaItem,
+    day: chrono::NaiveDate,
+    time_spent: &crate::models::TimeSpent,
+) -> String {
     let mut line = String::new();
     line.push_str(&"  ".repeat(item.indent));
 
     match item.kind {
         AgendaItemKind::Task => {
-            if item.is_done {
-                line.push_str("- [x] ");
-            } else {
-                line.push_str("- [ ] ");
-            }
+            line.push_str(item.status.checkbox());
         }
         AgendaItemKind::Note => {
             line.push_str("• ");
@@ -2290,9 +2424,70 @@ fn agenda_item_label(item: &crate::models::AgendaItem, day: chrono::NaiveDate) -
     if let Some(minutes) = item.duration_minutes {
         line.push_str(&format!(" ({})", format_duration(minutes)));
     }
+    if let Some(estimate) = item.schedule.estimate {
+        let identity = crate::storage::task_identity(&item.text);
+        let spent = time_spent.minutes(&item.task_instance(&identity), item.tomato_count);
+        line.push_str(&format!(" {}", estimate.badge(item.tomato_count, spent)));
+    }
+    if let Some(stamp) = done_stamp_label(&item.schedule, day) {
+        line.push(' ');
+        line.push_str(&stamp);
+    }
     line
 }
 
+/// `✓ 14:05` for tasks completed on `day`, `✓ 01-12` (plus time, if recorded) otherwise.
+fn done_stamp_label(
+    schedule: &crate::models::TaskSchedule,
+    day: chrono::NaiveDate,
+) -> Option<String> {
+    let done = schedule.done?;
+    let label = match schedule.done_time {
+        Some(time) if done == day => format_time(time),
+        Some(time) => format!("{} {}", done.format("%m-%d"), format_time(time)),
+        None => done.format("%m-%d").to_string(),
+    };
+    Some(format!("✓ {label}"))
+}
+
+/// Colour of a task by how close its deadline is: upcoming, due on `day`, or overdue.
+fn deadline_style(
+    item: &crate::models::AgendaItem,
+    day: chrono::NaiveDate,
+    warning_days: u32,
+    tokens: &theme::ThemeTokens,
+) -> Style {
+    if item.is_overdue_on(day) {
+        Style::default()
+            .fg(tokens.deadline_overdue)
+            .add_modifier(Modifier::BOLD)
+    } else if item.schedule.due == Some(day)
+        && item.kind == AgendaItemKind::Task
+        && !item.status.is_closed()
+    {
+        Style::default()
+            .fg(tokens.deadline_today)
+            .add_modifier(Modifier::BOLD)
+    } else if item.is_deadline_upcoming(day, warning_days) {
+        Style::default().fg(tokens.deadline_upcoming)
+    } else {
+        Style::default()
+    }
+}
+
+/// `3d overdue` / `due in 2d` for open tasks whose deadline is not `day`.
+fn deadline_hint(item: &crate::models::AgendaItem, day: chrono::NaiveDate) -> Option<String> {
+    if item.kind != AgendaItemKind::Task || item.status.is_closed() {
+        return None;
+    }
+    let days = (item.schedule.due? - day).num_days();
+    match days {
+        0 => None,
+        days if days < 0 => Some(format!("{}d overdue", -days)),
+        days => Some(format!("due in {days}d")),
+    }
+}
+
 fn agenda_badges(item: &crate::models::AgendaItem, day: chrono::NaiveDate) -> String {
     let mut badges = Vec::new();
     if item.schedule.scheduled.is_some() {
@@ -2304,11 +2499,7 @@ fn agenda_badges(item: &crate::models::AgendaItem, day: chrono::NaiveDate) -> St
     if item.time.is_some() {
         badges.push("[T]");
     }
-    if item.kind == AgendaItemKind::Task
-        && item.schedule.due.is_some()
-        && item.schedule.due.unwrap_or(day) < day
-        && !item.is_done
-    {
+    if item.is_overdue_on(day) {
         badges.push("[O]");
     }
     badges.join("")
@@ -2392,6 +2583,21 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, tokens: &theme::Theme
         ));
     }
 
+    if let Some(pomodoro) = pomodoro_status(app) {
+        if !right_plain.is_empty() {
+            right_plain.push_str("  ");
+            right_spans.push(Span::raw("  "));
+        }
+        let color = if app.pomodoro_phase.is_break() {
+            tokens.ui_toast_success
+        } else {
+            tokens.ui_accent
+        };
+        right_plain.push_str(&pomodoro);
+        right_spans.push(Span::styled(pomodoro, Style::default().fg(color)));
+    }
+
+    let quick_add_hint = quick_add_hint(app);
     let status_message = if let Some(hint) = app.visual_hint_message.as_deref() {
         if hint.is_empty() {
             None
@@ -2403,7 +2609,9 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, tokens: &theme::Theme
     {
         Some((toast, tokens.ui_toast_info))
     } else {
-        None
+        quick_add_hint
+            .as_deref()
+            .map(|hint| (hint, tokens.ui_accent))
     };
 
     if let Some((message, color)) = status_message {
@@ -2445,6 +2653,48 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App, tokens: &theme::Theme
     f.render_widget(right, status_chunks[1]);
 }
 
+/// Previews what quick add will make of the line under the cursor when the entry is saved.
+/// Phase, time left and today's session count while a pomodoro or break is running.
+fn pomodoro_status(app: &App) -> Option<String> {
+    let remaining = app.pomodoro_end? - Local::now();
+    if remaining.num_seconds() < 0 {
+        return None;
+    }
+    let (icon, session) = if app.pomodoro_phase.is_break() {
+        ("☕", app.pomodoro_sessions_today)
+    } else {
+        ("🍅", app.pomodoro_sessions_today + 1)
+    };
+    Some(format!(
+        "{icon} {} {:02}:{:02} · #{session}",
+        app.pomodoro_phase.label(),
+        remaining.num_minutes(),
+        remaining.num_seconds() % 60
+    ))
+}
+
+fn quick_add_hint(app: &App) -> Option<String> {
+    if app.input_mode != InputMode::Editing {
+        return None;
+    }
+    let toggle = app
+        .config
+        .keybindings
+        .composer
+        .quick_add_toggle
+        .first()
+        .map(String::as_str)
+        .unwrap_or("-");
+    if app.quick_add_literal {
+        return Some(format!("Quick add off ({toggle})"));
+    }
+    let preview = app.quick_add_preview()?;
+    Some(format!(
+        "Quick add: {} ({toggle}: keep text)",
+        preview.tokens.join(" ")
+    ))
+}
+
 fn status_file_label(app: &App) -> String {
     if app.input_mode == InputMode::Navigate {
         let selected_path = match app.navigate_focus {
@@ -2484,7 +2734,10 @@ fn status_file_label(app: &App) -> String {
         return "Search Results".to_string();
     }
 
-    format!("{}.md", app.active_date)
+    let path = storage::get_file_path_for_date(&app.config.data.log_path, &app.active_date);
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("{}.md", app.active_date))
 }
 
 fn next_scroll_top(prev_top: u16, cursor: u16, len: u16) -> u16 {
@@ -2497,11 +2750,8 @@ fn next_scroll_top(prev_top: u16, cursor: u16, len: u16) -> u16 {
     }
 }
 
-fn file_date(file_path: &str) -> Option<String> {
-    Path::new(file_path)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_string())
+fn entry_date(entry: &LogEntry) -> Option<String> {
+    entry.date.map(|date| date.format("%Y-%m-%d").to_string())
 }
 
 #[cfg(test)]
@@ -2510,6 +2760,7 @@ mod tests {
     use super::compose_wrapped_line;
     use super::collect_code_block_info;
     use super::hide_fence_marker;
+    use super::{agenda_grid_window, session_blocks};
     use crate::config::Theme;
     use crate::ui::theme::ThemeTokens;
 
@@ -2520,6 +2771,67 @@ mod tests {
             .collect::<String>()
     }
 
+    #[test]
+    fn session_blocks_label_interrupted_sessions_and_widen_the_grid() {
+        use chrono::{Local, NaiveDate, TimeZone};
+
+        let at = |h, m| Local.with_ymd_and_hms(2025, 3, 12, h, m, 0).single().expect("time");
+        let session = |start, end, actual, interrupted| crate::storage::PomodoroSession {
+            start,
+            end,
+            task: "Write report".to_string(),
+            task_identity: "write report".to_string(),
+            file_path: "/logs/2025-03-12.md".to_string(),
+            block_id: None,
+            planned_minutes: 25,
+            actual_minutes: actual,
+            interrupted,
+        };
+        let sessions = vec![
+            session(at(9, 0), at(9, 10), 10, true),
+            session(at(22, 40), at(23, 5), 25, false),
+        ];
+        let day = NaiveDate::from_ymd_opt(2025, 3, 12).expect("date");
+
+        let blocks = session_blocks(&sessions, day);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[0].label,
+            "🍅 Write report 09:00-09:10 (stopped after 10m)"
+        );
+        assert_eq!((blocks[0].start_min, blocks[0].end_min), (540, 550));
+        assert_eq!(blocks[1].label, "🍅 Write report 22:40-23:05");
+
+        assert_eq!(agenda_grid_window(&blocks[..1], 30), (6 * 60, 22 * 60));
+        assert_eq!(agenda_grid_window(&blocks, 30), (6 * 60, 23 * 60));
+        let early = session_blocks(&[session(at(5, 10), at(5, 35), 25, false)], day);
+        assert_eq!(agenda_grid_window(&early, 30), (5 * 60, 22 * 60));
+        let next_day = NaiveDate::from_ymd_opt(2025, 3, 13).expect("date");
+        assert!(session_blocks(&sessions, next_day).is_empty());
+    }
+
+    #[test]
+    fn big_digits_draw_centered_clock() {
+        use super::components::BigDigits;
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        assert_eq!(BigDigits::width("25:00"), 34);
+        let area = Rect::new(0, 0, 36, 5);
+        let mut buf = Buffer::empty(area);
+        BigDigits::new("25:00").render(area, &mut buf);
+        let row = |y: u16| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol())
+                .collect::<String>()
+        };
+        assert_eq!(row(0), " ██████  ██████      ██████  ██████ ");
+        assert_eq!(row(1), "     ██  ██      ██  ██  ██  ██  ██ ");
+
+        let mut small = Buffer::empty(Rect::new(0, 0, 10, 5));
+        BigDigits::new("25:00").render(small.area, &mut small);
+        assert_eq!(small, Buffer::empty(Rect::new(0, 0, 10, 5)));
+    }
+
     #[test]
     fn renders_bullets_with_indentation_levels() {
         let tokens = ThemeTokens::from_theme(&Theme::default());
@@ -1,15 +1,18 @@
 use super::components::{
-    centered_rect, markdown_prefix_width, parse_markdown_spans, wrap_markdown_line,
+    BigDigits, centered_rect, markdown_prefix_width, parse_markdown_spans, wrap_markdown_line,
 };
 use crate::app::App;
 use crate::config::{EditorStyle, ThemePreset};
-use crate::models::{DatePickerField, EditorMode, InputMode, Mood, VisualKind};
+use crate::models::{
+    self, BulkAction, DatePickerField, EditorMode, InputMode, Mood, VisualKind,
+};
+use crate::recurrence::{RECURRENCE_PRESETS, Recurrence};
 use crate::ui::color_parser::parse_color;
 use crate::ui::theme::ThemeTokens;
 use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Timelike};
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
@@ -33,6 +36,26 @@ pub fn render_siren_popup(f: &mut Frame, app: &App) {
         .as_deref()
         .unwrap_or("Pomodoro complete.");
 
+    let (advice, hint) = match app.pomodoro_pending_break {
+        Some(phase) => (
+            format!(
+                "Next: {} ({}m). Stretch. Drink water.",
+                phase.label(),
+                app.pomodoro_minutes(phase)
+            ),
+            "(Enter: start break · Esc: skip)".to_string(),
+        ),
+        None if app.pomodoro_phase.is_break() => (
+            format!("{} running. Stretch. Drink water.", app.pomodoro_phase.label()),
+            "(Press Esc to dismiss)".to_string(),
+        ),
+        None => (
+            "Back to work.".to_string(),
+            "(Press Esc to dismiss)".to_string(),
+        ),
+    };
+    let sessions = format!("Pomodoros today: {}", app.pomodoro_sessions_today);
+
     let siren_art = vec![
         "         _______  TIME'S UP!  _______",
         "        /       \\            /       \\",
@@ -41,9 +64,10 @@ pub fn render_siren_popup(f: &mut Frame, app: &App) {
         "",
         message,
         "",
-        "Take a break. Stretch. Drink water.",
+        advice.as_str(),
+        sessions.as_str(),
         "",
-        "(Press Esc to dismiss)",
+        hint.as_str(),
     ];
 
     let text_area = Layout::default()
@@ -76,7 +100,7 @@ pub fn render_activity_popup(f: &mut Frame, app: &App) {
 
     // Header row
     items.push(ListItem::new(Line::from(vec![Span::styled(
-        "Date        Logs  🍅   Activity                    Pomodoros",
+        "Date        Logs  Done  🍅   Activity                    Pomodoros",
         Style::default()
             .fg(Color::DarkGray)
             .add_modifier(Modifier::BOLD),
@@ -86,8 +110,8 @@ pub fn render_activity_popup(f: &mut Frame, app: &App) {
     for i in 0..14 {
         let date = today - chrono::Duration::days(i);
         let date_str = date.format("%Y-%m-%d").to_string();
-        let (line_count, tomato_count) =
-            app.activity_data.get(&date_str).cloned().unwrap_or((0, 0));
+        let (line_count, tomato_count, done_count) =
+            app.activity_data.get(&date_str).cloned().unwrap_or((0, 0, 0));
 
         // Activity bar (based on log count)
         let bar_len = line_count.min(20);
@@ -119,6 +143,11 @@ pub fn render_activity_popup(f: &mut Frame, app: &App) {
                 Style::default().fg(Color::Cyan),
             ),
             Span::raw("  "),
+            Span::styled(
+                format!("{:4}", done_count),
+                Style::default().fg(Color::Green),
+            ),
+            Span::raw("  "),
             Span::styled(
                 format!("{:2}", tomato_count),
                 Style::default().fg(Color::Red),
@@ -531,6 +560,7 @@ fn render_date_picker_fields(
         DatePickerField::Start,
         DatePickerField::Time,
         DatePickerField::Duration,
+        DatePickerField::Recurrence,
     ];
     let selected = fields
         .iter()
@@ -574,6 +604,7 @@ fn render_date_picker_detail(
         }
         DatePickerField::Time => render_date_picker_time(f, app, area, tokens),
         DatePickerField::Duration => render_date_picker_duration(f, app, area, tokens),
+        DatePickerField::Recurrence => render_date_picker_recurrence(f, app, area, tokens),
     }
 }
 
@@ -713,6 +744,44 @@ fn render_date_picker_duration(
     f.render_widget(Paragraph::new(lines), area);
 }
 
+fn render_date_picker_recurrence(
+    f: &mut Frame,
+    app: &App,
+    area: ratatui::layout::Rect,
+    tokens: &ThemeTokens,
+) {
+    let selected = app.date_picker_effective_recurrence();
+    let header = format!("Repeat: every {selected}");
+
+    let mut spans: Vec<Span> = Vec::new();
+    for preset in RECURRENCE_PRESETS {
+        let mut style = Style::default().fg(tokens.ui_fg);
+        if Recurrence::parse(preset).as_ref() == Some(&selected) {
+            style = style
+                .bg(tokens.ui_selection_bg)
+                .add_modifier(Modifier::BOLD);
+        }
+        spans.push(Span::styled(preset, style));
+        spans.push(Span::raw(" "));
+    }
+
+    let next = selected.next_after(Local::now().date_naive());
+    let lines = vec![
+        Line::from(Span::styled(
+            header,
+            Style::default()
+                .fg(tokens.ui_accent)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(spans),
+        Line::from(""),
+        Line::from(format!("Next after today: {}", next.format("%a %Y-%m-%d"))),
+        Line::from("Use left/right for presets, R for mon,thu or month 15."),
+    ];
+    f.render_widget(Paragraph::new(lines), area);
+}
+
 fn date_picker_field_label(field: DatePickerField) -> &'static str {
     match field {
         DatePickerField::Scheduled => "Scheduled",
@@ -720,6 +789,7 @@ fn date_picker_field_label(field: DatePickerField) -> &'static str {
         DatePickerField::Start => "Start",
         DatePickerField::Time => "Time",
         DatePickerField::Duration => "Duration",
+        DatePickerField::Recurrence => "Repeat",
     }
 }
 
@@ -750,6 +820,12 @@ fn date_picker_field_value(app: &App, field: DatePickerField) -> String {
             .duration_minutes
             .map(format_duration)
             .unwrap_or_else(|| "--".to_string()),
+        DatePickerField::Recurrence => app
+            .date_picker_schedule
+            .recurrence
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "--".to_string()),
     }
 }
 
@@ -896,6 +972,52 @@ pub fn render_tag_popup(f: &mut Frame, app: &mut App) {
     f.render_stateful_widget(list, popup_layout[0], &mut app.tag_list_state);
 }
 
+pub fn render_backup_popup(f: &mut Frame, app: &mut App) {
+    let name = app
+        .backups
+        .first()
+        .and_then(|backup| backup.target.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let title = format!(" Backups of {name} · Enter: restore · Esc: close ");
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let area = centered_rect(50, 60, f.area());
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let items: Vec<ListItem> = app
+        .backups
+        .iter()
+        .map(|backup| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    backup.taken_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(" ({} lines)", backup.line_count)),
+            ]))
+        })
+        .collect();
+
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .margin(1)
+        .split(area);
+
+    let highlight_bg = parse_color(&app.config.theme.text_highlight);
+    let list = List::new(items).highlight_symbol("").highlight_style(
+        Style::default()
+            .bg(highlight_bg)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, popup_layout[0], &mut app.backup_list_state);
+
+    let footer = Paragraph::new("The current version is backed up before restoring.")
+        .style(Style::default().fg(Color::DarkGray));
+    f.render_widget(footer, popup_layout[1]);
+}
+
 pub fn render_path_popup(f: &mut Frame, app: &App) {
     let block = Block::default()
         .title(" 📂 Log Directory Path ")
@@ -1156,6 +1278,10 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                 "Date picker".to_string(),
                 fmt_keys(&kb.composer.date_picker),
             ),
+            (
+                "Quick add on/off".to_string(),
+                fmt_keys(&kb.composer.quick_add_toggle),
+            ),
             (
                 "Context: work/personal/clear".to_string(),
                 composer_context_keys,
@@ -1188,6 +1314,10 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                 "Date picker".to_string(),
                 fmt_keys(&kb.composer.date_picker),
             ),
+            (
+                "Quick add on/off".to_string(),
+                fmt_keys(&kb.composer.quick_add_toggle),
+            ),
             (
                 "Context: work/personal/clear".to_string(),
                 composer_context_keys,
@@ -1231,6 +1361,7 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                     " | ",
                 ),
             ),
+            ("Focus mode".to_string(), fmt_keys(&kb.global.focus_mode)),
             ("Focus agenda".to_string(), fmt_keys(&kb.global.agenda)),
             (
                 "Log dir / Config".to_string(),
@@ -1253,6 +1384,7 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                 ),
             ),
             ("Google sync".to_string(), fmt_keys(&kb.global.sync_google)),
+            ("Backups".to_string(), fmt_keys(&kb.global.backups)),
             ("Quit".to_string(), fmt_keys(&kb.global.quit)),
         ]
     } else {
@@ -1263,6 +1395,7 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
             ("Search".to_string(), fmt_keys(&kb.global.search)),
             ("Tags".to_string(), fmt_keys(&kb.global.tags)),
             ("Pomodoro".to_string(), fmt_keys(&kb.global.pomodoro)),
+            ("Focus mode".to_string(), fmt_keys(&kb.global.focus_mode)),
             ("Activity".to_string(), fmt_keys(&kb.global.activity)),
             ("Focus agenda".to_string(), fmt_keys(&kb.global.agenda)),
             ("Log dir".to_string(), fmt_keys(&kb.global.log_dir)),
@@ -1279,6 +1412,7 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                 "Google sync (experimental)".to_string(),
                 fmt_keys(&kb.global.sync_google),
             ),
+            ("Restore backup".to_string(), fmt_keys(&kb.global.backups)),
             ("Quit".to_string(), fmt_keys(&kb.global.quit)),
         ]
     };
@@ -1398,7 +1532,32 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                     " | ",
                 ),
             ),
-            ("Edit".to_string(), fmt_keys(&kb.tasks.edit)),
+            (
+                "Status / Edit".to_string(),
+                join_key_groups_with_sep(
+                    &[fmt_keys(&kb.tasks.status_cycle), fmt_keys(&kb.tasks.edit)],
+                    " | ",
+                ),
+            ),
+            (
+                "Defer day/week/Mon/to".to_string(),
+                join_key_groups_with_sep(
+                    &[
+                        fmt_keys(&kb.tasks.defer_day),
+                        fmt_keys(&kb.tasks.defer_week),
+                        fmt_keys(&kb.tasks.defer_monday),
+                        fmt_keys(&kb.tasks.defer_to),
+                    ],
+                    " | ",
+                ),
+            ),
+            (
+                "Mark / Bulk actions".to_string(),
+                join_key_groups_with_sep(
+                    &[fmt_keys(&kb.tasks.mark), fmt_keys(&kb.tasks.bulk)],
+                    " | ",
+                ),
+            ),
             (
                 "Filter cycle / set".to_string(),
                 join_key_groups_with_sep(
@@ -1417,6 +1576,13 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                 "Priority cycle".to_string(),
                 fmt_keys(&kb.tasks.priority_cycle),
             ),
+            ("Status cycle".to_string(), fmt_keys(&kb.tasks.status_cycle)),
+            ("Defer +1 day".to_string(), fmt_keys(&kb.tasks.defer_day)),
+            ("Defer +1 week".to_string(), fmt_keys(&kb.tasks.defer_week)),
+            ("Defer to Monday".to_string(), fmt_keys(&kb.tasks.defer_monday)),
+            ("Defer to date".to_string(), fmt_keys(&kb.tasks.defer_to)),
+            ("Mark for bulk".to_string(), fmt_keys(&kb.tasks.mark)),
+            ("Bulk actions".to_string(), fmt_keys(&kb.tasks.bulk)),
             ("Pomodoro".to_string(), fmt_keys(&kb.tasks.start_pomodoro)),
             ("Edit".to_string(), fmt_keys(&kb.tasks.edit)),
             (
@@ -1446,7 +1612,32 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
                     " | ",
                 ),
             ),
-            ("Filter".to_string(), fmt_keys(&kb.agenda.filter)),
+            (
+                "Status / Filter".to_string(),
+                join_key_groups_with_sep(
+                    &[fmt_keys(&kb.agenda.status_cycle), fmt_keys(&kb.agenda.filter)],
+                    " | ",
+                ),
+            ),
+            (
+                "Defer day/week/Mon/to".to_string(),
+                join_key_groups_with_sep(
+                    &[
+                        fmt_keys(&kb.agenda.defer_day),
+                        fmt_keys(&kb.agenda.defer_week),
+                        fmt_keys(&kb.agenda.defer_monday),
+                        fmt_keys(&kb.agenda.defer_to),
+                    ],
+                    " | ",
+                ),
+            ),
+            (
+                "Mark / Bulk actions".to_string(),
+                join_key_groups_with_sep(
+                    &[fmt_keys(&kb.agenda.mark), fmt_keys(&kb.agenda.bulk)],
+                    " | ",
+                ),
+            ),
             (
                 "Prev/Next day".to_string(),
                 join_key_groups_with_sep(
@@ -1481,6 +1672,13 @@ fn help_sections(app: &App, compact: bool) -> Vec<HelpSection> {
             ("Down".to_string(), fmt_keys(&kb.agenda.down)),
             ("Open memo".to_string(), fmt_keys(&kb.agenda.open)),
             ("Toggle task".to_string(), fmt_keys(&kb.agenda.toggle)),
+            ("Status cycle".to_string(), fmt_keys(&kb.agenda.status_cycle)),
+            ("Defer +1 day".to_string(), fmt_keys(&kb.agenda.defer_day)),
+            ("Defer +1 week".to_string(), fmt_keys(&kb.agenda.defer_week)),
+            ("Defer to Monday".to_string(), fmt_keys(&kb.agenda.defer_monday)),
+            ("Defer to date".to_string(), fmt_keys(&kb.agenda.defer_to)),
+            ("Mark for bulk".to_string(), fmt_keys(&kb.agenda.mark)),
+            ("Bulk actions".to_string(), fmt_keys(&kb.agenda.bulk)),
             ("Filter cycle".to_string(), fmt_keys(&kb.agenda.filter)),
             ("Prev day".to_string(), fmt_keys(&kb.agenda.prev_day)),
             ("Next day".to_string(), fmt_keys(&kb.agenda.next_day)),
@@ -1902,6 +2100,206 @@ pub fn render_pomodoro_popup(f: &mut Frame, app: &App) {
     f.render_widget(Paragraph::new(body), inner);
 }
 
+/// Full-screen view of the running timer: big countdown, the task and its entry, today's
+/// tomatoes and a capture line for interruptions. Leaves the status bar visible.
+pub fn render_focus_screen(f: &mut Frame, app: &App) {
+    let tokens = ThemeTokens::from_theme(&app.config.theme);
+    let full = f.area();
+    let area = Rect {
+        height: full.height.saturating_sub(1),
+        ..full
+    };
+    let phase = app.pomodoro_phase;
+    let accent = if phase.is_break() {
+        tokens.ui_toast_success
+    } else {
+        tokens.ui_accent
+    };
+    let block = Block::default()
+        .title(" Focus ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent));
+    f.render_widget(Clear, area);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let now = Local::now();
+    let remaining = app
+        .pomodoro_end
+        .filter(|end| *end > now)
+        .map(|end| end - now);
+    let clock = match remaining {
+        Some(left) => format!("{:02}:{:02}", left.num_minutes(), left.num_seconds() % 60),
+        None => "00:00".to_string(),
+    };
+    let heading = match remaining {
+        Some(_) if phase.is_break() => format!("☕ {}", phase.label()),
+        Some(_) => format!(
+            "🍅 {} · session #{}",
+            phase.label(),
+            app.pomodoro_sessions_today + 1
+        ),
+        None => "No timer running".to_string(),
+    };
+
+    let task = match app.pomodoro_target.as_ref() {
+        Some(models::PomodoroTarget::Task { text, .. }) => text.clone(),
+        None if phase.is_break() => "Step away from the screen.".to_string(),
+        None => String::new(),
+    };
+    let entry = app
+        .focus_entry
+        .as_ref()
+        .map(|entry| focus_entry_summary(entry, app))
+        .unwrap_or_default();
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(2),
+            Constraint::Length(BigDigits::HEIGHT),
+            Constraint::Length(1),
+            Constraint::Length(4),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .margin(1)
+        .split(inner);
+
+    let centered = |text: String, style: Style| {
+        Paragraph::new(Line::from(Span::styled(text, style))).alignment(Alignment::Center)
+    };
+    f.render_widget(
+        centered(heading, Style::default().fg(accent).add_modifier(Modifier::BOLD)),
+        rows[1],
+    );
+    if rows[2].width >= BigDigits::width(&clock) {
+        f.render_widget(
+            BigDigits::new(&clock).style(Style::default().fg(accent)),
+            rows[2],
+        );
+    } else {
+        f.render_widget(
+            centered(clock, Style::default().fg(accent).add_modifier(Modifier::BOLD)),
+            rows[2],
+        );
+    }
+
+    let details = vec![
+        Line::from(Span::styled(
+            task,
+            Style::default()
+                .fg(tokens.ui_fg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(entry, Style::default().fg(tokens.ui_muted))),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("Today: 🍅 {}", app.today_tomatoes),
+            Style::default().fg(tokens.ui_fg),
+        )),
+    ];
+    f.render_widget(
+        Paragraph::new(details)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        rows[4],
+    );
+
+    let capture = vec![
+        Line::from(vec![
+            Span::styled("Interruption: ", Style::default().fg(tokens.ui_muted)),
+            Span::styled(
+                format!("{}_", app.focus_input),
+                Style::default()
+                    .fg(tokens.ui_accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter: log as #interrupt note · Esc: back (timer keeps running)",
+            Style::default().fg(tokens.ui_muted),
+        )),
+    ];
+    f.render_widget(Paragraph::new(capture), rows[6]);
+}
+
+/// `[09:00:00] Planning notes`: the entry's heading and its first line other than the task.
+fn focus_entry_summary(entry: &models::LogEntry, app: &App) -> String {
+    let task_line = app.pomodoro_target.as_ref().map(|target| match target {
+        models::PomodoroTarget::Task { line_number, .. } => *line_number,
+    });
+    let mut lines = entry.content.lines().enumerate();
+    let heading = lines
+        .next()
+        .map(|(_, line)| line.trim_start_matches('#').trim().to_string())
+        .unwrap_or_default();
+    let body = lines
+        .find(|(idx, line)| {
+            Some(entry.line_number + idx) != task_line && !line.trim().is_empty()
+        })
+        .map(|(_, line)| line.trim().to_string());
+    match body {
+        Some(body) => format!("in {heading} {body}"),
+        None => format!("in {heading}"),
+    }
+}
+
+pub fn render_defer_popup(f: &mut Frame, app: &App) {
+    let block = Block::default()
+        .title(" Defer Task ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Yellow));
+    let area = centered_rect(60, 30, f.area());
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let (task, current) = app
+        .defer_target
+        .as_ref()
+        .map(|t| (t.text.as_str(), t.date.format("%a %Y-%m-%d").to_string()))
+        .unwrap_or(("<no task selected>", String::new()));
+
+    let body = vec![
+        Line::from(vec![
+            Span::styled("Task: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                task.to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Currently: ", Style::default().fg(Color::DarkGray)),
+            Span::raw(current),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Move to: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                app.defer_input.clone(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "tomorrow, fri, next mon, +3d, +2w, 2025-02-01 (from today)",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::raw("Enter: defer  Esc: cancel  Backspace: edit")),
+    ];
+
+    let inner = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(100)])
+        .margin(2)
+        .split(area)[0];
+    f.render_widget(Paragraph::new(body), inner);
+}
+
 pub fn render_theme_switcher_popup(f: &mut Frame, app: &mut App) {
     let tokens = ThemeTokens::from_theme(&app.config.theme);
     let block = Block::default()
@@ -1988,6 +2386,72 @@ pub fn render_editor_style_popup(f: &mut Frame, app: &mut App) {
     f.render_widget(help, popup_layout[1]);
 }
 
+pub fn render_bulk_popup(f: &mut Frame, app: &mut App) {
+    let tokens = ThemeTokens::from_theme(&app.config.theme);
+    let count = app.bulk_targets.len();
+    let title = if count == 1 {
+        " Bulk: 1 task ".to_string()
+    } else {
+        format!(" Bulk: {count} tasks ")
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(tokens.ui_border_default));
+    let area = centered_rect(50, 40, f.area());
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .margin(1)
+        .split(area);
+
+    let items: Vec<ListItem> = BulkAction::ALL
+        .iter()
+        .map(|action| {
+            let style = if *action == BulkAction::Delete {
+                Style::default().fg(tokens.ui_toast_error)
+            } else {
+                Style::default().fg(tokens.ui_fg)
+            };
+            ListItem::new(Line::from(Span::styled(action.label(), style)))
+        })
+        .collect();
+
+    let highlight_style = Style::default()
+        .bg(tokens.ui_selection_bg)
+        .add_modifier(Modifier::BOLD);
+    let list = List::new(items)
+        .highlight_symbol("> ")
+        .highlight_style(highlight_style);
+    f.render_stateful_widget(list, popup_layout[0], &mut app.bulk_list_state);
+
+    let accent = Style::default()
+        .fg(tokens.ui_accent)
+        .add_modifier(Modifier::BOLD);
+    let help = match app.bulk_prompt {
+        Some(BulkAction::Delete) => Line::from(vec![
+            Span::styled(format!("Delete {count} task(s)? "), accent),
+            Span::styled("(y) Yes  (n/Esc) No", Style::default().fg(tokens.ui_muted)),
+        ]),
+        Some(BulkAction::Reschedule) => Line::from(vec![
+            Span::styled("Move to: ", Style::default().fg(tokens.ui_muted)),
+            Span::styled(app.bulk_input.clone(), accent),
+        ]),
+        Some(_) => Line::from(vec![
+            Span::styled("Tag: #", Style::default().fg(tokens.ui_muted)),
+            Span::styled(app.bulk_input.clone(), accent),
+        ]),
+        None => Line::from(Span::styled(
+            "(Up/Down) Move  (Enter) Apply  (Esc) Cancel",
+            Style::default().fg(tokens.ui_muted),
+        )),
+    };
+    f.render_widget(Paragraph::new(help), popup_layout[1]);
+}
+
 fn fmt_keys(keys: &[String]) -> String {
     if keys.is_empty() {
         return "-".to_string();
@@ -24,6 +24,9 @@ pub struct ThemeTokens {
     pub ui_toast_info: Color,
     pub ui_toast_success: Color,
     pub ui_toast_error: Color,
+    pub deadline_upcoming: Color,
+    pub deadline_today: Color,
+    pub deadline_overdue: Color,
 }
 
 impl ThemeTokens {
@@ -107,6 +110,23 @@ impl ThemeTokens {
                 .unwrap_or(theme.todo_wip.as_str()),
         );
 
+        let deadline = theme.ui.as_ref().and_then(|ui| ui.deadline.as_ref());
+        let deadline_upcoming = parse_color(
+            deadline
+                .and_then(|deadline| deadline.upcoming.as_deref())
+                .unwrap_or(theme.border_todo_header.as_str()),
+        );
+        let deadline_today = parse_color(
+            deadline
+                .and_then(|deadline| deadline.today.as_deref())
+                .unwrap_or("LightMagenta"),
+        );
+        let deadline_overdue = parse_color(
+            deadline
+                .and_then(|deadline| deadline.overdue.as_deref())
+                .unwrap_or("Red"),
+        );
+
         Self {
             ui_border_default,
             ui_border_editing,
@@ -127,6 +147,9 @@ impl ThemeTokens {
             ui_toast_info,
             ui_toast_success,
             ui_toast_error,
+            deadline_upcoming,
+            deadline_today,
+            deadline_overdue,
         }
     }
 }
@@ -134,7 +157,7 @@ impl ThemeTokens {
 #[cfg(test)]
 mod tests {
     use super::ThemeTokens;
-    use crate::config::{Theme, ThemeToastOverrides, ThemeUiOverrides};
+    use crate::config::{Theme, ThemeDeadlineOverrides, ThemeToastOverrides, ThemeUiOverrides};
     use ratatui::style::Color;
 
     #[test]
@@ -164,6 +187,11 @@ mod tests {
         assert_eq!(tokens.content_tag, Color::Cyan);
         assert_eq!(tokens.content_mood, Color::Magenta);
         assert_eq!(tokens.content_timestamp, Color::LightCyan);
+        assert_eq!(tokens.deadline_upcoming, Color::Yellow);
+        assert_eq!(tokens.deadline_today, Color::LightMagenta);
+        assert_eq!(tokens.deadline_overdue, Color::Red);
+        assert_ne!(tokens.deadline_today, tokens.deadline_overdue);
+        assert_ne!(tokens.deadline_today, tokens.deadline_upcoming);
     }
 
     #[test]
@@ -181,6 +209,11 @@ mod tests {
                     success: Some("LightGreen".to_string()),
                     error: Some("LightRed".to_string()),
                 }),
+                deadline: Some(ThemeDeadlineOverrides {
+                    upcoming: Some("Yellow".to_string()),
+                    today: Some("208,135,0".to_string()),
+                    overdue: None,
+                }),
             }),
             ..Default::default()
         };
@@ -195,5 +228,8 @@ mod tests {
         assert_eq!(tokens.ui_toast_info, Color::Magenta);
         assert_eq!(tokens.ui_toast_success, Color::LightGreen);
         assert_eq!(tokens.ui_toast_error, Color::LightRed);
+        assert_eq!(tokens.deadline_upcoming, Color::Yellow);
+        assert_eq!(tokens.deadline_today, Color::Rgb(208, 135, 0));
+        assert_eq!(tokens.deadline_overdue, Color::Red);
     }
 }